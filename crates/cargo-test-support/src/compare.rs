@@ -278,6 +278,7 @@ static E2E_LITERAL_REDACTIONS: &[(&str, &str)] = &[
     ("[RUNNING]", "     Running"),
     ("[COMPILING]", "   Compiling"),
     ("[CHECKING]", "    Checking"),
+    ("[COMPARING]", "   Comparing"),
     ("[COMPLETED]", "   Completed"),
     ("[CREATED]", "     Created"),
     ("[CREATING]", "    Creating"),
@@ -302,10 +303,13 @@ static E2E_LITERAL_REDACTIONS: &[(&str, &str)] = &[
     ("[DOCTEST]", "   Doc-tests"),
     ("[PACKAGING]", "   Packaging"),
     ("[PACKAGED]", "    Packaged"),
+    ("[REPACKAGING]", " Repackaging"),
     ("[DOWNLOADING]", " Downloading"),
     ("[DOWNLOADED]", "  Downloaded"),
     ("[UPLOADING]", "   Uploading"),
     ("[UPLOADED]", "    Uploaded"),
+    ("[STAGED]", "      Staged"),
+    ("[PROMOTING]", "   Promoting"),
     ("[VERIFYING]", "   Verifying"),
     ("[ARCHIVING]", "   Archiving"),
     ("[INSTALLING]", "  Installing"),
@@ -322,6 +326,7 @@ static E2E_LITERAL_REDACTIONS: &[(&str, &str)] = &[
     ("[LOGOUT]", "      Logout"),
     ("[YANK]", "        Yank"),
     ("[OWNER]", "       Owner"),
+    ("[EDITING]", "     Editing"),
     ("[MIGRATING]", "   Migrating"),
     ("[EXECUTABLE]", "  Executable"),
     ("[SKIPPING]", "    Skipping"),
@@ -243,6 +243,7 @@ pub(crate) fn create_index_line(
     yanked: bool,
     links: Option<String>,
     rust_version: Option<&str>,
+    pubtime: Option<&str>,
     v: Option<u32>,
 ) -> String {
     // This emulates what crates.io does to retain backwards compatibility.
@@ -266,6 +267,9 @@ pub(crate) fn create_index_line(
     if let Some(rust_version) = rust_version {
         json["rust_version"] = serde_json::json!(rust_version);
     }
+    if let Some(pubtime) = pubtime {
+        json["pubtime"] = serde_json::json!(pubtime);
+    }
 
     json.to_string()
 }
@@ -285,28 +289,66 @@ pub(crate) fn write_to_index(registry_path: &Path, name: &str, line: String, loc
 
     // Add the new file to the index.
     if !local {
-        let repo = t!(git2::Repository::open(&registry_path));
-        let mut index = t!(repo.index());
-        t!(index.add_path(Path::new(&file)));
-        t!(index.write());
-        let id = t!(index.write_tree());
-
-        // Commit this change.
-        let tree = t!(repo.find_tree(id));
-        let sig = t!(repo.signature());
-        let parent = t!(repo.refname_to_id("refs/heads/master"));
-        let parent = t!(repo.find_commit(parent));
-        t!(repo.commit(
-            Some("HEAD"),
-            &sig,
-            &sig,
-            "Another commit",
-            &tree,
-            &[&parent]
-        ));
+        commit_index_file(registry_path, &file);
     }
 }
 
+/// Rewrites the index entry of `name@vers` with `f`, like a registry does when
+/// a version is yanked.
+///
+/// Does nothing if the version isn't in the index.
+pub(crate) fn update_index_entry(
+    registry_path: &Path,
+    name: &str,
+    vers: &str,
+    f: impl FnOnce(&mut serde_json::Value),
+) {
+    let file = cargo_util::registry::make_dep_path(name, false);
+    let dst = registry_path.join(&file);
+    let Ok(prev) = fs::read_to_string(&dst) else {
+        return;
+    };
+    let mut f = Some(f);
+    let lines = prev
+        .lines()
+        .map(|line| {
+            let mut entry = serde_json::from_str::<serde_json::Value>(line).unwrap();
+            if entry["vers"] != vers {
+                return line.to_string();
+            }
+            if let Some(f) = f.take() {
+                f(&mut entry);
+            }
+            entry.to_string()
+        })
+        .map(|line| line + "\n")
+        .collect::<String>();
+    t!(fs::write(&dst, lines));
+    commit_index_file(registry_path, &file);
+}
+
+fn commit_index_file(registry_path: &Path, file: &str) {
+    let repo = t!(git2::Repository::open(&registry_path));
+    let mut index = t!(repo.index());
+    t!(index.add_path(Path::new(&file)));
+    t!(index.write());
+    let id = t!(index.write_tree());
+
+    // Commit this change.
+    let tree = t!(repo.find_tree(id));
+    let sig = t!(repo.signature());
+    let parent = t!(repo.refname_to_id("refs/heads/master"));
+    let parent = t!(repo.find_commit(parent));
+    t!(repo.commit(
+        Some("HEAD"),
+        &sig,
+        &sig,
+        "Another commit",
+        &tree,
+        &[&parent]
+    ));
+}
+
 fn split_index_features(mut features: FeatureMap) -> (FeatureMap, Option<FeatureMap>) {
     let mut features2 = FeatureMap::new();
     for (feat, values) in features.iter_mut() {
@@ -44,7 +44,7 @@
 
 use crate::git::repo;
 use crate::paths;
-use crate::publish::{create_index_line, write_to_index};
+use crate::publish::{create_index_line, update_index_entry, write_to_index};
 use cargo_util::paths::append;
 use cargo_util::Sha256;
 use flate2::write::GzEncoder;
@@ -574,6 +574,7 @@ pub struct Package {
     proc_macro: bool,
     links: Option<String>,
     rust_version: Option<String>,
+    pubtime: Option<String>,
     cargo_features: Vec<String>,
     v: Option<u32>,
 }
@@ -979,11 +980,40 @@ impl HttpServer {
             }
             // publish
             ("put", ["api", "v1", "crates", "new"]) => self.check_authorized_publish(req),
-            // The remainder of the operators in the test framework do nothing other than responding 'ok'.
-            //
-            // Note: We don't need to support anything real here because there are no tests that
-            // currently require anything other than publishing via the http api.
-
+            // staged publish
+            ("put", ["api", "v1", "crates", "staged"]) => self.check_authorized_stage(req),
+            // promote
+            ("put", ["api", "v1", "crates", crate_name, version, "promote"]) => {
+                if !self.check_authorized(
+                    req,
+                    Some(Mutation {
+                        mutation: "promote",
+                        name: Some(crate_name),
+                        vers: Some(version),
+                        cksum: None,
+                    }),
+                ) {
+                    self.unauthorized(req)
+                } else {
+                    self.promote(req, crate_name, version)
+                }
+            }
+            // edit metadata
+            ("put", ["api", "v1", "crates", crate_name, version, "metadata"]) => {
+                if !self.check_authorized(
+                    req,
+                    Some(Mutation {
+                        mutation: "edit-metadata",
+                        name: Some(crate_name),
+                        vers: Some(version),
+                        cksum: None,
+                    }),
+                ) {
+                    self.unauthorized(req)
+                } else {
+                    self.edit_metadata(req, crate_name, version)
+                }
+            }
             // yank / unyank
             ("delete" | "put", ["api", "v1", "crates", crate_name, version, mutation]) => {
                 if !self.check_authorized(
@@ -997,7 +1027,7 @@ impl HttpServer {
                 ) {
                     self.unauthorized(req)
                 } else {
-                    self.ok(&req)
+                    self.yank(req, crate_name, version, *mutation == "yank")
                 }
             }
             // owners
@@ -1013,7 +1043,7 @@ impl HttpServer {
                 ) {
                     self.unauthorized(req)
                 } else {
-                    self.ok(&req)
+                    self.owners(req, crate_name)
                 }
             }
             _ => self.not_found(&req),
@@ -1021,12 +1051,24 @@ impl HttpServer {
     }
 
     /// Unauthorized response
+    ///
+    /// With asymmetric tokens, the challenge advertises the id of the key the
+    /// server accepts.
     pub fn unauthorized(&self, _req: &Request) -> Response {
+        let mut challenge =
+            r#"WWW-Authenticate: Cargo login_url="https://test-registry-login/me""#.to_string();
+        if let Token::Keys(private_key, _) = &self.token {
+            let secret: AsymmetricSecretKey<pasetors::version3::V3> =
+                private_key.as_str().try_into().unwrap();
+            let public: AsymmetricPublicKey<pasetors::version3::V3> = (&secret).try_into().unwrap();
+            let pub_key_id: pasetors::paserk::Id = (&public).into();
+            let mut paserk_pub_key_id = String::new();
+            FormatAsPaserk::fmt(&pub_key_id, &mut paserk_pub_key_id).unwrap();
+            challenge.push_str(&format!(r#", kip="{paserk_pub_key_id}""#));
+        }
         Response {
             code: 401,
-            headers: vec![
-                r#"WWW-Authenticate: Cargo login_url="https://test-registry-login/me""#.to_string(),
-            ],
+            headers: vec![challenge],
             body: b"Unauthorized message from server.".to_vec(),
         }
     }
@@ -1122,10 +1164,21 @@ impl HttpServer {
     }
 
     pub fn check_authorized_publish(&self, req: &Request) -> Response {
+        self.receive_crate(req, false)
+    }
+
+    /// Stores a crate uploaded by `cargo publish --stage` until it is
+    /// promoted, without adding it to the index.
+    pub fn check_authorized_stage(&self, req: &Request) -> Response {
+        self.receive_crate(req, true)
+    }
+
+    fn receive_crate(&self, req: &Request, staged: bool) -> Response {
         if let Some(body) = &req.body {
             // Mimic the publish behavior for local registries by writing out the request
             // so tests can verify publishes made to either registry type.
-            let path = self.api_path.join("api/v1/crates/new");
+            let endpoint = if staged { "staged" } else { "new" };
+            let path = self.api_path.join("api/v1/crates").join(endpoint);
             t!(fs::create_dir_all(path.parent().unwrap()));
             t!(fs::write(&path, body));
 
@@ -1152,6 +1205,17 @@ impl HttpServer {
                 return self.unauthorized(req);
             }
 
+            if staged {
+                let dir = self.staged_path(&new_crate.name, &new_crate.vers);
+                t!(fs::create_dir_all(&dir));
+                t!(fs::write(dir.join("download"), file));
+                t!(fs::write(
+                    dir.join("crate.json"),
+                    serde_json::to_vec(&new_crate).unwrap()
+                ));
+                return self.ok(&req);
+            }
+
             let dst = self
                 .dl_path
                 .join(&new_crate.name)
@@ -1179,6 +1243,156 @@ impl HttpServer {
             }
         }
     }
+
+    /// Where a crate uploaded by `cargo publish --stage` is kept until it is
+    /// promoted.
+    fn staged_path(&self, name: &str, vers: &str) -> PathBuf {
+        self.api_path.join("staged").join(name).join(vers)
+    }
+
+    /// Makes a staged crate available for download and adds it to the index.
+    pub fn promote(&self, req: &Request, name: &str, vers: &str) -> Response {
+        let dir = self.staged_path(name, vers);
+        if !dir.exists() {
+            return Response {
+                code: 404,
+                headers: vec![],
+                body: format!(
+                    r#"{{"errors": [{{"detail": "no staged version {vers} of crate `{name}`"}}]}}"#
+                )
+                .into_bytes(),
+            };
+        }
+        let new_crate: crates_io::NewCrate =
+            serde_json::from_slice(&t!(fs::read(dir.join("crate.json")))).unwrap();
+        let file = t!(fs::read(dir.join("download")));
+        let dst = self.dl_path.join(name).join(vers).join("download");
+        save_new_crate(dst, new_crate, &file, cksum(&file), &self.registry_path);
+        t!(fs::remove_dir_all(&dir));
+        self.ok(req)
+    }
+    /// Where the owners of a crate are kept, in the format of the owners
+    /// list endpoint.
+    fn owners_path(&self, name: &str) -> PathBuf {
+        self.api_path
+            .join("api/v1/crates")
+            .join(name)
+            .join("owners")
+    }
+
+    /// Lists, adds or removes the owners of a crate.
+    ///
+    /// Owners are added with the role in the request, if any, and adding an
+    /// existing owner changes its role.
+    pub fn owners(&self, req: &Request, name: &str) -> Response {
+        let path = self.owners_path(name);
+        let mut owners = match fs::read(&path) {
+            Ok(body) => serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+            Err(_) => serde_json::json!({ "users": [] }),
+        };
+        if req.method == "get" {
+            return Response {
+                code: 200,
+                headers: vec![],
+                body: serde_json::to_vec(&owners).unwrap(),
+            };
+        }
+
+        let Some(body) = &req.body else {
+            return Response {
+                code: 400,
+                headers: vec![],
+                body: b"The request was missing a body".to_vec(),
+            };
+        };
+        let body = serde_json::from_slice::<serde_json::Value>(body).unwrap();
+        let logins = body["users"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|login| login.as_str().unwrap())
+            .collect::<Vec<_>>();
+        let users = owners["users"].as_array_mut().unwrap();
+        let msg = if req.method == "put" {
+            for login in &logins {
+                match users.iter_mut().find(|user| user["login"] == *login) {
+                    Some(user) => user["role"] = body["role"].clone(),
+                    None => {
+                        let id = users.len() + 1;
+                        users.push(serde_json::json!({
+                            "id": id,
+                            "login": login,
+                            "role": body["role"],
+                        }));
+                    }
+                }
+            }
+            format!("{} added as owners of crate {name}", logins.join(", "))
+        } else {
+            users.retain(|user| !logins.iter().any(|login| user["login"] == *login));
+            "owners successfully removed".to_string()
+        };
+        t!(fs::create_dir_all(path.parent().unwrap()));
+        t!(fs::write(&path, serde_json::to_vec(&owners).unwrap()));
+        Response {
+            code: 200,
+            headers: vec![],
+            body: serde_json::to_vec(&serde_json::json!({ "ok": true, "msg": msg })).unwrap(),
+        }
+    }
+
+    /// Marks a version as yanked or not in the index, recording the reason
+    /// and advisory sent along with a yank.
+    pub fn yank(&self, req: &Request, name: &str, vers: &str, yanked: bool) -> Response {
+        let reason = req
+            .body
+            .as_ref()
+            .filter(|body| !body.is_empty())
+            .map(|body| serde_json::from_slice::<crates_io::YankReason>(body).unwrap())
+            .unwrap_or_default();
+        update_index_entry(&self.registry_path, name, vers, |entry| {
+            let entry = entry.as_object_mut().unwrap();
+            entry.insert("yanked".to_string(), yanked.into());
+            entry.remove("yank_reason");
+            entry.remove("yank_advisory");
+            if let Some(reason) = reason.reason {
+                entry.insert("yank_reason".to_string(), reason.into());
+            }
+            if let Some(advisory) = reason.advisory {
+                entry.insert("yank_advisory".to_string(), advisory.into());
+            }
+        });
+        self.ok(req)
+    }
+
+    /// Records the metadata sent by `cargo registry edit-metadata` so tests
+    /// can verify it, merging it with the metadata of earlier edits.
+    pub fn edit_metadata(&self, req: &Request, name: &str, vers: &str) -> Response {
+        let Some(body) = &req.body else {
+            return Response {
+                code: 400,
+                headers: vec![],
+                body: b"The request was missing a body".to_vec(),
+            };
+        };
+        let path = self
+            .api_path
+            .join("api/v1/crates")
+            .join(name)
+            .join(vers)
+            .join("metadata");
+        let mut metadata = match fs::read(&path) {
+            Ok(existing) => serde_json::from_slice::<serde_json::Value>(&existing).unwrap(),
+            Err(_) => serde_json::json!({}),
+        };
+        let edit = serde_json::from_slice::<serde_json::Value>(body).unwrap();
+        for (key, value) in edit.as_object().unwrap() {
+            metadata[key] = value.clone();
+        }
+        t!(fs::create_dir_all(path.parent().unwrap()));
+        t!(fs::write(&path, serde_json::to_vec(&metadata).unwrap()));
+        self.ok(req)
+    }
 }
 
 fn save_new_crate(
@@ -1227,6 +1441,7 @@ fn save_new_crate(
         new_crate.links,
         new_crate.rust_version.as_deref(),
         None,
+        None,
     );
 
     write_to_index(registry_path, &new_crate.name, line, false);
@@ -1253,6 +1468,7 @@ impl Package {
             proc_macro: false,
             links: None,
             rust_version: None,
+            pubtime: None,
             cargo_features: Vec::new(),
             v: None,
         }
@@ -1404,6 +1620,12 @@ impl Package {
         self
     }
 
+    /// Sets when the version was published, in RFC 3339 format, in the index.
+    pub fn pubtime(&mut self, pubtime: &str) -> &mut Package {
+        self.pubtime = Some(pubtime.into());
+        self
+    }
+
     /// Causes the JSON line emitted in the index to be invalid, presumably
     /// causing Cargo to skip over this version.
     pub fn invalid_json(&mut self, invalid: bool) -> &mut Package {
@@ -1494,6 +1716,7 @@ impl Package {
             self.yanked,
             self.links.clone(),
             self.rust_version.as_deref(),
+            self.pubtime.as_deref(),
             self.v,
         );
 
@@ -783,6 +783,8 @@ pub struct TomlDetailedDependency<P: Clone = String> {
     pub branch: Option<String>,
     pub tag: Option<String>,
     pub rev: Option<String>,
+    /// Requires the revision of a `git` dependency to be signed
+    pub verify_signature: Option<bool>,
     pub features: Option<Vec<String>>,
     pub optional: Option<bool>,
     pub default_features: Option<bool>,
@@ -823,6 +825,7 @@ impl<P: Clone> Default for TomlDetailedDependency<P> {
             branch: Default::default(),
             tag: Default::default(),
             rev: Default::default(),
+            verify_signature: Default::default(),
             features: Default::default(),
             optional: Default::default(),
             default_features: Default::default(),
@@ -32,11 +32,32 @@ pub enum Auth {
     Unauthorized,
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 pub struct Crate {
     pub name: String,
     pub description: Option<String>,
     pub max_version: String,
+    pub downloads: Option<u64>,
+    pub recent_downloads: Option<u64>,
+    pub updated_at: Option<String>,
+    pub homepage: Option<String>,
+    pub documentation: Option<String>,
+    pub repository: Option<String>,
+}
+
+/// The criteria of a search with [`Registry::search_page`].
+///
+/// Registries are free to ignore the ordering and filters they don't support.
+#[derive(Default)]
+pub struct SearchQuery<'a> {
+    /// The text to search for.
+    pub query: &'a str,
+    /// The order of the results, like `downloads` or `recent-updates`.
+    pub sort: Option<&'a str>,
+    /// Only return crates in this category.
+    pub category: Option<&'a str>,
+    /// Only return crates with this keyword.
+    pub keyword: Option<&'a str>,
 }
 
 /// This struct is serialized as JSON and sent as metadata ahead of the crate
@@ -97,6 +118,42 @@ pub struct User {
     pub avatar: Option<String>,
     pub email: Option<String>,
     pub name: Option<String>,
+    /// The role of a team owner, if the registry assigns one.
+    #[serde(default)]
+    pub role: Option<String>,
+}
+
+/// The metadata of a published version that can be changed with
+/// [`Registry::edit_metadata`].
+///
+/// Fields that are `None` are left unchanged by the registry.
+///
+/// see <https://doc.rust-lang.org/cargo/reference/registry-web-api.html#edit-metadata>
+#[derive(Serialize, Deserialize, Default)]
+pub struct EditMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub documentation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keywords: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub categories: Option<Vec<String>>,
+}
+
+/// Why a version is being yanked, sent along with [`Registry::yank`].
+///
+/// see <https://doc.rust-lang.org/cargo/reference/registry-web-api.html#yank>
+#[derive(Serialize, Deserialize, Default)]
+pub struct YankReason {
+    /// A short message for users of the yanked version.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// A URL of a security advisory or issue describing the problem.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub advisory: Option<String>,
 }
 
 pub struct Warnings {
@@ -125,6 +182,8 @@ struct ApiError {
 #[derive(Serialize)]
 struct OwnersReq<'a> {
     users: &'a [&'a str],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    role: Option<&'a str>,
 }
 #[derive(Deserialize)]
 struct Users {
@@ -247,14 +306,36 @@ impl Registry {
     }
 
     pub fn add_owners(&mut self, krate: &str, owners: &[&str]) -> Result<String> {
-        let body = serde_json::to_string(&OwnersReq { users: owners })?;
+        self.put_owners(krate, owners, None)
+    }
+
+    /// Invites `owners` like [`Registry::add_owners`], giving them `role`.
+    ///
+    /// Inviting an existing owner changes its role.
+    pub fn add_owners_with_role(
+        &mut self,
+        krate: &str,
+        owners: &[&str],
+        role: &str,
+    ) -> Result<String> {
+        self.put_owners(krate, owners, Some(role))
+    }
+
+    fn put_owners(&mut self, krate: &str, owners: &[&str], role: Option<&str>) -> Result<String> {
+        let body = serde_json::to_string(&OwnersReq {
+            users: owners,
+            role,
+        })?;
         let body = self.put(&format!("/crates/{}/owners", krate), body.as_bytes())?;
         assert!(serde_json::from_str::<OwnerResponse>(&body)?.ok);
         Ok(serde_json::from_str::<OwnerResponse>(&body)?.msg)
     }
 
     pub fn remove_owners(&mut self, krate: &str, owners: &[&str]) -> Result<()> {
-        let body = serde_json::to_string(&OwnersReq { users: owners })?;
+        let body = serde_json::to_string(&OwnersReq {
+            users: owners,
+            role: None,
+        })?;
         let body = self.delete(&format!("/crates/{}/owners", krate), Some(body.as_bytes()))?;
         assert!(serde_json::from_str::<OwnerResponse>(&body)?.ok);
         Ok(())
@@ -265,7 +346,25 @@ impl Registry {
         Ok(serde_json::from_str::<Users>(&body)?.users)
     }
 
-    pub fn publish(&mut self, krate: &NewCrate, mut tarball: &File) -> Result<Warnings> {
+    pub fn publish(&mut self, krate: &NewCrate, tarball: &File) -> Result<Warnings> {
+        self.upload("/crates/new", krate, tarball)
+    }
+
+    /// Uploads a crate like [`Registry::publish`], but keeps it hidden from
+    /// the index until it is promoted with [`Registry::promote`].
+    pub fn publish_staged(&mut self, krate: &NewCrate, tarball: &File) -> Result<Warnings> {
+        self.upload("/crates/staged", krate, tarball)
+    }
+
+    /// Makes a crate uploaded with [`Registry::publish_staged`] visible in the
+    /// index.
+    pub fn promote(&mut self, krate: &str, version: &str) -> Result<()> {
+        let body = self.put(&format!("/crates/{}/{}/promote", krate, version), &[])?;
+        assert!(serde_json::from_str::<R>(&body)?.ok);
+        Ok(())
+    }
+
+    fn upload(&mut self, path: &str, krate: &NewCrate, mut tarball: &File) -> Result<Warnings> {
         let json = serde_json::to_string(krate)?;
         // Prepare the body. The format of the upload request is:
         //
@@ -291,7 +390,7 @@ impl Registry {
         let size = tarball_len as usize + header.len();
         let mut body = Cursor::new(header).chain(tarball);
 
-        let url = format!("{}/api/v1/crates/new", self.host);
+        let url = format!("{}/api/v1{}", self.host, path);
 
         self.handle.put(true)?;
         self.handle.url(&url)?;
@@ -350,19 +449,50 @@ impl Registry {
     }
 
     pub fn search(&mut self, query: &str, limit: u32) -> Result<(Vec<Crate>, u32)> {
-        let formatted_query = percent_encode(query.as_bytes(), NON_ALPHANUMERIC);
-        let body = self.req(
-            &format!("/crates?q={}&per_page={}", formatted_query, limit),
-            None,
-            Auth::Unauthorized,
-        )?;
+        let query = SearchQuery {
+            query,
+            ..Default::default()
+        };
+        self.search_page(&query, 1, limit)
+    }
+
+    /// Returns the crates on the 1-based `page` of the results of `query`,
+    /// with `per_page` crates per page, and the total number of results.
+    pub fn search_page(
+        &mut self,
+        query: &SearchQuery<'_>,
+        page: u32,
+        per_page: u32,
+    ) -> Result<(Vec<Crate>, u32)> {
+        let encode = |s: &str| percent_encode(s.as_bytes(), NON_ALPHANUMERIC).to_string();
+        let mut path = format!("/crates?q={}&per_page={}", encode(query.query), per_page);
+        if page > 1 {
+            path.push_str(&format!("&page={}", page));
+        }
+        let params = [
+            ("sort", query.sort),
+            ("category", query.category),
+            ("keyword", query.keyword),
+        ];
+        for (name, value) in params {
+            if let Some(value) = value {
+                path.push_str(&format!("&{}={}", name, encode(value)));
+            }
+        }
+        let body = self.req(&path, None, Auth::Unauthorized)?;
 
         let crates = serde_json::from_str::<Crates>(&body)?;
         Ok((crates.crates, crates.meta.total))
     }
 
-    pub fn yank(&mut self, krate: &str, version: &str) -> Result<()> {
-        let body = self.delete(&format!("/crates/{}/{}/yank", krate, version), None)?;
+    /// Yanks a version, optionally recording why so that the registry can
+    /// show it to users of that version.
+    pub fn yank(&mut self, krate: &str, version: &str, reason: Option<&YankReason>) -> Result<()> {
+        let reason = reason.map(serde_json::to_string).transpose()?;
+        let body = self.delete(
+            &format!("/crates/{}/{}/yank", krate, version),
+            reason.as_ref().map(|r| r.as_bytes()),
+        )?;
         assert!(serde_json::from_str::<R>(&body)?.ok);
         Ok(())
     }
@@ -373,6 +503,22 @@ impl Registry {
         Ok(())
     }
 
+    /// Changes the metadata of an already published version.
+    pub fn edit_metadata(
+        &mut self,
+        krate: &str,
+        version: &str,
+        metadata: &EditMetadata,
+    ) -> Result<()> {
+        let body = serde_json::to_string(metadata)?;
+        let body = self.put(
+            &format!("/crates/{}/{}/metadata", krate, version),
+            body.as_bytes(),
+        )?;
+        assert!(serde_json::from_str::<R>(&body)?.ok);
+        Ok(())
+    }
+
     fn put(&mut self, path: &str, b: &[u8]) -> Result<String> {
         self.handle.put(true)?;
         self.req(path, Some(b), Auth::Authorized)
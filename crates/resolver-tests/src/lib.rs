@@ -142,7 +142,7 @@ pub fn resolve_with_global_context_raw(
         ) -> Poll<CargoResult<()>> {
             for summary in self.list.iter() {
                 let matched = match kind {
-                    QueryKind::Exact => dep.matches(summary),
+                    QueryKind::Exact | QueryKind::ExactWithYanked => dep.matches(summary),
                     QueryKind::Alternatives => true,
                     QueryKind::Normalized => true,
                 };
@@ -168,6 +168,20 @@ pub enum Operation<'a> {
         /// The version of the crate
         vers: &'a str,
     },
+    /// The user is attempting to make a staged crate visible.
+    Promote {
+        /// The name of the crate
+        name: &'a str,
+        /// The version of the crate
+        vers: &'a str,
+    },
+    /// The user is attempting to change the metadata of a published crate.
+    EditMetadata {
+        /// The name of the crate
+        name: &'a str,
+        /// The version of the crate
+        vers: &'a str,
+    },
     /// The user is attempting to modify the owners of a crate.
     Owners {
         /// The name of the crate
@@ -207,16 +221,34 @@ pub enum CacheControl {
     },
     /// Cache this result and use it for all subsequent requests in the current Cargo invocation.
     Session,
+    /// Cache this result until the specified time, like [`CacheControl::Expires`], but ask the
+    /// provider for a new token once `refresh_after` has passed. If getting a new token fails,
+    /// Cargo keeps using the cached one until it expires.
+    ///
+    /// Only sent with [`PROTOCOL_VERSION_2`]. For [`PROTOCOL_VERSION_1`] requests, [`main`]
+    /// sends it as [`CacheControl::Expires`].
+    Refresh {
+        #[serde(with = "time::serde::timestamp")]
+        expiration: OffsetDateTime,
+        #[serde(rename = "refresh-after", with = "time::serde::timestamp")]
+        refresh_after: OffsetDateTime,
+    },
     #[serde(other)]
     Unknown,
 }
 
 /// Credential process JSON protocol version. If the protocol needs to make
-/// a breaking change, a new protocol version should be defined (`PROTOCOL_VERSION_2`).
+/// a breaking change, a new protocol version should be defined (like `PROTOCOL_VERSION_2`).
 /// This library should offer support for both protocols if possible, by signaling
 /// in the `CredentialHello` message. Cargo will then choose which protocol to use,
 /// or it will error if there are no common protocol versions available.
 pub const PROTOCOL_VERSION_1: u32 = 1;
+/// Credential process JSON protocol version 2. Adds refresh hints to tokens
+/// ([`CacheControl::Refresh`]).
+pub const PROTOCOL_VERSION_2: u32 = 2;
+/// Protocol versions supported by this library, in ascending order.
+pub const PROTOCOL_VERSIONS: &[u32] = &[PROTOCOL_VERSION_1, PROTOCOL_VERSION_2];
+
 pub trait Credential {
     /// Retrieves a token for the given registry.
     fn perform(
@@ -241,7 +273,7 @@ fn doit(
     credential: impl Credential,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
     let hello = CredentialHello {
-        v: vec![PROTOCOL_VERSION_1],
+        v: PROTOCOL_VERSIONS.to_vec(),
     };
     serde_json::to_writer(std::io::stdout(), &hello)?;
     println!();
@@ -255,7 +287,8 @@ fn doit(
         let request = deserialize_request(&buffer)?;
         let response = stdin_stdout_to_console(|| {
             credential.perform(&request.registry, &request.action, &request.args)
-        })?;
+        })?
+        .map(|response| for_protocol_version(response, request.v));
 
         serde_json::to_writer(std::io::stdout(), &response)?;
         println!();
@@ -267,12 +300,29 @@ fn deserialize_request(
     value: &str,
 ) -> Result<CredentialRequest<'_>, Box<dyn std::error::Error + Send + Sync>> {
     let request: CredentialRequest<'_> = serde_json::from_str(&value)?;
-    if request.v != PROTOCOL_VERSION_1 {
+    if !PROTOCOL_VERSIONS.contains(&request.v) {
         return Err(format!("unsupported protocol version {}", request.v).into());
     }
     Ok(request)
 }
 
+/// Replaces the parts of a response that don't exist in protocol version `v`
+/// with their closest equivalent.
+fn for_protocol_version(response: CredentialResponse, v: u32) -> CredentialResponse {
+    match response {
+        CredentialResponse::Get {
+            token,
+            cache: CacheControl::Refresh { expiration, .. },
+            operation_independent,
+        } if v < PROTOCOL_VERSION_2 => CredentialResponse::Get {
+            token,
+            cache: CacheControl::Expires { expiration },
+            operation_independent,
+        },
+        response => response,
+    }
+}
+
 /// Read a line of text from stdin.
 pub fn read_line() -> Result<String, io::Error> {
     let mut buf = String::new();
@@ -336,6 +386,42 @@ mod tests {
                 .unwrap_err()
                 .to_string()
         );
+
+        let cc = CacheControl::Refresh {
+            expiration: OffsetDateTime::from_unix_timestamp(1693928537).unwrap(),
+            refresh_after: OffsetDateTime::from_unix_timestamp(1693928237).unwrap(),
+        };
+        let json = serde_json::to_string(&cc).unwrap();
+        assert_eq!(
+            json,
+            r#"{"cache":"refresh","expiration":1693928537,"refresh-after":1693928237}"#
+        );
+        assert_eq!(serde_json::from_str::<CacheControl>(&json).unwrap(), cc);
+    }
+
+    #[test]
+    fn refresh_for_protocol_version_1() {
+        let expiration = OffsetDateTime::from_unix_timestamp(1693928537).unwrap();
+        let response = CredentialResponse::Get {
+            token: Secret::from("value".to_string()),
+            cache: CacheControl::Refresh {
+                expiration,
+                refresh_after: OffsetDateTime::from_unix_timestamp(1693928237).unwrap(),
+            },
+            operation_independent: false,
+        };
+        assert_eq!(
+            for_protocol_version(response.clone(), PROTOCOL_VERSION_2),
+            response
+        );
+        assert_eq!(
+            for_protocol_version(response, PROTOCOL_VERSION_1),
+            CredentialResponse::Get {
+                token: Secret::from("value".to_string()),
+                cache: CacheControl::Expires { expiration },
+                operation_independent: false,
+            }
+        );
     }
 
     #[test]
@@ -7,7 +7,7 @@ use snapbox::prelude::*;
 fn stdout_redirected() {
     let bin = snapbox::cmd::compile_example("stdout-redirected", []).unwrap();
 
-    let hello = r#"{"v":[1]}"#;
+    let hello = r#"{"v":[1,2]}"#;
     let get_request = r#"{"v": 1, "registry": {"index-url":"sparse+https://test/","name":"alternative"},"kind": "get","operation": "read","args": []}"#;
     let err_not_supported = r#"{"Err":{"kind":"operation-not-supported"}}"#;
 
@@ -24,7 +24,7 @@ fn stdout_redirected() {
 fn file_provider() {
     let bin = snapbox::cmd::compile_example("file-provider", []).unwrap();
 
-    let hello = r#"{"v":[1]}"#;
+    let hello = r#"{"v":[1,2]}"#;
     let login_request = r#"{"v": 1,"registry": {"index-url":"https://github.com/rust-lang/crates.io-index","name":"crates-io"},"kind": "login","token": "s3krit","args": []}"#;
     let login_response = r#"{"Ok":{"kind":"login"}}"#;
 
@@ -1,13 +1,22 @@
 use crate::command_prelude::*;
 
 use cargo::ops;
-use cargo::ops::FetchOptions;
+use cargo::ops::{FetchOptions, FetchStatsFormat};
 
 pub fn cli() -> Command {
     subcommand("fetch")
         .about("Fetch dependencies of a package from the network")
         .arg_silent_suggestion()
         .arg_target_triple("Fetch dependencies for the target triple")
+        .arg(
+            optional_opt(
+                "stats",
+                "Print a summary of the network requests once done (unstable) [default: human]",
+            )
+            .value_name("FMT")
+            .require_equals(true)
+            .value_parser(["human", "json"]),
+        )
         .arg_manifest_path()
         .arg_lockfile_path()
         .after_help(color_print::cstr!(
@@ -18,9 +27,20 @@ pub fn cli() -> Command {
 pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
     let ws = args.workspace(gctx)?;
 
+    let stats = if args.contains_id("stats") {
+        gctx.cli_unstable().fail_if_stable_opt("--stats", 14700)?;
+        match args.get_one::<String>("stats").map(String::as_str) {
+            Some("json") => Some(FetchStatsFormat::Json),
+            _ => Some(FetchStatsFormat::Human),
+        }
+    } else {
+        None
+    };
+
     let opts = FetchOptions {
         gctx,
         targets: args.targets()?,
+        stats,
     };
     let _ = ops::fetch(&ws, &opts)?;
     Ok(())
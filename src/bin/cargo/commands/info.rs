@@ -13,6 +13,10 @@ pub fn cli() -> Command {
                 .help_heading(heading::PACKAGE_SELECTION)
                 .help("Package to inspect"),
         )
+        .arg(flag(
+            "versions",
+            "List all versions with their publish date, yank status and rust-version (unstable)",
+        ))
         .arg_index("Registry index URL to search packages in")
         .arg_registry("Registry to search packages in")
         .arg_silent_suggestion()
@@ -29,7 +33,13 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
     let spec = PackageIdSpec::parse(package)
         .with_context(|| format!("invalid package ID specification: `{package}`"))?;
 
+    let versions = args.flag("versions");
+    if versions {
+        gctx.cli_unstable()
+            .fail_if_stable_opt("--versions", 14707)?;
+    }
+
     let reg_or_index = args.registry_or_index(gctx)?;
-    info(&spec, gctx, reg_or_index)?;
+    info(&spec, gctx, reg_or_index, versions)?;
     Ok(())
 }
@@ -8,6 +8,20 @@ pub fn cli() -> Command {
         .about("Log in to a registry.")
         .arg(Arg::new("token").value_name("TOKEN").action(ArgAction::Set))
         .arg_registry("Registry to use")
+        .arg(
+            flag(
+                "status",
+                "Show which credential providers are used and whether they have a token (unstable)",
+            )
+            .conflicts_with_all(["token", "args"]),
+        )
+        .arg(
+            flag(
+                "rotate-key",
+                "Replace the secret key, keeping the old one for registries that still expect it (unstable)",
+            )
+            .conflicts_with("status"),
+        )
         .arg(
             Arg::new("args")
                 .help("Additional arguments for the credential provider")
@@ -27,16 +41,29 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
         "must not be index URL"
     );
 
+    if args.flag("status") {
+        gctx.cli_unstable().fail_if_stable_opt("--status", 14708)?;
+        ops::registry_login_status(gctx, reg.as_ref())?;
+        return Ok(());
+    }
+
     let extra_args = args
         .get_many::<String>("args")
         .unwrap_or_default()
         .map(String::as_str)
         .collect::<Vec<_>>();
+    let rotate_key = args.flag("rotate-key");
+    if rotate_key {
+        let unstable = gctx.cli_unstable();
+        let enabled = unstable.asymmetric_token;
+        unstable.fail_if_stable_opt_custom_z("--rotate-key", 10519, "asymmetric-token", enabled)?;
+    }
     ops::registry_login(
         gctx,
         args.get_one::<String>("token").map(|s| s.as_str().into()),
         reg.as_ref(),
         &extra_args,
+        rotate_key,
     )?;
     Ok(())
 }
@@ -27,6 +27,7 @@ pub fn builtin() -> Vec<Command> {
         pkgid::cli(),
         publish::cli(),
         read_manifest::cli(),
+        registry::cli(),
         remove::cli(),
         report::cli(),
         run::cli(),
@@ -73,6 +74,7 @@ pub fn builtin_exec(cmd: &str) -> Option<Exec> {
         "pkgid" => pkgid::exec,
         "publish" => publish::exec,
         "read-manifest" => read_manifest::exec,
+        "registry" => registry::exec,
         "remove" => remove::exec,
         "report" => report::exec,
         "run" => run::exec,
@@ -117,6 +119,7 @@ pub mod package;
 pub mod pkgid;
 pub mod publish;
 pub mod read_manifest;
+pub mod registry;
 pub mod remove;
 pub mod report;
 pub mod run;
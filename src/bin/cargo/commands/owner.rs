@@ -23,6 +23,11 @@ pub fn cli() -> Command {
             )
             .short('r'),
         )
+        .arg(
+            opt("role", "Role to give to the owners being added (unstable)")
+                .value_name("ROLE")
+                .requires("add"),
+        )
         .arg(flag("list", "List owners of a crate").short('l'))
         .arg_index("Registry index URL to modify owners for")
         .arg_registry("Registry to modify owners for")
@@ -34,6 +39,9 @@ pub fn cli() -> Command {
 }
 
 pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
+    if args.contains_id("role") {
+        gctx.cli_unstable().fail_if_stable_opt("--role", 14704)?;
+    }
     let opts = OwnersOptions {
         krate: args.get_one::<String>("crate").cloned(),
         token: args.get_one::<String>("token").cloned().map(Secret::from),
@@ -41,6 +49,7 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
         to_add: args
             .get_many::<String>("add")
             .map(|xs| xs.cloned().collect()),
+        role: args.get_one::<String>("role").cloned(),
         to_remove: args
             .get_many::<String>("remove")
             .map(|xs| xs.cloned().collect()),
@@ -26,6 +26,17 @@ pub fn cli() -> Command {
             "allow-dirty",
             "Allow dirty working directories to be packaged",
         ))
+        .arg(flag(
+            "reproducible-check",
+            "Repackage in different conditions and fail if the results differ (unstable)",
+        ))
+        .arg(
+            flag(
+                "diff-published",
+                "Print the changes since the last published version (unstable)",
+            )
+            .conflicts_with("list"),
+        )
         .arg_silent_suggestion()
         .arg_package_spec_no_all(
             "Package(s) to assemble",
@@ -60,6 +71,14 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
             gctx.cli_unstable().package_workspace,
         )?;
     }
+    if args.flag("reproducible-check") {
+        gctx.cli_unstable()
+            .fail_if_stable_opt("--reproducible-check", 14701)?;
+    }
+    if args.flag("diff-published") {
+        gctx.cli_unstable()
+            .fail_if_stable_opt("--diff-published", 14702)?;
+    }
     let reg_or_index = args.registry_or_index(gctx)?;
     let ws = args.workspace(gctx)?;
     if ws.root_maybe().is_embedded() {
@@ -85,6 +104,8 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
             keep_going: args.keep_going(),
             cli_features: args.cli_features()?,
             reg_or_index,
+            reproducible_check: args.flag("reproducible-check"),
+            diff_published: args.flag("diff-published"),
         },
     )?;
 
@@ -17,6 +17,15 @@ pub fn cli() -> Command {
             "allow-dirty",
             "Allow dirty working directories to be packaged",
         ))
+        .arg(flag(
+            "stage",
+            "Upload without making the package available until it is promoted (unstable)",
+        ))
+        .arg(
+            opt("promote", "Make a staged package available (unstable)")
+                .value_name("NAME@VERSION")
+                .conflicts_with_all(["stage", "dry-run"]),
+        )
         .arg_silent_suggestion()
         .arg_package_spec_no_all(
             "Package(s) to publish",
@@ -35,7 +44,23 @@ pub fn cli() -> Command {
 }
 
 pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
+    if args.flag("stage") {
+        gctx.cli_unstable().fail_if_stable_opt("--stage", 14703)?;
+    }
+    if args.contains_id("promote") {
+        gctx.cli_unstable().fail_if_stable_opt("--promote", 14703)?;
+    }
     let reg_or_index = args.registry_or_index(gctx)?;
+    if let Some(spec) = args.get_one::<String>("promote") {
+        ops::promote(
+            gctx,
+            spec,
+            args.get_one::<String>("token")
+                .map(|s| s.to_string().into()),
+            reg_or_index,
+        )?;
+        return Ok(());
+    }
     let ws = args.workspace(gctx)?;
     if ws.root_maybe().is_embedded() {
         return Err(anyhow::format_err!(
@@ -77,6 +102,7 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
             jobs: args.jobs()?,
             keep_going: args.keep_going(),
             dry_run: args.dry_run(),
+            stage: args.flag("stage"),
             cli_features: args.cli_features()?,
         },
     )?;
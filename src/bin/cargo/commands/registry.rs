@@ -0,0 +1,90 @@
+use crate::command_prelude::*;
+
+use cargo::ops::{self, EditMetadataOptions};
+use cargo_credential::Secret;
+use crates_io::EditMetadata;
+
+use super::yank::resolve_crate;
+
+pub fn cli() -> Command {
+    subcommand("registry")
+        .about("Manage published crates on a registry")
+        .after_help(color_print::cstr!(
+            "Run `<cyan,bold>cargo help registry</>` for more detailed information.\n"
+        ))
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .subcommand(
+            subcommand("edit-metadata")
+                .about("Change the metadata of a published version of a crate (unstable)")
+                .arg(Arg::new("crate").value_name("CRATE").action(ArgAction::Set))
+                .arg(
+                    opt("version", "The version to edit the metadata of")
+                        .alias("vers")
+                        .value_name("VERSION"),
+                )
+                .arg(opt("description", "New description of the crate").value_name("TEXT"))
+                .arg(opt("documentation", "New URL of the crate's documentation").value_name("URL"))
+                .arg(opt("homepage", "New URL of the crate's home page").value_name("URL"))
+                .arg(multi_opt(
+                    "keyword",
+                    "KEYWORD",
+                    "Keyword of the crate, replacing all existing keywords",
+                ))
+                .arg(multi_opt(
+                    "category",
+                    "CATEGORY",
+                    "Category of the crate, replacing all existing categories",
+                ))
+                .arg_index("Registry index URL of the crate")
+                .arg_registry("Registry of the crate")
+                .arg(opt("token", "API token to use when authenticating").value_name("TOKEN"))
+                .arg_silent_suggestion(),
+        )
+}
+
+pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
+    match args.subcommand() {
+        Some(("edit-metadata", args)) => edit_metadata(gctx, args),
+        Some((cmd, _)) => {
+            unreachable!("unexpected command {}", cmd)
+        }
+        None => {
+            unreachable!("unexpected command")
+        }
+    }
+}
+
+fn edit_metadata(gctx: &GlobalContext, args: &ArgMatches) -> CliResult {
+    gctx.cli_unstable().fail_if_stable_command(
+        gctx,
+        "registry edit-metadata",
+        14705,
+        "unstable-options",
+        gctx.cli_unstable().unstable_options,
+    )?;
+    let (krate, version) = resolve_crate(
+        args.get_one::<String>("crate").map(String::as_str),
+        args.get_one::<String>("version").map(String::as_str),
+    )?;
+    let metadata = EditMetadata {
+        description: args.get_one::<String>("description").cloned(),
+        documentation: args.get_one::<String>("documentation").cloned(),
+        homepage: args.get_one::<String>("homepage").cloned(),
+        keywords: args
+            .get_many::<String>("keyword")
+            .map(|xs| xs.cloned().collect()),
+        categories: args
+            .get_many::<String>("category")
+            .map(|xs| xs.cloned().collect()),
+    };
+    let opts = EditMetadataOptions {
+        krate: krate.map(|s| s.to_string()),
+        version: version.map(|s| s.to_string()),
+        token: args.get_one::<String>("token").cloned().map(Secret::from),
+        reg_or_index: args.registry_or_index(gctx)?,
+        metadata,
+    };
+    ops::edit_metadata(gctx, opts)?;
+    Ok(())
+}
@@ -1,8 +1,6 @@
 use crate::command_prelude::*;
 
-use std::cmp::min;
-
-use cargo::ops;
+use cargo::ops::{self, SearchOptions};
 
 pub fn cli() -> Command {
     subcommand("search")
@@ -11,10 +9,25 @@ pub fn cli() -> Command {
         .arg(
             opt(
                 "limit",
-                "Limit the number of results (default: 10, max: 100)",
+                "Limit the number of results (default: 10, max: 1000)",
             )
             .value_name("LIMIT"),
         )
+        .arg(
+            opt("sort", "Order of the results, as supported by the registry")
+                .value_name("ORDER")
+                .value_parser([
+                    "relevance",
+                    "alpha",
+                    "downloads",
+                    "recent-downloads",
+                    "recent-updates",
+                    "new",
+                ]),
+        )
+        .arg(opt("category", "Only show packages in the category").value_name("SLUG"))
+        .arg(opt("keyword", "Only show packages with the keyword").value_name("KEYWORD"))
+        .arg(flag("json", "Print the results as JSON"))
         .arg_index("Registry index URL to search packages in")
         .arg_registry("Registry to search packages in")
         .arg_silent_suggestion()
@@ -25,14 +38,22 @@ pub fn cli() -> Command {
 
 pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
     let reg_or_index = args.registry_or_index(gctx)?;
-    let limit = args.value_of_u32("limit")?;
-    let limit = min(100, limit.unwrap_or(10));
+    let limit = args.value_of_u32("limit")?.unwrap_or(10);
     let query: Vec<&str> = args
         .get_many::<String>("query")
         .unwrap_or_default()
         .map(String::as_str)
         .collect();
     let query: String = query.join("+");
-    ops::search(&query, gctx, reg_or_index, limit)?;
+    let opts = SearchOptions {
+        query,
+        reg_or_index,
+        limit,
+        sort: args.get_one::<String>("sort").cloned(),
+        category: args.get_one::<String>("category").cloned(),
+        keyword: args.get_one::<String>("keyword").cloned(),
+        json: args.flag("json"),
+    };
+    ops::search(gctx, &opts)?;
     Ok(())
 }
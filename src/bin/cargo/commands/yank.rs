@@ -16,6 +16,19 @@ pub fn cli() -> Command {
             "undo",
             "Undo a yank, putting a version back into the index",
         ))
+        .arg(
+            opt("reason", "Why the version is yanked, shown to its users")
+                .value_name("REASON")
+                .conflicts_with("undo"),
+        )
+        .arg(
+            opt(
+                "advisory",
+                "URL of an advisory describing why the version is yanked",
+            )
+            .value_name("URL")
+            .conflicts_with("undo"),
+        )
         .arg_index("Registry index URL to yank from")
         .arg_registry("Registry to yank from")
         .arg(opt("token", "API token to use when authenticating").value_name("TOKEN"))
@@ -41,11 +54,13 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
         args.get_one::<String>("token").cloned().map(Secret::from),
         args.registry_or_index(gctx)?,
         args.flag("undo"),
+        args.get_one::<String>("reason").cloned(),
+        args.get_one::<String>("advisory").cloned(),
     )?;
     Ok(())
 }
 
-fn resolve_crate<'k>(
+pub(crate) fn resolve_crate<'k>(
     mut krate: Option<&'k str>,
     mut version: Option<&'k str>,
 ) -> crate::CargoResult<(Option<&'k str>, Option<&'k str>)> {
@@ -1,6 +1,6 @@
 //! Type definitions for the result of a compilation.
 
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::ffi::{OsStr, OsString};
 use std::path::PathBuf;
 
@@ -8,9 +8,10 @@ use cargo_platform::CfgExpr;
 use cargo_util::{paths, ProcessBuilder};
 
 use crate::core::compiler::apply_env_config;
+use crate::core::compiler::unused_dependencies::UnusedDependency;
 use crate::core::compiler::BuildContext;
 use crate::core::compiler::{CompileKind, Metadata, Unit};
-use crate::core::Package;
+use crate::core::{Package, PackageId};
 use crate::util::{context, CargoResult, GlobalContext};
 
 /// Represents the kind of process we are creating.
@@ -109,6 +110,10 @@ pub struct Compilation<'gctx> {
     /// The target host triple.
     pub host: String,
 
+    /// Dependencies of workspace members that none of their built targets
+    /// used, for the `unused_dependencies` lint.
+    pub unused_dependencies: BTreeMap<PackageId, Vec<UnusedDependency>>,
+
     gctx: &'gctx GlobalContext,
 
     /// Rustc process to be used by default
@@ -152,6 +157,7 @@ impl<'gctx> Compilation<'gctx> {
             to_doc_test: Vec::new(),
             gctx: bcx.gctx,
             host: bcx.host_triple().to_string(),
+            unused_dependencies: BTreeMap::new(),
             rustc_process: rustc,
             rustc_workspace_wrapper_process,
             primary_rustc_process,
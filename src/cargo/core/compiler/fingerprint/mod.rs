@@ -390,6 +390,7 @@ use crate::util::{internal, path_args, StableHasher};
 use crate::{GlobalContext, CARGO_ENV};
 
 use super::custom_build::BuildDeps;
+use super::unused_dependencies;
 use super::{BuildContext, BuildRunner, FileFlavor, Job, Unit, Work};
 
 pub use dirty_reason::DirtyReason;
@@ -1516,6 +1517,7 @@ fn calculate_normal(
         build_runner.bcx.extra_args_for(unit),
         build_runner.lto[unit],
         unit.pkg.manifest().lint_rustflags(),
+        unused_dependencies::is_tracked(build_runner, unit),
     ));
     // Include metadata since it is exposed as environment variables.
     let m = unit.pkg.manifest().metadata();
@@ -197,4 +197,11 @@ impl<'a, 'gctx> JobState<'a, 'gctx> {
         self.messages
             .push(Message::FutureIncompatReport(self.id, report));
     }
+
+    /// Reports an extern crate that rustc found unused, for the
+    /// `unused_dependencies` cargo lint.
+    pub fn unused_dependency(&self, extern_crate_name: String) {
+        self.messages
+            .push(Message::UnusedDependency(self.id, extern_crate_name));
+    }
 }
@@ -133,6 +133,7 @@ pub use self::job::{Job, Work};
 pub use self::job_state::JobState;
 use super::build_runner::OutputFile;
 use super::timings::Timings;
+use super::unused_dependencies::{self, UnusedDependencies};
 use super::{BuildContext, BuildPlan, BuildRunner, CompileMode, Unit};
 use crate::core::compiler::descriptive_pkg_name;
 use crate::core::compiler::future_incompat::{
@@ -200,6 +201,7 @@ struct DrainState<'gctx> {
     /// How many jobs we've finished
     finished: usize,
     per_package_future_incompat_reports: Vec<FutureIncompatReportPackage>,
+    unused_dependencies: UnusedDependencies,
 }
 
 /// Count of warnings, used to print a summary after the job succeeds
@@ -372,6 +374,7 @@ enum Message {
     Token(io::Result<Acquired>),
     Finish(JobId, Artifact, CargoResult<()>),
     FutureIncompatReport(JobId, Vec<FutureBreakageItem>),
+    UnusedDependency(JobId, String),
 }
 
 impl<'gctx> JobQueue<'gctx> {
@@ -503,6 +506,7 @@ impl<'gctx> JobQueue<'gctx> {
             ),
             finished: 0,
             per_package_future_incompat_reports: Vec::new(),
+            unused_dependencies: UnusedDependencies::default(),
         };
 
         // Create a helper thread for acquiring jobserver tokens
@@ -699,6 +703,10 @@ impl<'gctx> DrainState<'gctx> {
                 self.per_package_future_incompat_reports
                     .push(FutureIncompatReportPackage { package_id, items });
             }
+            Message::UnusedDependency(id, extern_crate_name) => {
+                self.unused_dependencies
+                    .unused_extern(id, extern_crate_name);
+            }
             Message::Token(acquired_token) => {
                 let token = acquired_token.context("failed to acquire jobserver token")?;
                 self.tokens.push(token);
@@ -788,6 +796,23 @@ impl<'gctx> DrainState<'gctx> {
         }
         self.progress.clear();
 
+        if errors.count == 0 {
+            let unused = std::mem::take(&mut self.unused_dependencies).finish();
+            // `cargo fix` removes the dependencies instead of reporting them.
+            if build_runner
+                .bcx
+                .build_config
+                .rustfix_diagnostic_server
+                .borrow()
+                .is_none()
+            {
+                if let Err(e) = unused_dependencies::emit_lints(build_runner, &unused) {
+                    self.handle_error(&mut build_runner.bcx.gctx.shell(), &mut errors, e);
+                }
+            }
+            build_runner.compilation.unused_dependencies = unused;
+        }
+
         let profile_name = build_runner.bcx.build_config.requested_profile;
         // NOTE: this may be a bit inaccurate, since this may not display the
         // profile for what was actually built. Profile overrides can change
@@ -1103,7 +1128,11 @@ impl<'gctx> DrainState<'gctx> {
         }
         let unlocked = self.queue.finish(unit, &artifact);
         match artifact {
-            Artifact::All => self.timings.unit_finished(id, unlocked),
+            Artifact::All => {
+                self.unused_dependencies
+                    .unit_finished(id, unit, build_runner);
+                self.timings.unit_finished(id, unlocked)
+            }
             Artifact::Metadata => self.timings.unit_rmeta_finished(id, unlocked),
         }
         Ok(())
@@ -53,6 +53,7 @@ mod timings;
 mod unit;
 pub mod unit_dependencies;
 pub mod unit_graph;
+pub mod unused_dependencies;
 
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
@@ -209,6 +210,7 @@ fn compile<'gctx>(
                 build_runner.files().message_cache_path(unit),
                 build_runner.bcx.build_config.message_format,
                 unit.show_warnings(bcx.gctx),
+                unused_dependencies::is_tracked(build_runner, unit),
             );
             // Need to link targets on both the dirty and fresh.
             work.then(link_targets(build_runner, unit, true)?)
@@ -1112,6 +1114,9 @@ fn build_base_args(
         trim_paths_args(cmd, build_runner, unit, &trim_paths)?;
     }
 
+    if unused_dependencies::is_tracked(build_runner, unit) {
+        cmd.arg("--force-warn").arg(unused_dependencies::RUSTC_LINT);
+    }
     cmd.args(unit.pkg.manifest().lint_rustflags());
     cmd.args(&profile_rustflags);
     if !cargo_rustc_higher_args_precedence(build_runner) {
@@ -1649,6 +1654,9 @@ struct OutputOptions {
     warnings_seen: usize,
     /// Tracks the number of errors we've seen so far.
     errors_seen: usize,
+    /// If `true`, capture the unused dependencies rustc reports for the
+    /// `unused_dependencies` cargo lint, instead of displaying them.
+    capture_unused_dependencies: bool,
 }
 
 impl OutputOptions {
@@ -1663,6 +1671,7 @@ impl OutputOptions {
             show_diagnostics: true,
             warnings_seen: 0,
             errors_seen: 0,
+            capture_unused_dependencies: unused_dependencies::is_tracked(build_runner, unit),
         }
     }
 }
@@ -1746,6 +1755,37 @@ fn on_stderr_line_inner(
         return Ok(true);
     }
 
+    if options.capture_unused_dependencies {
+        #[derive(serde::Deserialize)]
+        struct CompilerMessage<'a> {
+            #[serde(borrow)]
+            message: Cow<'a, str>,
+            #[serde(borrow)]
+            level: Cow<'a, str>,
+            code: Option<DiagnosticCode<'a>>,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct DiagnosticCode<'a> {
+            #[serde(borrow)]
+            code: Cow<'a, str>,
+        }
+
+        if let Ok(msg) = serde_json::from_str::<CompilerMessage<'_>>(compiler_message.get()) {
+            if msg.level == "warning"
+                && msg
+                    .code
+                    .is_some_and(|c| c.code == unused_dependencies::RUSTC_LINT_CODE)
+            {
+                // The message is like "extern crate `foo` is unused in crate `bar`".
+                if let Some(name) = msg.message.split('`').nth(1) {
+                    state.unused_dependency(name.to_string());
+                    return Ok(true);
+                }
+            }
+        }
+    }
+
     // Depending on what we're emitting from Cargo itself, we figure out what to
     // do with this JSON message.
     match options.format {
@@ -1921,6 +1961,7 @@ fn replay_output_cache(
     path: PathBuf,
     format: MessageFormat,
     show_diagnostics: bool,
+    capture_unused_dependencies: bool,
 ) -> Work {
     let target = target.clone();
     let mut options = OutputOptions {
@@ -1929,6 +1970,7 @@ fn replay_output_cache(
         show_diagnostics,
         warnings_seen: 0,
         errors_seen: 0,
+        capture_unused_dependencies,
     };
     Work::new(move |state| {
         if !path.exists() {
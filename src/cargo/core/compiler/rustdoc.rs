@@ -245,16 +245,19 @@ pub fn add_root_urls(
 }
 
 /// Adds unstable flag [`--output-format`][1] to the given `rustdoc`
-/// invocation. This is for unstable feature [`-Zunstable-features`].
+/// invocation. This is for unstable feature [`-Zunstable-features`], and
+/// [`-Zsemver-check`] which compares the JSON output of two versions of a
+/// package.
 ///
 /// [1]: https://doc.rust-lang.org/nightly/rustdoc/unstable-features.html?highlight=output-format#-w--output-format-output-format
+/// [`-Zsemver-check`]: https://doc.rust-lang.org/nightly/cargo/reference/unstable.html#semver-check
 pub fn add_output_format(
     build_runner: &BuildRunner<'_, '_>,
     unit: &Unit,
     rustdoc: &mut ProcessBuilder,
 ) -> CargoResult<()> {
     let gctx = build_runner.bcx.gctx;
-    if !gctx.cli_unstable().unstable_options {
+    if !gctx.cli_unstable().unstable_options && !gctx.cli_unstable().semver_check {
         tracing::debug!("`unstable-options` is ignored, required -Zunstable-options flag");
         return Ok(());
     }
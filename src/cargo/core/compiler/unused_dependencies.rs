@@ -0,0 +1,153 @@
+//! Support for the `unused_dependencies` cargo lint.
+//!
+//! For the units of workspace members that enable the lint, rustc is asked to
+//! report its `unused_crate_dependencies` lint, and the reports are captured
+//! instead of displayed. Once every unit is built, a dependency of a package is
+//! unused if every unit it was passed to left it unused.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::job_queue::JobId;
+use super::{BuildRunner, CompileMode, Unit};
+use crate::core::PackageId;
+use crate::util::interning::InternedString;
+use crate::util::lints::{self, LintLevel};
+use crate::util::CargoResult;
+
+/// The rustc lint reporting unused dependencies, passed with `--force-warn` so
+/// that `-D warnings` and `#![allow]` in the source don't change its level.
+pub const RUSTC_LINT: &str = "unused-crate-dependencies";
+
+/// The code rustc gives the diagnostics of [`RUSTC_LINT`].
+pub const RUSTC_LINT_CODE: &str = "unused_crate_dependencies";
+
+/// A dependency that none of the targets it was passed to used.
+#[derive(Debug, Clone)]
+pub struct UnusedDependency {
+    /// The name of the dependency in the manifest.
+    pub name: InternedString,
+    /// Whether it is a build dependency, rather than a normal or dev one.
+    pub build: bool,
+    /// Whether a unit of the package was built with all of its features, so
+    /// that no code behind a feature that wasn't built could use it.
+    pub all_features: bool,
+}
+
+/// Whether rustc should report the unused dependencies of `unit`.
+pub fn is_tracked(build_runner: &BuildRunner<'_, '_>, unit: &Unit) -> bool {
+    build_runner.bcx.gctx.cli_unstable().cargo_lints
+        && !unit.is_std
+        && matches!(
+            unit.mode,
+            CompileMode::Build | CompileMode::Check { .. } | CompileMode::Test | CompileMode::Bench
+        )
+        && build_runner.bcx.ws.is_member(&unit.pkg)
+        // The `[lints]` config was already loaded for the manifests of the
+        // members, so this doesn't fail.
+        && lints::unused_dependencies_level(&unit.pkg, build_runner.bcx.gctx)
+            .is_ok_and(|level| level != LintLevel::Allow)
+}
+
+/// Collects what rustc reports while units are built.
+#[derive(Default)]
+pub struct UnusedDependencies {
+    /// Extern crates that rustc reported as unused, by unit.
+    unused_externs: HashMap<JobId, HashSet<String>>,
+    /// For each package, whether any unit used each of its dependencies, keyed
+    /// by whether it is a build dependency and its name in the manifest.
+    used: BTreeMap<PackageId, BTreeMap<(bool, InternedString), bool>>,
+    /// For each package, whether any unit was built with all of its features.
+    all_features: HashMap<PackageId, bool>,
+}
+
+impl UnusedDependencies {
+    /// Records that rustc reported `extern_crate_name` as unused by unit `id`.
+    pub fn unused_extern(&mut self, id: JobId, extern_crate_name: String) {
+        self.unused_externs
+            .entry(id)
+            .or_default()
+            .insert(extern_crate_name);
+    }
+
+    /// Records which dependencies unit `id` used, once it finished building.
+    pub fn unit_finished(&mut self, id: JobId, unit: &Unit, build_runner: &BuildRunner<'_, '_>) {
+        let unused = self.unused_externs.remove(&id).unwrap_or_default();
+        if !is_tracked(build_runner, unit) {
+            return;
+        }
+        let build = unit.target.is_custom_build();
+        *self.all_features.entry(unit.pkg.package_id()).or_default() |= unit
+            .pkg
+            .summary()
+            .features()
+            .keys()
+            .all(|feature| unit.features.contains(feature));
+        let used = self.used.entry(unit.pkg.package_id()).or_default();
+        for dep in build_runner.unit_deps(unit) {
+            // Only libraries are passed with `--extern`, and the package's own
+            // library is not a dependency.
+            if dep.unit.pkg.package_id() == unit.pkg.package_id()
+                || !dep.unit.target.is_lib()
+                || dep.unit.mode.is_run_custom_build()
+            {
+                continue;
+            }
+            let name = dep.dep_name.unwrap_or_else(|| dep.unit.pkg.name());
+            *used.entry((build, name)).or_default() |=
+                !unused.contains(dep.extern_crate_name.as_str());
+        }
+    }
+
+    /// The dependencies of each package that no unit used.
+    pub fn finish(self) -> BTreeMap<PackageId, Vec<UnusedDependency>> {
+        let all_features = self.all_features;
+        self.used
+            .into_iter()
+            .map(|(pkg_id, used)| {
+                let all_features = all_features.get(&pkg_id).copied().unwrap_or_default();
+                let unused = used
+                    .into_iter()
+                    .filter(|(_, used)| !used)
+                    .map(|((build, name), _)| UnusedDependency {
+                        name,
+                        build,
+                        all_features,
+                    })
+                    .collect::<Vec<_>>();
+                (pkg_id, unused)
+            })
+            .filter(|(_, unused)| !unused.is_empty())
+            .collect()
+    }
+}
+
+/// Emits the `unused_dependencies` lint for the dependencies in `unused`.
+pub fn emit_lints(
+    build_runner: &BuildRunner<'_, '_>,
+    unused: &BTreeMap<PackageId, Vec<UnusedDependency>>,
+) -> CargoResult<()> {
+    let ws = build_runner.bcx.ws;
+    let mut error_count = 0;
+    for pkg in ws.members() {
+        if let Some(unused) = unused.get(&pkg.package_id()) {
+            lints::check_unused_dependencies(
+                ws,
+                pkg,
+                unused,
+                &mut error_count,
+                build_runner.bcx.build_config.message_format,
+                ws.gctx(),
+            )?;
+        }
+    }
+    if error_count > 0 {
+        Err(
+            crate::util::errors::AlreadyPrintedError::new(anyhow::anyhow!(
+                "encountered {error_count} errors(s) while running lints"
+            ))
+            .into(),
+        )
+    } else {
+        Ok(())
+    }
+}
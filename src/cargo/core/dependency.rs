@@ -41,6 +41,8 @@ struct Inner {
 
     optional: bool,
     public: bool,
+    /// Whether the revision of a git dependency must be signed.
+    verify_signature: bool,
     default_features: bool,
     features: Vec<InternedString>,
     // The presence of this information turns a dependency into an artifact dependency.
@@ -154,6 +156,7 @@ impl Dependency {
                 only_match_name: true,
                 optional: false,
                 public: false,
+                verify_signature: false,
                 features: Vec::new(),
                 default_features: true,
                 specified_req: false,
@@ -273,6 +276,17 @@ impl Dependency {
         self
     }
 
+    /// Returns `true` if the revision of this git dependency must be signed.
+    pub fn verify_signature(&self) -> bool {
+        self.inner.verify_signature
+    }
+
+    /// Sets whether the revision of this git dependency must be signed.
+    pub fn set_verify_signature(&mut self, verify_signature: bool) -> &mut Dependency {
+        Arc::make_mut(&mut self.inner).verify_signature = verify_signature;
+        self
+    }
+
     pub fn specified_req(&self) -> bool {
         self.inner.specified_req
     }
@@ -763,9 +763,11 @@ unstable_cli_options!(
     checksum_freshness: bool = ("Use a checksum to determine if output is fresh rather than filesystem mtime"),
     codegen_backend: bool = ("Enable the `codegen-backend` option in profiles in .cargo/config.toml file"),
     config_include: bool = ("Enable the `include` key in config files"),
+    config_lints: bool = ("Enable the `[lints]` table in config files"),
     direct_minimal_versions: bool = ("Resolve minimal dependency versions instead of maximum (direct dependencies only)"),
     doctest_xcompile: bool = ("Compile and run doctests for non-host target using runner config"),
     dual_proc_macros: bool = ("Build proc-macros for both the host and the target"),
+    encrypted_credentials: bool = ("Enable the `cargo:encrypted-file` credential provider"),
     features: Option<Vec<String>>,
     gc: bool = ("Track cache usage and \"garbage collect\" unused files"),
     #[serde(deserialize_with = "deserialize_git_features")]
@@ -782,14 +784,17 @@ unstable_cli_options!(
     panic_abort_tests: bool = ("Enable support to run tests with -Cpanic=abort"),
     profile_rustflags: bool = ("Enable the `rustflags` option in profiles in .cargo/config.toml file"),
     public_dependency: bool = ("Respect a dependency's `public` field in Cargo.toml to control public/private dependencies"),
+    publish_checks: bool = ("Enable the `publish.checks` key in .cargo/config.toml file"),
     publish_timeout: bool = ("Enable the `publish.timeout` key in .cargo/config.toml file"),
     rustdoc_map: bool = ("Allow passing external documentation mappings to rustdoc"),
     rustdoc_scrape_examples: bool = ("Allows Rustdoc to scrape code examples from reverse-dependencies"),
     script: bool = ("Enable support for single-file, `.rs` packages"),
+    semver_check: bool = ("Check the public API against the last published version when packaging"),
     separate_nightlies: bool,
     skip_rustdoc_fingerprint: bool,
     target_applies_to_host: bool = ("Enable the `target-applies-to-host` key in the .cargo/config.toml file"),
     trim_paths: bool = ("Enable the `trim-paths` option in profiles"),
+    trusted_publishing: bool = ("Enable the `cargo:trusted-publishing` credential provider"),
     unstable_options: bool = ("Allow the usage of unstable options"),
 );
 
@@ -888,18 +893,41 @@ pub struct GitFeatures {
     pub shallow_index: bool,
     /// When cloning git dependencies, perform a shallow clone and maintain shallowness on subsequent fetches.
     pub shallow_deps: bool,
+    /// When checking out git dependencies, only check out the directories of the requested
+    /// packages, their in-repository path dependencies and the workspace root manifest.
+    pub sparse_checkout: bool,
+    /// Honors `verify-signature` on git dependencies, requiring their revisions to be signed.
+    pub verify_signature: bool,
+    /// Before fetching a git dependency, asks the remote where its reference points to, and skips
+    /// the fetch if the database has that commit already.
+    pub ls_remote: bool,
 }
 
 impl GitFeatures {
+    /// The features enabled by a bare `-Zgit`.
+    ///
+    /// `sparse-checkout` is left out, as it breaks git dependencies reading
+    /// files outside of their package, and so is `verify-signature`, as it
+    /// makes builds fail on unsigned revisions, so both have to be asked for
+    /// explicitly.
     pub fn all() -> Self {
         GitFeatures {
             shallow_index: true,
             shallow_deps: true,
+            sparse_checkout: false,
+            verify_signature: false,
+            ls_remote: true,
         }
     }
 
     fn expecting() -> String {
-        let fields = vec!["`shallow-index`", "`shallow-deps`"];
+        let fields = vec![
+            "`shallow-index`",
+            "`shallow-deps`",
+            "`sparse-checkout`",
+            "`verify-signature`",
+            "`ls-remote`",
+        ];
         format!(
             "unstable 'git' only takes {} as valid inputs",
             fields.join(" and ")
@@ -963,12 +991,18 @@ fn parse_git(it: impl Iterator<Item = impl AsRef<str>>) -> CargoResult<Option<Gi
     let GitFeatures {
         shallow_index,
         shallow_deps,
+        sparse_checkout,
+        verify_signature,
+        ls_remote,
     } = &mut out;
 
     for e in it {
         match e.as_ref() {
             "shallow-index" => *shallow_index = true,
             "shallow-deps" => *shallow_deps = true,
+            "sparse-checkout" => *sparse_checkout = true,
+            "verify-signature" => *verify_signature = true,
+            "ls-remote" => *ls_remote = true,
             _ => {
                 bail!(GitFeatures::expecting())
             }
@@ -1258,9 +1292,11 @@ impl CliUnstable {
             "cargo-lints" => self.cargo_lints = parse_empty(k, v)?,
             "codegen-backend" => self.codegen_backend = parse_empty(k, v)?,
             "config-include" => self.config_include = parse_empty(k, v)?,
+            "config-lints" => self.config_lints = parse_empty(k, v)?,
             "direct-minimal-versions" => self.direct_minimal_versions = parse_empty(k, v)?,
             "doctest-xcompile" => self.doctest_xcompile = parse_empty(k, v)?,
             "dual-proc-macros" => self.dual_proc_macros = parse_empty(k, v)?,
+            "encrypted-credentials" => self.encrypted_credentials = parse_empty(k, v)?,
             "gc" => self.gc = parse_empty(k, v)?,
             "git" => {
                 self.git = v.map_or_else(
@@ -1286,6 +1322,8 @@ impl CliUnstable {
             "public-dependency" => self.public_dependency = parse_empty(k, v)?,
             "profile-rustflags" => self.profile_rustflags = parse_empty(k, v)?,
             "trim-paths" => self.trim_paths = parse_empty(k, v)?,
+            "trusted-publishing" => self.trusted_publishing = parse_empty(k, v)?,
+            "publish-checks" => self.publish_checks = parse_empty(k, v)?,
             "publish-timeout" => self.publish_timeout = parse_empty(k, v)?,
             "rustdoc-map" => self.rustdoc_map = parse_empty(k, v)?,
             "rustdoc-scrape-examples" => self.rustdoc_scrape_examples = parse_empty(k, v)?,
@@ -1293,6 +1331,7 @@ impl CliUnstable {
             "checksum-freshness" => self.checksum_freshness = parse_empty(k, v)?,
             "skip-rustdoc-fingerprint" => self.skip_rustdoc_fingerprint = parse_empty(k, v)?,
             "script" => self.script = parse_empty(k, v)?,
+            "semver-check" => self.semver_check = parse_empty(k, v)?,
             "target-applies-to-host" => self.target_applies_to_host = parse_empty(k, v)?,
             "unstable-options" => self.unstable_options = parse_empty(k, v)?,
             _ => bail!("\
@@ -33,8 +33,10 @@ use crate::util::errors::{CargoResult, HttpNotSuccessful};
 use crate::util::interning::InternedString;
 use crate::util::network::http::http_handle_and_timeout;
 use crate::util::network::http::HttpTimeout;
+use crate::util::network::http::{configure_registry_client_cert, explain_curl_error};
 use crate::util::network::retry::{Retry, RetryResult};
 use crate::util::network::sleep::SleepTracker;
+use crate::util::network::stats::RequestKind;
 use crate::util::{self, internal, GlobalContext, Progress, ProgressStyle};
 
 /// Information about a package that is available somewhere in the file system.
@@ -690,6 +692,7 @@ impl<'a, 'gctx> Downloads<'a, 'gctx> {
         let source = sources
             .get_mut(id.source_id())
             .ok_or_else(|| internal(format!("couldn't find source for `{}`", id)))?;
+        let registry_id = source.replaced_source_id();
         let pkg = source
             .download(id)
             .context("unable to get packages from source")?;
@@ -716,6 +719,7 @@ impl<'a, 'gctx> Downloads<'a, 'gctx> {
         assert!(self.pending_ids.insert(id));
 
         let (mut handle, _timeout) = http_handle_and_timeout(self.set.gctx)?;
+        configure_registry_client_cert(self.set.gctx, &mut handle, registry_id)?;
         handle.get(true)?;
         handle.url(&url)?;
         handle.follow_location(true)?; // follow redirects
@@ -773,6 +777,7 @@ impl<'a, 'gctx> Downloads<'a, 'gctx> {
             self.set.gctx.shell().status("Downloading", "crates ...")?;
         }
 
+        let retry = Retry::new(self.set.gctx)?.with_url(&url);
         let dl = Download {
             token,
             data: RefCell::new(Vec::new()),
@@ -784,7 +789,7 @@ impl<'a, 'gctx> Downloads<'a, 'gctx> {
             current: Cell::new(0),
             start: Instant::now(),
             timed_out: Cell::new(None),
-            retry: Retry::new(self.set.gctx)?,
+            retry,
         };
         self.enqueue(dl, handle)?;
         self.tick(WhyTick::DownloadStarted)?;
@@ -820,6 +825,13 @@ impl<'a, 'gctx> Downloads<'a, 'gctx> {
             let headers = mem::take(&mut *dl.headers.borrow_mut());
             let mut handle = self.set.multi.remove(handle)?;
             self.pending_ids.remove(&dl.id);
+            self.set.gctx.network_stats().record_request(
+                &dl.url,
+                RequestKind::Crate,
+                data.len() as u64,
+                handle.total_time().unwrap_or_default(),
+                false,
+            );
 
             // Check if this was a spurious error. If it was a spurious error
             // then we want to re-enqueue our request for another attempt and
@@ -837,7 +849,7 @@ impl<'a, 'gctx> Downloads<'a, 'gctx> {
                         // it's flagged as spurious) and then attach our extra
                         // information to the error.
                         if !e.is_aborted_by_callback() {
-                            return Err(e.into());
+                            return Err(explain_curl_error(e));
                         }
 
                         return Err(match timed_out.replace(None) {
@@ -733,8 +733,15 @@ impl<'gctx> Registry for PackageRegistry<'gctx> {
                             return;
                         }
                     }
-                    let summary = summary.into_summary();
-                    f(IndexSummary::Candidate(lock(locked, all_patches, summary)))
+                    // Only keep the yank status when it was asked for, the
+                    // resolver treats everything it gets as a candidate.
+                    if kind == QueryKind::ExactWithYanked && summary.is_yanked() {
+                        let summary = summary.into_summary();
+                        f(IndexSummary::Yanked(lock(locked, all_patches, summary)))
+                    } else {
+                        let summary = summary.into_summary();
+                        f(IndexSummary::Candidate(lock(locked, all_patches, summary)))
+                    }
                 };
                 return source.query(dep, kind, callback);
             }
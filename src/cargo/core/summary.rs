@@ -1,4 +1,4 @@
-use crate::core::{Dependency, PackageId, SourceId};
+use crate::core::{Dependency, PackageId, Shell, SourceId};
 use crate::util::interning::InternedString;
 use crate::util::CargoResult;
 use anyhow::bail;
@@ -28,6 +28,31 @@ struct Inner {
     checksum: Option<String>,
     links: Option<InternedString>,
     rust_version: Option<RustVersion>,
+    pubtime: Option<String>,
+    yank_reason: Option<YankReason>,
+}
+
+/// Why a version was yanked, as recorded by its registry.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct YankReason {
+    /// A short message from the author of the yank.
+    pub reason: Option<String>,
+    /// A URL of an advisory describing the problem.
+    pub advisory: Option<String>,
+}
+
+impl YankReason {
+    /// Adds notes with the reason and advisory, if any, after a warning
+    /// about a yanked version.
+    pub fn note(&self, shell: &mut Shell) -> CargoResult<()> {
+        if let Some(reason) = &self.reason {
+            shell.note(format!("yank reason: {reason}"))?;
+        }
+        if let Some(advisory) = &self.advisory {
+            shell.note(format!("yank advisory: {advisory}"))?;
+        }
+        Ok(())
+    }
 }
 
 /// Indicates the dependency inferred from the `dep` syntax that should exist,
@@ -89,6 +114,8 @@ impl Summary {
                 checksum: None,
                 links: links.map(|l| l.into()),
                 rust_version,
+                pubtime: None,
+                yank_reason: None,
             }),
         })
     }
@@ -132,6 +159,26 @@ impl Summary {
         Arc::make_mut(&mut self.inner).checksum = Some(cksum);
     }
 
+    /// When this version was published to its registry, in RFC 3339 format,
+    /// if the registry records it.
+    pub fn pubtime(&self) -> Option<&str> {
+        self.inner.pubtime.as_deref()
+    }
+
+    pub fn set_pubtime(&mut self, pubtime: String) {
+        Arc::make_mut(&mut self.inner).pubtime = Some(pubtime);
+    }
+
+    /// Why this version was yanked, if it is yanked and its registry
+    /// recorded a reason.
+    pub fn yank_reason(&self) -> Option<&YankReason> {
+        self.inner.yank_reason.as_ref()
+    }
+
+    pub fn set_yank_reason(&mut self, yank_reason: YankReason) {
+        Arc::make_mut(&mut self.inner).yank_reason = Some(yank_reason);
+    }
+
     pub fn map_dependencies<F>(self, mut f: F) -> Summary
     where
         F: FnMut(Dependency) -> Dependency,
@@ -10,11 +10,11 @@ use itertools::Itertools;
 use tracing::debug;
 use url::Url;
 
-use crate::core::compiler::Unit;
+use crate::core::compiler::{MessageFormat, Unit};
 use crate::core::features::Features;
 use crate::core::registry::PackageRegistry;
 use crate::core::resolver::features::CliFeatures;
-use crate::core::resolver::ResolveBehavior;
+use crate::core::resolver::{Resolve, ResolveBehavior};
 use crate::core::{
     Dependency, Edition, FeatureValue, PackageId, PackageIdSpec, PackageIdSpecQuery,
 };
@@ -24,7 +24,13 @@ use crate::sources::{PathSource, SourceConfigMap, CRATES_IO_INDEX, CRATES_IO_REG
 use crate::util::edit_distance;
 use crate::util::errors::{CargoResult, ManifestError};
 use crate::util::interning::InternedString;
-use crate::util::lints::{analyze_cargo_lints_table, check_im_a_teapot};
+use crate::util::lints::{
+    analyze_cargo_lints_table, check_divergent_dependency_versions,
+    check_feature_enables_dev_dependency, check_im_a_teapot,
+    check_incompatible_dependency_rust_version, check_ineffective_weak_dependency_feature,
+    check_shadowed_optional_dependency, check_unused_features, find_divergent_dependencies,
+    needs_incompatible_dependency_rust_version, DivergentDependency,
+};
 use crate::util::toml::{read_manifest, InheritableFields};
 use crate::util::{
     context::CargoResolverConfig, context::ConfigRelativePath, context::IncompatibleRustVersions,
@@ -1152,12 +1158,37 @@ impl<'gctx> Workspace<'gctx> {
         }
     }
 
-    pub fn emit_warnings(&self) -> CargoResult<()> {
+    /// Emits the warnings of the manifests and the cargo lints, which follow
+    /// `message_format`.
+    pub fn emit_warnings(&self, message_format: MessageFormat) -> CargoResult<()> {
+        // Workspace-wide lints are only computed once for all members.
+        let divergent = if self.gctx.cli_unstable().cargo_lints {
+            find_divergent_dependencies(self)
+        } else {
+            Vec::new()
+        };
+        let mut resolved = None;
+        if self.gctx.cli_unstable().cargo_lints {
+            let mut needs_resolve = false;
+            for maybe_pkg in self.packages.packages.values() {
+                if let MaybePackage::Package(pkg) = maybe_pkg {
+                    needs_resolve |= needs_incompatible_dependency_rust_version(pkg, self.gctx)?;
+                }
+            }
+            if needs_resolve {
+                let mut registry = self.package_registry()?;
+                let resolve = ops::resolve_with_registry(self, &mut registry, false)?;
+                resolved = Some((resolve, registry));
+            }
+        }
         for (path, maybe_pkg) in &self.packages.packages {
             let path = path.join("Cargo.toml");
             if let MaybePackage::Package(pkg) = maybe_pkg {
                 if self.gctx.cli_unstable().cargo_lints {
-                    self.emit_lints(pkg, &path)?
+                    let resolved = resolved
+                        .as_mut()
+                        .map(|(resolve, registry)| (&*resolve, registry));
+                    self.emit_lints(pkg, &path, &divergent, resolved, message_format)?
                 }
             }
             let warnings = match maybe_pkg {
@@ -1185,7 +1216,14 @@ impl<'gctx> Workspace<'gctx> {
         Ok(())
     }
 
-    pub fn emit_lints(&self, pkg: &Package, path: &Path) -> CargoResult<()> {
+    pub fn emit_lints(
+        &self,
+        pkg: &Package,
+        path: &Path,
+        divergent: &[DivergentDependency],
+        resolved: Option<(&Resolve, &mut PackageRegistry<'gctx>)>,
+        message_format: MessageFormat,
+    ) -> CargoResult<()> {
         let mut error_count = 0;
         let toml_lints = pkg
             .manifest()
@@ -1216,9 +1254,63 @@ impl<'gctx> Workspace<'gctx> {
             ws_contents,
             ws_document,
             self.root_manifest(),
+            message_format,
+            self.gctx,
+        )?;
+        check_divergent_dependency_versions(
+            divergent,
+            pkg,
+            &cargo_lints,
+            &mut error_count,
+            message_format,
+            self.gctx,
+        )?;
+        check_feature_enables_dev_dependency(
+            pkg,
+            &cargo_lints,
+            &mut error_count,
+            message_format,
+            self.gctx,
+        )?;
+        check_im_a_teapot(
+            pkg,
+            &cargo_lints,
+            &mut error_count,
+            message_format,
+            self.gctx,
+        )?;
+        if let Some((resolve, registry)) = resolved {
+            check_incompatible_dependency_rust_version(
+                pkg,
+                resolve,
+                registry,
+                &mut error_count,
+                message_format,
+                self.gctx,
+            )?;
+        }
+        check_ineffective_weak_dependency_feature(
+            pkg,
+            &cargo_lints,
+            &mut error_count,
+            message_format,
+            self.gctx,
+        )?;
+        check_shadowed_optional_dependency(
+            pkg,
+            &cargo_lints,
+            &mut error_count,
+            message_format,
+            self.gctx,
+        )?;
+        check_unused_features(
+            self,
+            pkg,
+            &cargo_lints,
+            &mut error_count,
+            message_format,
             self.gctx,
         )?;
-        check_im_a_teapot(pkg, &path, &cargo_lints, &mut error_count, self.gctx)?;
         if error_count > 0 {
             Err(crate::util::errors::AlreadyPrintedError::new(anyhow!(
                 "encountered {error_count} errors(s) while running lints"
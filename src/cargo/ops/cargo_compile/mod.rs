@@ -137,7 +137,7 @@ pub fn compile_with_exec<'a>(
     options: &CompileOptions,
     exec: &Arc<dyn Executor>,
 ) -> CargoResult<Compilation<'a>> {
-    ws.emit_warnings()?;
+    ws.emit_warnings(options.build_config.message_format)?;
     compile_ws(ws, options, exec)
 }
 
@@ -1,16 +1,29 @@
 use crate::core::compiler::standard_lib;
-use crate::core::compiler::{BuildConfig, CompileMode, RustcTargetData};
+use crate::core::compiler::{BuildConfig, CompileMode, MessageFormat, RustcTargetData};
 use crate::core::{PackageSet, Resolve, Workspace};
 use crate::ops;
 use crate::util::context::JobsConfig;
 use crate::util::CargoResult;
 use crate::util::GlobalContext;
+use crate::{drop_println, util};
+use bytesize::ByteSize;
 use std::collections::HashSet;
 
 pub struct FetchOptions<'a> {
     pub gctx: &'a GlobalContext,
     /// The target arch triple to fetch dependencies for
     pub targets: Vec<String>,
+    /// Print a summary of the network activity once done, if set.
+    pub stats: Option<FetchStatsFormat>,
+}
+
+/// Output format of the network summary of `cargo fetch --stats`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FetchStatsFormat {
+    /// A table for humans.
+    Human,
+    /// A single JSON object.
+    Json,
 }
 
 /// Executes `cargo fetch`.
@@ -18,7 +31,7 @@ pub fn fetch<'a>(
     ws: &Workspace<'a>,
     options: &FetchOptions<'a>,
 ) -> CargoResult<(Resolve, PackageSet<'a>)> {
-    ws.emit_warnings()?;
+    ws.emit_warnings(MessageFormat::Human)?;
     let dry_run = false;
     let (mut packages, resolve) = ops::resolve_ws(ws, dry_run)?;
 
@@ -74,5 +87,80 @@ pub fn fetch<'a>(
     packages.get_many(to_download)?;
     crate::core::gc::auto_gc(gctx);
 
+    if let Some(format) = options.stats {
+        print_stats(gctx, format)?;
+    }
+
     Ok((resolve, packages))
 }
+
+/// Prints the [`NetworkStats`] collected while fetching.
+///
+/// [`NetworkStats`]: crate::util::network::stats::NetworkStats
+fn print_stats(gctx: &GlobalContext, format: FetchStatsFormat) -> CargoResult<()> {
+    let stats = gctx.network_stats();
+    match format {
+        FetchStatsFormat::Json => {
+            #[derive(serde::Serialize)]
+            struct HostEntry<'a> {
+                host: &'a str,
+                #[serde(flatten)]
+                stats: &'a util::network::stats::HostStats,
+            }
+            #[derive(serde::Serialize)]
+            struct Report<'a> {
+                hosts: Vec<HostEntry<'a>>,
+                total: util::network::stats::HostStats,
+            }
+            let report = Report {
+                hosts: stats
+                    .hosts()
+                    .map(|(host, stats)| HostEntry { host, stats })
+                    .collect(),
+                total: stats.total(),
+            };
+            gctx.shell().print_json(&report)?;
+        }
+        FetchStatsFormat::Human => {
+            if stats.is_empty() {
+                gctx.shell()
+                    .note("no network requests were made, everything was cached locally")?;
+                return Ok(());
+            }
+            let total = stats.total();
+            let rows = stats
+                .hosts()
+                .chain(std::iter::once(("total", &total)))
+                .map(|(host, s)| {
+                    [
+                        host.to_string(),
+                        s.index_requests.to_string(),
+                        s.cache_hits.to_string(),
+                        s.crate_requests.to_string(),
+                        s.retries.to_string(),
+                        ByteSize(s.bytes).to_string(),
+                        util::elapsed(s.duration),
+                    ]
+                })
+                .collect::<Vec<_>>();
+            let header = [
+                "host", "index", "cached", "crates", "retries", "bytes", "time",
+            ]
+            .map(String::from);
+            let mut widths = header.each_ref().map(|h| h.len());
+            for row in &rows {
+                for (width, cell) in widths.iter_mut().zip(row) {
+                    *width = (*width).max(cell.len());
+                }
+            }
+            for row in std::iter::once(&header).chain(&rows) {
+                let mut line = format!("{:<width$}", row[0], width = widths[0]);
+                for (cell, width) in row.iter().zip(widths).skip(1) {
+                    line.push_str(&format!("  {cell:>width$}"));
+                }
+                drop_println!(gctx, "{}", line);
+            }
+        }
+    }
+    Ok(())
+}
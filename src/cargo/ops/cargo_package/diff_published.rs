@@ -0,0 +1,129 @@
+//! Compares the contents of a package with its last published version, for
+//! `cargo package --diff-published`.
+
+use std::collections::{BTreeMap, HashSet};
+use std::io::prelude::*;
+use std::io::SeekFrom;
+
+use cargo_util::paths;
+use flate2::read::GzDecoder;
+use similar::TextDiff;
+use tar::Archive;
+
+use crate::core::{Package, Workspace};
+use crate::drop_println;
+use crate::sources::registry::crate_file_path;
+use crate::sources::SourceConfigMap;
+use crate::util::errors::CargoResult;
+use crate::util::{FileLock, GlobalContext};
+
+use super::{latest_published, PackageOpts};
+
+/// Prints the files added, removed and modified in `tarball` compared to the
+/// `.crate` file of the latest published version of `pkg`, followed by the
+/// changes to the normalized `Cargo.toml`.
+pub(super) fn diff_published(
+    ws: &Workspace<'_>,
+    pkg: &Package,
+    tarball: &FileLock,
+    opts: &PackageOpts<'_>,
+) -> CargoResult<()> {
+    let gctx = ws.gctx();
+    let Some(published) = latest_published(gctx, pkg, opts)? else {
+        gctx.shell().note(format!(
+            "no version of `{}` lower than {} is published, nothing to compare with",
+            pkg.name(),
+            pkg.version()
+        ))?;
+        return Ok(());
+    };
+    gctx.shell().status(
+        "Comparing",
+        format!("contents of {} to {}", pkg, published.package_id()),
+    )?;
+
+    let old_files = published_files(gctx, &published)?;
+    let mut new_tarball = Vec::new();
+    let mut file = tarball.file();
+    file.seek(SeekFrom::Start(0))?;
+    file.read_to_end(&mut new_tarball)?;
+    let new_files = crate_files(pkg, &new_tarball)?;
+
+    let mut changed = false;
+    let mut paths = old_files.keys().chain(new_files.keys()).collect::<Vec<_>>();
+    paths.sort();
+    paths.dedup();
+    for path in paths {
+        let change = match (old_files.get(path), new_files.get(path)) {
+            (None, Some(_)) => "added",
+            (Some(_), None) => "removed",
+            (Some(old), Some(new)) if old != new => "modified",
+            _ => continue,
+        };
+        changed = true;
+        drop_println!(gctx, "{change}: {path}");
+    }
+    if !changed {
+        gctx.shell().note(format!(
+            "the contents of {} are identical to {}",
+            pkg,
+            published.package_id()
+        ))?;
+        return Ok(());
+    }
+
+    let old_manifest = old_files
+        .get("Cargo.toml")
+        .map(|c| String::from_utf8_lossy(c));
+    let new_manifest = new_files
+        .get("Cargo.toml")
+        .map(|c| String::from_utf8_lossy(c));
+    if let (Some(old), Some(new)) = (old_manifest, new_manifest) {
+        if old != new {
+            let old_name = format!("{}-{}/Cargo.toml", published.name(), published.version());
+            let new_name = format!("{}-{}/Cargo.toml", pkg.name(), pkg.version());
+            let diff = TextDiff::from_lines(old.as_ref(), new.as_ref());
+            let diff = diff.unified_diff().header(&old_name, &new_name).to_string();
+            drop_println!(gctx, "");
+            drop_println!(gctx, "{}", diff.trim_end());
+        }
+    }
+    Ok(())
+}
+
+/// Reads the files of the `.crate` file of `published`, as it was downloaded
+/// from the registry.
+fn published_files(
+    gctx: &GlobalContext,
+    published: &Package,
+) -> CargoResult<BTreeMap<String, Vec<u8>>> {
+    let pkg_id = published.package_id();
+    let source_id = SourceConfigMap::new(gctx)?
+        .load(pkg_id.source_id(), &HashSet::new())?
+        .replaced_source_id();
+    let Some(path) = crate_file_path(gctx, source_id, pkg_id) else {
+        anyhow::bail!("cannot compare with {pkg_id}, {source_id} has no `.crate` files");
+    };
+    crate_files(published, &paths::read_bytes(&path)?)
+}
+
+/// Reads the files of the `.crate` file of `pkg` in `bytes`, keyed by their
+/// path in the package.
+fn crate_files(pkg: &Package, bytes: &[u8]) -> CargoResult<BTreeMap<String, Vec<u8>>> {
+    let base_name = format!("{}-{}", pkg.name(), pkg.version());
+    let mut files = BTreeMap::new();
+    let mut archive = Archive::new(GzDecoder::new(bytes));
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        let rel_path = path.strip_prefix(&base_name).unwrap_or(&path);
+        let rel_str = rel_path
+            .to_str()
+            .ok_or_else(|| anyhow::format_err!("non-utf8 path in archive: {}", path.display()))?
+            .replace('\\', "/");
+        let mut contents = Vec::new();
+        entry.read_to_end(&mut contents)?;
+        files.insert(rel_str, contents);
+    }
+    Ok(files)
+}
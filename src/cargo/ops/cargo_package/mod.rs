@@ -9,14 +9,16 @@ use std::task::Poll;
 use crate::core::compiler::{BuildConfig, CompileMode, DefaultExecutor, Executor};
 use crate::core::dependency::DepKind;
 use crate::core::manifest::Target;
+use crate::core::registry::PackageRegistry;
 use crate::core::resolver::CliFeatures;
 use crate::core::resolver::HasDevUnits;
+use crate::core::{Dependency, Package, PackageId, PackageSet, Registry as _, Resolve, SourceId};
 use crate::core::{Feature, PackageIdSpecQuery, Shell, Verbosity, Workspace};
-use crate::core::{Package, PackageId, PackageSet, Resolve, SourceId};
 use crate::ops::lockfile::LOCKFILE_NAME;
 use crate::ops::registry::{infer_registry, RegistryOrIndex};
 use crate::sources::registry::index::{IndexPackage, RegistryDependency};
-use crate::sources::{PathSource, CRATES_IO_REGISTRY};
+use crate::sources::source::QueryKind;
+use crate::sources::{IndexSummary, PathSource, SourceConfigMap, CRATES_IO_REGISTRY};
 use crate::util::cache_lock::CacheLockMode;
 use crate::util::context::JobsConfig;
 use crate::util::errors::CargoResult;
@@ -29,11 +31,15 @@ use anyhow::{bail, Context as _};
 use cargo_util::paths;
 use flate2::read::GzDecoder;
 use flate2::{Compression, GzBuilder};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tar::{Archive, Builder, EntryType, Header, HeaderMode};
 use tracing::debug;
 use unicase::Ascii as UncasedAscii;
 
+mod diff_published;
+mod reproducible;
+mod semver_check;
+
 #[derive(Clone)]
 pub struct PackageOpts<'gctx> {
     pub gctx: &'gctx GlobalContext,
@@ -47,11 +53,14 @@ pub struct PackageOpts<'gctx> {
     pub targets: Vec<String>,
     pub cli_features: CliFeatures,
     pub reg_or_index: Option<ops::RegistryOrIndex>,
+    pub reproducible_check: bool,
+    pub diff_published: bool,
 }
 
 const ORIGINAL_MANIFEST_FILE: &str = "Cargo.toml.orig";
 const VCS_INFO_FILE: &str = ".cargo_vcs_info.json";
 
+#[derive(Clone)]
 struct ArchiveFile {
     /// The relative path in the archive (not including the top-level package
     /// name directory).
@@ -62,6 +71,7 @@ struct ArchiveFile {
     contents: FileContents,
 }
 
+#[derive(Clone)]
 enum FileContents {
     /// Absolute path to the file on disk to add to the archive.
     OnDisk(PathBuf),
@@ -69,6 +79,7 @@ enum FileContents {
     Generated(GeneratedFile),
 }
 
+#[derive(Clone)]
 enum GeneratedFile {
     /// Generates `Cargo.toml` by rewriting the original.
     Manifest,
@@ -78,18 +89,18 @@ enum GeneratedFile {
     VcsInfo(VcsInfo),
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Serialize, Deserialize)]
 struct VcsInfo {
     git: GitVcsInfo,
     /// Path to the package within repo (empty string if root). / not \
     path_in_vcs: String,
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Serialize, Deserialize)]
 struct GitVcsInfo {
     sha1: String,
     /// Indicate whether or not the Git worktree is dirty.
-    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     dirty: bool,
 }
 
@@ -125,12 +136,13 @@ fn create_package(
     let uncompressed_size = tar(ws, pkg, local_reg, ar_files, dst.file(), &filename)
         .context("failed to prepare local package for uploading")?;
 
-    dst.seek(SeekFrom::Start(0))?;
     let src_path = dst.path();
     let dst_path = dst.parent().join(&filename);
     fs::rename(&src_path, &dst_path)
         .context("failed to move temporary tarball into final location")?;
 
+    dst.seek(SeekFrom::Start(0))?;
+
     let dst_metadata = dst
         .file()
         .metadata()
@@ -249,7 +261,16 @@ fn do_package<'a>(
                 drop_println!(ws.gctx(), "{}", ar_file.rel_str);
             }
         } else {
-            let tarball = create_package(ws, &pkg, ar_files, local_reg.as_ref())?;
+            if ws.gctx().cli_unstable().semver_check {
+                semver_check::check_semver(ws, &pkg, &opts)?;
+            }
+            let tarball = create_package(ws, &pkg, ar_files.clone(), local_reg.as_ref())?;
+            if opts.diff_published {
+                diff_published::diff_published(ws, &pkg, &tarball, &opts)?;
+            }
+            if opts.reproducible_check {
+                reproducible::check_reproducible(ws, &pkg, ar_files, local_reg.as_ref(), &tarball)?;
+            }
             if let Some(local_reg) = local_reg.as_mut() {
                 if pkg.publish() != &Some(Vec::new()) {
                     local_reg.add_package(ws, &pkg, &tarball)?;
@@ -311,6 +332,48 @@ fn get_registry(
     Ok(ops::registry::get_source_id(gctx, reg_or_index.as_ref())?.replacement)
 }
 
+/// Finds the latest non-yanked release of `pkg` in the registry it is packaged
+/// for, which is lower than the version being packaged, and downloads it.
+///
+/// Pre-releases are skipped, so they are never used as the baseline.
+fn latest_published(
+    gctx: &GlobalContext,
+    pkg: &Package,
+    opts: &PackageOpts<'_>,
+) -> CargoResult<Option<Package>> {
+    let reg_or_index = match opts.reg_or_index.clone() {
+        Some(r) => Some(r),
+        None => infer_registry(&[pkg])?,
+    };
+    let source_id = ops::registry::get_initial_source_id(gctx, reg_or_index.as_ref())?;
+    let source_config = SourceConfigMap::new(gctx)?;
+    let mut registry = PackageRegistry::new_with_source_config(gctx, source_config)?;
+    let _lock = gctx.acquire_package_cache_lock(CacheLockMode::DownloadExclusive)?;
+    registry.lock_patches();
+
+    let dep = Dependency::parse(pkg.name(), None, source_id)?;
+    let summaries = loop {
+        match registry.query_vec(&dep, QueryKind::Exact)? {
+            Poll::Ready(summaries) => break summaries,
+            Poll::Pending => registry.block_until_ready()?,
+        }
+    };
+    let latest = summaries
+        .iter()
+        .filter_map(|s| match s {
+            IndexSummary::Candidate(s) => Some(s.package_id()),
+            _ => None,
+        })
+        .filter(|id| id.version().pre.is_empty() && id.version() < pkg.version())
+        .max_by(|a, b| a.version().cmp(b.version()));
+    let Some(latest) = latest else {
+        return Ok(None);
+    };
+
+    let pkg_set = registry.get(&[latest])?;
+    Ok(Some(pkg_set.get_one(latest)?.clone()))
+}
+
 /// Just the part of the dependency graph that's between the packages we're packaging.
 #[derive(Clone, Debug, Default)]
 pub(crate) struct LocalDependencies<T> {
@@ -942,11 +1005,7 @@ fn tar(
                 uncompressed_size += metadata.len() as u64;
             }
             FileContents::Generated(generated_kind) => {
-                let contents = match generated_kind {
-                    GeneratedFile::Manifest => publish_pkg.manifest().to_normalized_contents()?,
-                    GeneratedFile::Lockfile => build_lock(ws, &publish_pkg, local_reg)?,
-                    GeneratedFile::VcsInfo(ref s) => serde_json::to_string_pretty(s)?,
-                };
+                let contents = generated_contents(ws, &publish_pkg, local_reg, &generated_kind)?;
                 header.set_entry_type(EntryType::file());
                 header.set_mode(0o644);
                 header.set_size(contents.len() as u64);
@@ -965,6 +1024,20 @@ fn tar(
     Ok(uncompressed_size)
 }
 
+/// Generates the contents of a [`GeneratedFile`] of `publish_pkg`.
+fn generated_contents(
+    ws: &Workspace<'_>,
+    publish_pkg: &Package,
+    local_reg: Option<&TmpRegistry<'_>>,
+    generated: &GeneratedFile,
+) -> CargoResult<String> {
+    Ok(match generated {
+        GeneratedFile::Manifest => publish_pkg.manifest().to_normalized_contents()?,
+        GeneratedFile::Lockfile => build_lock(ws, publish_pkg, local_reg)?,
+        GeneratedFile::VcsInfo(s) => serde_json::to_string_pretty(s)?,
+    })
+}
+
 /// Generate warnings when packaging Cargo.lock, and the resolve have changed.
 fn compare_resolve(
     gctx: &GlobalContext,
@@ -1071,7 +1144,7 @@ pub fn check_yanked(
     while !pending.is_empty() {
         pending.retain(|pkg_id| {
             if let Some(source) = sources.get_mut(pkg_id.source_id()) {
-                match source.is_yanked(*pkg_id) {
+                match source.yank_reason(*pkg_id) {
                     Poll::Ready(result) => results.push((*pkg_id, result)),
                     Poll::Pending => return true,
                 }
@@ -1083,14 +1156,16 @@ pub fn check_yanked(
         }
     }
 
-    for (pkg_id, is_yanked) in results {
-        if is_yanked? {
-            gctx.shell().warn(format!(
+    for (pkg_id, yank_reason) in results {
+        if let Some(yank_reason) = yank_reason? {
+            let mut shell = gctx.shell();
+            shell.warn(format!(
                 "package `{}` in Cargo.lock is yanked in registry `{}`, {}",
                 pkg_id,
                 pkg_id.source_id().display_registry_name(),
                 hint
             ))?;
+            yank_reason.note(&mut shell)?;
         }
     }
     Ok(())
@@ -1384,6 +1459,9 @@ impl<'a> TmpRegistry<'a> {
             yanked: None,
             links: new_crate.links.map(|x| x.into()),
             rust_version: None,
+            pubtime: None,
+            yank_reason: None,
+            yank_advisory: None,
             v: Some(2),
         })?;
 
@@ -0,0 +1,243 @@
+//! Reproducibility of `.crate` files.
+//!
+//! `cargo package --reproducible-check` repackages the files of a package
+//! from copies in different directories, with different modification times
+//! and permissions, and fails if any resulting archive differs from the
+//! `.crate` file.
+//!
+//! Of those, only the executable bit of the owner of a file ends up in the
+//! archive, so the check fails for packages with executable files, which
+//! would be packaged differently from a file system without executable bits.
+//!
+//! Once the check passes, the `.crate` file is accompanied by a digest
+//! manifest, a JSON file with the SHA-256 of the archive, of each file in it
+//! and the VCS commit it was packaged from, so the archive can be compared
+//! with one built elsewhere.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::SeekFrom;
+use std::path::Path;
+
+use anyhow::Context as _;
+use cargo_util::paths;
+use cargo_util::Sha256;
+use filetime::FileTime;
+use flate2::read::GzDecoder;
+use serde::Serialize;
+use tar::Archive;
+
+use crate::core::{Package, Workspace};
+use crate::util::errors::CargoResult;
+use crate::util::FileLock;
+
+use super::{tar, ArchiveFile, FileContents, TmpRegistry, VcsInfo, VCS_INFO_FILE};
+
+/// The digest manifest written next to a `.crate` file.
+#[derive(Serialize)]
+struct Digests {
+    /// Version of this format.
+    v: u32,
+    name: String,
+    vers: String,
+    /// SHA-256 of the `.crate` file.
+    cksum: String,
+    /// SHA-256 of each file in the archive, keyed by its path in the package.
+    files: BTreeMap<String, String>,
+    /// The contents of `.cargo_vcs_info.json`, if the package has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    vcs_info: Option<VcsInfo>,
+}
+
+/// Name of the digest manifest of `pkg`.
+fn digests_name(pkg: &Package) -> String {
+    format!("{}-{}.digests.json", pkg.name(), pkg.version())
+}
+
+/// Writes the digest manifest of the `.crate` file of `pkg` in `dst` to `path`.
+fn write_digests(pkg: &Package, mut dst: &File, path: &Path) -> CargoResult<()> {
+    dst.seek(SeekFrom::Start(0))?;
+    let mut bytes = Vec::new();
+    dst.read_to_end(&mut bytes)?;
+    let (files, vcs_info) = file_digests(pkg, &bytes)?;
+    let digests = Digests {
+        v: 1,
+        name: pkg.name().to_string(),
+        vers: pkg.version().to_string(),
+        cksum: Sha256::new().update(&bytes).finish_hex(),
+        files,
+        vcs_info,
+    };
+    let mut json = serde_json::to_string_pretty(&digests)?;
+    json.push('\n');
+    paths::write(path, json)
+}
+
+/// Computes the SHA-256 of each file in a `.crate` file, and reads its VCS
+/// info along the way.
+fn file_digests(
+    pkg: &Package,
+    bytes: &[u8],
+) -> CargoResult<(BTreeMap<String, String>, Option<VcsInfo>)> {
+    let base_name = format!("{}-{}", pkg.name(), pkg.version());
+    let mut files = BTreeMap::new();
+    let mut vcs_info = None;
+    let mut archive = Archive::new(GzDecoder::new(bytes));
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        let rel_path = path.strip_prefix(&base_name).unwrap_or(&path);
+        let rel_str = rel_path
+            .to_str()
+            .ok_or_else(|| anyhow::format_err!("non-utf8 path in archive: {}", path.display()))?
+            .replace('\\', "/");
+        let mut contents = Vec::new();
+        entry.read_to_end(&mut contents)?;
+        if rel_str == VCS_INFO_FILE {
+            vcs_info = Some(serde_json::from_slice(&contents)?);
+        }
+        files.insert(rel_str, Sha256::new().update(&contents).finish_hex());
+    }
+    Ok((files, vcs_info))
+}
+
+/// The conditions a package is repackaged in by [`check_reproducible`].
+struct Environment {
+    /// The modification time of the copied files.
+    mtime: FileTime,
+    /// The permission bits removed from the copied files.
+    umask: u32,
+}
+
+/// Repackages `ar_files` from copies of the files on disk, and fails if the
+/// result differs from `tarball`. Otherwise writes the digest manifest next
+/// to it.
+pub(super) fn check_reproducible(
+    ws: &Workspace<'_>,
+    pkg: &Package,
+    ar_files: Vec<ArchiveFile>,
+    local_reg: Option<&TmpRegistry<'_>>,
+    tarball: &FileLock,
+) -> CargoResult<()> {
+    let gctx = ws.gctx();
+    gctx.shell()
+        .status("Repackaging", pkg.package_id().to_string())?;
+
+    let mut expected = Vec::new();
+    let mut file = tarball.file();
+    file.seek(SeekFrom::Start(0))?;
+    file.read_to_end(&mut expected)?;
+
+    let envs = [
+        Environment {
+            mtime: FileTime::zero(),
+            umask: 0o077,
+        },
+        // As on a file system without executable bits.
+        Environment {
+            mtime: FileTime::now(),
+            umask: 0o111,
+        },
+    ];
+    let filename = pkg.package_id().tarball_name();
+    let dir = ws
+        .target_dir()
+        .join("package")
+        .join("reproducible-check")
+        .into_path_unlocked();
+    let result = (|| {
+        for (i, env) in envs.iter().enumerate() {
+            let root = dir.join(i.to_string());
+            if root.exists() {
+                paths::remove_dir_all(&root)?;
+            }
+            let files = ar_files
+                .iter()
+                .map(|ar_file| copy_file(ar_file, &root, env))
+                .collect::<CargoResult<Vec<_>>>()?;
+
+            let path = root.join(&filename);
+            let dst = File::options()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(&path)
+                .with_context(|| format!("failed to create `{}`", path.display()))?;
+            tar(ws, pkg, local_reg, files, &dst, &filename)
+                .context("failed to repackage local package")?;
+            let actual = paths::read_bytes(&path)?;
+            if actual != expected {
+                return Err(not_reproducible(pkg, &expected, &actual));
+            }
+        }
+        Ok(())
+    })();
+    // Report the differences rather than a failure to clean up after them.
+    let cleanup = paths::remove_dir_all(&dir);
+    result.and(cleanup)?;
+
+    let digests_path = tarball.parent().join(digests_name(pkg));
+    write_digests(pkg, tarball.file(), &digests_path)
+        .context("failed to write the digests of the package")
+}
+
+/// Copies a file to be archived into `root`, in the conditions of `env`.
+fn copy_file(ar_file: &ArchiveFile, root: &Path, env: &Environment) -> CargoResult<ArchiveFile> {
+    let FileContents::OnDisk(src) = &ar_file.contents else {
+        return Ok(ar_file.clone());
+    };
+    let dst = root.join(&ar_file.rel_path);
+    paths::create_dir_all(dst.parent().unwrap())?;
+    paths::copy(src, &dst)?;
+    #[cfg(unix)]
+    {
+        use std::fs;
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(src)?.permissions().mode() & !env.umask;
+        fs::set_permissions(&dst, fs::Permissions::from_mode(mode))?;
+    }
+    #[cfg(not(unix))]
+    let _ = env.umask;
+    filetime::set_file_mtime(&dst, env.mtime)
+        .with_context(|| format!("failed to set the mtime of `{}`", dst.display()))?;
+    Ok(ArchiveFile {
+        contents: FileContents::OnDisk(dst),
+        ..ar_file.clone()
+    })
+}
+
+/// Builds the error for a package whose repackaged archive is `actual`
+/// instead of `expected`, listing the files that differ.
+fn not_reproducible(pkg: &Package, expected: &[u8], actual: &[u8]) -> anyhow::Error {
+    let mut msg = format!(
+        "package `{}` is not reproducible, repackaging it produced a different `.crate` file",
+        pkg.package_id()
+    );
+    let (Ok((expected, _)), Ok((actual, _))) =
+        (file_digests(pkg, expected), file_digests(pkg, actual))
+    else {
+        return anyhow::format_err!(msg);
+    };
+    let mut differing = Vec::new();
+    for (path, digest) in &expected {
+        match actual.get(path) {
+            Some(d) if d == digest => {}
+            Some(_) => differing.push(format!("changed: {path}")),
+            None => differing.push(format!("removed: {path}")),
+        }
+    }
+    for path in actual.keys().filter(|p| !expected.contains_key(*p)) {
+        differing.push(format!("added: {path}"));
+    }
+    if differing.is_empty() {
+        msg.push_str("\nthe contents of all files are identical, but their metadata differs");
+    } else {
+        msg.push_str(", with these differences:");
+        for line in differing {
+            msg.push_str(&format!("\n  {line}"));
+        }
+    }
+    anyhow::format_err!(msg)
+}
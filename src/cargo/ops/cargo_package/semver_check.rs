@@ -0,0 +1,646 @@
+//! Checks the public API of a package against its last published version,
+//! for the unstable `-Zsemver-check`.
+//!
+//! Both versions of the library are documented with the JSON output format of
+//! rustdoc. The public items reachable from the crate root are collected by
+//! their path, along with a signature which doesn't depend on the ids rustdoc
+//! assigns to items. An item of the published version that is missing or has
+//! a different signature in the new version is a breaking change, which is
+//! only allowed when the version is bumped to a semver incompatible one.
+//!
+//! This is not an exhaustive check of the [semver rules]. For example, changes
+//! of trait bounds on impls, or of the auto traits of types are not detected.
+//!
+//! [semver rules]: https://doc.rust-lang.org/cargo/reference/semver.html
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context as _;
+use semver::Version;
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::core::compiler::BuildConfig;
+use crate::core::compiler::CompileMode;
+use crate::core::Package;
+use crate::core::Workspace;
+use crate::ops;
+use crate::util::errors::CargoResult;
+
+use super::{latest_published, PackageOpts};
+
+/// Compares the public API of `pkg` with the one of the latest published
+/// version lower than its own, and fails if there are breaking changes
+/// without a semver incompatible version bump.
+pub(super) fn check_semver(
+    ws: &Workspace<'_>,
+    pkg: &Package,
+    opts: &PackageOpts<'_>,
+) -> CargoResult<()> {
+    let gctx = ws.gctx();
+    if !pkg.targets().iter().any(|t| t.is_lib()) {
+        // Only libraries have a public API.
+        return Ok(());
+    }
+
+    let Some(published) = latest_published(gctx, pkg, opts)? else {
+        gctx.shell().note(format!(
+            "skipping semver check, no version of `{}` lower than {} is published",
+            pkg.name(),
+            pkg.version()
+        ))?;
+        return Ok(());
+    };
+
+    gctx.shell().status(
+        "Comparing",
+        format!("public API of {} to {}", pkg, published.package_id()),
+    )?;
+
+    let new_api = document(ws, pkg, opts)?;
+
+    // The published version is documented in a workspace of its own, without
+    // touching the source in the registry cache.
+    let target_dir = ws.target_dir().join("package").join("semver-check");
+    let mut old_ws = Workspace::ephemeral(published.clone(), gctx, Some(target_dir), false)?;
+    old_ws.set_ignore_lock(true);
+    let old_api = document(&old_ws, &published, opts)?;
+
+    let changes = breaking_changes(&old_api, &new_api);
+    if changes.is_empty() || is_breaking_bump(published.version(), pkg.version()) {
+        return Ok(());
+    }
+
+    let mut msg = format!(
+        "the public API of `{}` has breaking changes since the published version {}, \
+         but version {} is semver compatible with it:",
+        pkg.name(),
+        published.version(),
+        pkg.version()
+    );
+    for change in &changes {
+        msg.push_str(&format!("\n  {change}"));
+    }
+    msg.push_str(&format!(
+        "\n\nBump the version to {} or later to publish these changes.",
+        breaking_bump(published.version())
+    ));
+    anyhow::bail!(msg)
+}
+
+/// Builds the rustdoc JSON output of the library of `pkg` in `ws`, and reads
+/// its public API.
+fn document(ws: &Workspace<'_>, pkg: &Package, opts: &PackageOpts<'_>) -> CargoResult<PublicApi> {
+    let gctx = ws.gctx();
+    let compile_opts = ops::CompileOptions {
+        build_config: BuildConfig::new(
+            gctx,
+            opts.jobs.clone(),
+            opts.keep_going,
+            &opts.targets,
+            CompileMode::Doc {
+                deps: false,
+                json: true,
+            },
+        )?,
+        cli_features: opts.cli_features.clone(),
+        spec: ops::Packages::Packages(vec![pkg.package_id().to_spec().to_string()]),
+        filter: ops::CompileFilter::lib_only(),
+        target_rustdoc_args: None,
+        target_rustc_args: None,
+        target_rustc_crate_types: None,
+        rustdoc_document_private_items: false,
+        honor_rust_version: None,
+    };
+    let compilation = ops::compile(ws, &compile_opts)?;
+
+    let lib = pkg.targets().iter().find(|t| t.is_lib()).unwrap();
+    let kind = compile_opts.build_config.requested_kinds[0];
+    let path = compilation.root_output[&kind]
+        .with_file_name("doc")
+        .join(format!("{}.json", lib.crate_name()));
+    PublicApi::read(&path)
+}
+
+/// Checks if `new` is semver incompatible with `old`, hence allowed to contain
+/// breaking changes.
+fn is_breaking_bump(old: &Version, new: &Version) -> bool {
+    match (old.major, old.minor) {
+        (0, 0) => new.major != 0 || new.minor != 0 || new.patch != old.patch,
+        (0, minor) => new.major != 0 || new.minor != minor,
+        (major, _) => new.major != major,
+    }
+}
+
+/// The lowest version semver incompatible with `old`.
+fn breaking_bump(old: &Version) -> Version {
+    match (old.major, old.minor) {
+        (0, 0) => Version::new(0, 0, old.patch + 1),
+        (0, minor) => Version::new(0, minor + 1, 0),
+        (major, _) => Version::new(major + 1, 0, 0),
+    }
+}
+
+/// The public items of a crate, keyed by their path.
+struct PublicApi {
+    items: BTreeMap<String, ApiItem>,
+}
+
+#[derive(PartialEq)]
+struct ApiItem {
+    /// The kind of the item as named by rustdoc, like `function`.
+    kind: String,
+    /// The parts of the item definition that other crates may depend on.
+    signature: String,
+}
+
+/// A breaking change found by [`breaking_changes`].
+enum Change<'a> {
+    Removed(&'a str, &'a ApiItem),
+    Changed(&'a str, &'a ApiItem),
+}
+
+impl fmt::Display for Change<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (action, path, item) = match self {
+            Change::Removed(path, item) => ("removed", path, item),
+            Change::Changed(path, item) => ("changed", path, item),
+        };
+        let kind = item.kind.replace('_', " ");
+        write!(f, "{action} {kind} `{path}`")
+    }
+}
+
+fn breaking_changes<'a>(old: &'a PublicApi, new: &'a PublicApi) -> Vec<Change<'a>> {
+    old.items
+        .iter()
+        .filter_map(|(path, old)| match new.items.get(path) {
+            None => Some(Change::Removed(path, old)),
+            Some(new) if new != old => Some(Change::Changed(path, new)),
+            Some(_) => None,
+        })
+        .collect()
+}
+
+impl PublicApi {
+    fn read(path: &Path) -> CargoResult<PublicApi> {
+        let read = || -> CargoResult<PublicApi> {
+            let contents = cargo_util::paths::read(path)?;
+            let krate: Value = serde_json::from_str(&contents)?;
+            let empty = Map::new();
+            let walker = Walker {
+                index: krate["index"].as_object().unwrap_or(&empty),
+                paths: krate["paths"].as_object().unwrap_or(&empty),
+                modules: Vec::new(),
+                items: BTreeMap::new(),
+            };
+            Ok(walker.walk(&krate["root"]))
+        };
+        read().with_context(|| {
+            format!(
+                "failed to read rustdoc JSON output at `{}`",
+                PathBuf::from(path).display()
+            )
+        })
+    }
+}
+
+/// Collects the public items of a crate from the rustdoc JSON output, by
+/// walking the modules from the crate root.
+struct Walker<'a> {
+    /// The `index` of the output, mapping ids to items.
+    index: &'a Map<String, Value>,
+    /// The `paths` of the output, mapping ids to the canonical paths of items.
+    paths: &'a Map<String, Value>,
+    /// Ids of the modules being walked, to not loop on glob re-exports.
+    modules: Vec<String>,
+    items: BTreeMap<String, ApiItem>,
+}
+
+impl<'a> Walker<'a> {
+    fn walk(mut self, root: &Value) -> PublicApi {
+        if let Some(root) = self.item(root) {
+            let name = root["name"].as_str().unwrap_or_default();
+            self.module(root, name);
+        }
+        PublicApi { items: self.items }
+    }
+
+    fn item(&self, id: &Value) -> Option<&'a Value> {
+        self.index.get(&id_key(id)?)
+    }
+
+    fn add(&mut self, path: &str, kind: &str, signature: String) {
+        let item = ApiItem {
+            kind: kind.to_string(),
+            signature,
+        };
+        self.items.insert(path.to_string(), item);
+    }
+
+    /// Adds the public items of the module `item`, located at `path`.
+    fn module(&mut self, item: &'a Value, path: &str) {
+        let Some(id) = id_key(&item["id"]) else {
+            return;
+        };
+        if self.modules.contains(&id) {
+            return;
+        }
+        self.modules.push(id);
+        for child in ids(&inner(item).1["items"]) {
+            let Some(child) = self.item(child) else {
+                continue;
+            };
+            if child["visibility"] != "public" {
+                continue;
+            }
+            match inner(child) {
+                // `import` is the name used by older format versions.
+                ("use" | "import", import) => {
+                    let glob = import["is_glob"].as_bool() == Some(true)
+                        || import["glob"].as_bool() == Some(true);
+                    let name = import["name"].as_str().unwrap_or_default();
+                    match self.item(&import["id"]) {
+                        Some(target) if glob => {
+                            if inner(target).0 == "module" {
+                                self.module(target, path);
+                            }
+                        }
+                        Some(target) => self.add_item(&join(path, name), target),
+                        // Re-exports of items from other crates.
+                        None if !glob => {
+                            let source = import["source"].as_str().unwrap_or_default();
+                            self.add(&join(path, name), "use", source.to_string());
+                        }
+                        None => {}
+                    }
+                }
+                _ => {
+                    if let Some(name) = child["name"].as_str() {
+                        self.add_item(&join(path, name), child);
+                    }
+                }
+            }
+        }
+        self.modules.pop();
+    }
+
+    /// Adds `item` at `path`, along with its public members.
+    fn add_item(&mut self, path: &str, item: &'a Value) {
+        let (kind, def) = inner(item);
+        let signature = match kind {
+            "module" => {
+                self.add(path, kind, String::new());
+                self.module(item, path);
+                return;
+            }
+            "struct" => {
+                let shape = &def["kind"];
+                let (shape_name, fields) = match shape {
+                    Value::Object(shape) if shape.contains_key("plain") => {
+                        let plain = &shape["plain"];
+                        let stripped = plain["has_stripped_fields"].as_bool() == Some(true)
+                            || plain["fields_stripped"].as_bool() == Some(true);
+                        ("plain", Some((&plain["fields"], stripped)))
+                    }
+                    Value::Object(shape) if shape.contains_key("tuple") => {
+                        let stripped = ids(&shape["tuple"]).any(Value::is_null);
+                        ("tuple", Some((&shape["tuple"], stripped)))
+                    }
+                    _ => ("unit", None),
+                };
+                let mut public_fields = Vec::new();
+                if let Some((fields, stripped)) = fields {
+                    for (i, field) in ids(fields).enumerate() {
+                        let Some(field) = self.item(field) else {
+                            continue;
+                        };
+                        if field["visibility"] != "public" {
+                            continue;
+                        }
+                        let name = field_name(field, i);
+                        self.add_item(&join(path, &name), field);
+                        public_fields.push(name);
+                    }
+                    // Adding fields to a struct that can be constructed with
+                    // a struct expression is a breaking change.
+                    if stripped || is_non_exhaustive(item) {
+                        public_fields = vec!["..".to_string()];
+                    }
+                }
+                self.impls(&def["impls"], path);
+                format!(
+                    "{shape_name} {{{}}} {}",
+                    public_fields.join(", "),
+                    self.normalize(&def["generics"])
+                )
+            }
+            "enum" => {
+                let mut variants = Vec::new();
+                for variant in ids(&def["variants"]) {
+                    let Some(variant) = self.item(variant) else {
+                        continue;
+                    };
+                    let name = variant["name"].as_str().unwrap_or_default();
+                    self.add_item(&join(path, name), variant);
+                    variants.push(name.to_string());
+                }
+                // Adding variants to an exhaustive enum is a breaking change.
+                if is_non_exhaustive(item) {
+                    variants = vec!["..".to_string()];
+                }
+                self.impls(&def["impls"], path);
+                format!(
+                    "{{{}}} {}",
+                    variants.join(", "),
+                    self.normalize(&def["generics"])
+                )
+            }
+            "variant" => {
+                let shape = &def["kind"];
+                let (shape_name, fields) = match shape {
+                    Value::Object(shape) if shape.contains_key("tuple") => {
+                        ("tuple", ids(&shape["tuple"]).collect::<Vec<_>>())
+                    }
+                    Value::Object(shape) if shape.contains_key("struct") => {
+                        ("struct", ids(&shape["struct"]["fields"]).collect())
+                    }
+                    _ => ("plain", Vec::new()),
+                };
+                let mut names = Vec::new();
+                for (i, field) in fields.into_iter().enumerate() {
+                    if let Some(field) = self.item(field) {
+                        let name = field_name(field, i);
+                        self.add_item(&join(path, &name), field);
+                        names.push(name);
+                    }
+                }
+                if is_non_exhaustive(item) {
+                    names = vec!["..".to_string()];
+                }
+                format!("{shape_name} {{{}}}", names.join(", "))
+            }
+            "union" => {
+                for field in ids(&def["fields"]) {
+                    if let Some(field) = self.item(field) {
+                        if field["visibility"] == "public" {
+                            let name = field["name"].as_str().unwrap_or_default();
+                            self.add_item(&join(path, name), field);
+                        }
+                    }
+                }
+                self.impls(&def["impls"], path);
+                self.normalize(&def["generics"]).to_string()
+            }
+            "trait" => {
+                let mut required = Vec::new();
+                for trait_item in ids(&def["items"]) {
+                    let Some(trait_item) = self.item(trait_item) else {
+                        continue;
+                    };
+                    let name = trait_item["name"].as_str().unwrap_or_default();
+                    self.add_item(&join(path, name), trait_item);
+                    // Adding items without a default to a trait is a breaking
+                    // change.
+                    let (kind, def) = inner(trait_item);
+                    let is_required = match kind {
+                        "function" => def["has_body"].as_bool() == Some(false),
+                        "assoc_const" => def["value"].is_null() && def["default"].is_null(),
+                        "assoc_type" => def["type"].is_null() && def["default"].is_null(),
+                        _ => false,
+                    };
+                    if is_required {
+                        required.push(name.to_string());
+                    }
+                }
+                let mut def = def.clone();
+                for key in [
+                    "items",
+                    "implementations",
+                    "is_dyn_compatible",
+                    "is_object_safe",
+                ] {
+                    def.as_object_mut().map(|def| def.remove(key));
+                }
+                format!("{{{}}} {}", required.join(", "), self.normalize(&def))
+            }
+            "function" => {
+                let mut def = def.clone();
+                def.as_object_mut().map(|def| def.remove("has_body"));
+                self.normalize(&def).to_string()
+            }
+            "constant" | "assoc_const" => {
+                // The value of constants is not part of their signature.
+                self.normalize(&def["type"]).to_string()
+            }
+            "static" => {
+                let mut def = def.clone();
+                def.as_object_mut().map(|def| def.remove("expr"));
+                self.normalize(&def).to_string()
+            }
+            _ => self.normalize(def).to_string(),
+        };
+        self.add(path, kind, signature);
+    }
+
+    /// Adds the public methods of inherent impls, and the implemented traits
+    /// of the type at `path`.
+    fn impls(&mut self, impls: &Value, path: &str) {
+        for id in ids(impls) {
+            let Some(imp) = self.item(id) else {
+                continue;
+            };
+            let (_, imp) = inner(imp);
+            if !imp["blanket_impl"].is_null() {
+                continue;
+            }
+            match &imp["trait"] {
+                Value::Null => {
+                    for method in ids(&imp["items"]) {
+                        let Some(method) = self.item(method) else {
+                            continue;
+                        };
+                        if method["visibility"] != "public" {
+                            continue;
+                        }
+                        if let Some(name) = method["name"].as_str() {
+                            self.add_item(&join(path, name), method);
+                        }
+                    }
+                }
+                trait_ => {
+                    let negative = if imp["is_negative"].as_bool() == Some(true) {
+                        "!"
+                    } else {
+                        ""
+                    };
+                    let key = format!("<{path} as {negative}{}>", self.render_path(trait_));
+                    self.add(&key, "impl", String::new());
+                }
+            }
+        }
+    }
+
+    /// The canonical path of the item `id`, if known.
+    fn canonical_path(&self, id: &Value) -> Option<String> {
+        let summary = self.paths.get(&id_key(id)?)?;
+        let segments: Vec<_> = summary["path"]
+            .as_array()?
+            .iter()
+            .filter_map(Value::as_str)
+            .collect();
+        Some(segments.join("::"))
+    }
+
+    /// Replaces the paths of referenced items with their canonical paths,
+    /// and removes the ids of items, which differ between builds.
+    fn normalize(&self, value: &Value) -> Value {
+        match value {
+            Value::Object(object) => {
+                let canonical = object.get("id").and_then(|id| self.canonical_path(id));
+                let mut normalized = Map::new();
+                for (key, value) in object {
+                    match key.as_str() {
+                        "id" => {}
+                        // `name` is the key used by older format versions.
+                        "path" | "name" if value.is_string() && canonical.is_some() => {
+                            normalized.insert(key.clone(), Value::from(canonical.clone()));
+                        }
+                        _ => {
+                            normalized.insert(key.clone(), self.normalize(value));
+                        }
+                    }
+                }
+                Value::Object(normalized)
+            }
+            Value::Array(values) => {
+                Value::Array(values.iter().map(|v| self.normalize(v)).collect())
+            }
+            value => value.clone(),
+        }
+    }
+
+    /// Renders a path to an item, like the trait of an impl, with its generic
+    /// arguments.
+    fn render_path(&self, path: &Value) -> String {
+        let name = self
+            .canonical_path(&path["id"])
+            .or_else(|| path["path"].as_str().map(str::to_string))
+            .or_else(|| path["name"].as_str().map(str::to_string))
+            .unwrap_or_default();
+        let args: Vec<_> = path["args"]["angle_bracketed"]["args"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|arg| match arg {
+                Value::Object(arg) if arg.contains_key("type") => self.render_type(&arg["type"]),
+                Value::Object(arg) if arg.contains_key("lifetime") => {
+                    arg["lifetime"].as_str().unwrap_or("'_").to_string()
+                }
+                _ => "_".to_string(),
+            })
+            .collect();
+        if args.is_empty() {
+            name
+        } else {
+            format!("{name}<{}>", args.join(", "))
+        }
+    }
+
+    fn render_type(&self, ty: &Value) -> String {
+        let Value::Object(ty) = ty else {
+            return "_".to_string();
+        };
+        let Some((kind, def)) = ty.iter().next() else {
+            return "_".to_string();
+        };
+        match kind.as_str() {
+            "primitive" | "generic" => def.as_str().unwrap_or("_").to_string(),
+            "resolved_path" => self.render_path(def),
+            "borrowed_ref" => {
+                let mutability = if def["is_mutable"].as_bool() == Some(true)
+                    || def["mutable"].as_bool() == Some(true)
+                {
+                    "mut "
+                } else {
+                    ""
+                };
+                format!("&{mutability}{}", self.render_type(&def["type"]))
+            }
+            "slice" => format!("[{}]", self.render_type(def)),
+            "array" => format!(
+                "[{}; {}]",
+                self.render_type(&def["type"]),
+                def["len"].as_str().unwrap_or("_")
+            ),
+            "tuple" => {
+                let types: Vec<_> = ids(def).map(|ty| self.render_type(ty)).collect();
+                format!("({})", types.join(", "))
+            }
+            _ => self.normalize(def).to_string(),
+        }
+    }
+}
+
+/// The kind of the item, and its kind specific definition.
+fn inner(item: &Value) -> (&str, &Value) {
+    match &item["inner"] {
+        Value::Object(inner) => match inner.iter().next() {
+            Some((kind, def)) => (kind.as_str(), def),
+            None => ("", &Value::Null),
+        },
+        // Older format versions have the kind next to the item.
+        _ => (item["kind"].as_str().unwrap_or_default(), &item["inner"]),
+    }
+}
+
+/// Iterates over a list of ids, or any other values.
+fn ids(value: &Value) -> impl Iterator<Item = &Value> {
+    value.as_array().into_iter().flatten()
+}
+
+/// Ids are strings in older format versions, and integers in newer ones.
+fn id_key(id: &Value) -> Option<String> {
+    match id {
+        Value::String(id) => Some(id.clone()),
+        Value::Number(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+fn field_name(field: &Value, index: usize) -> String {
+    match field["name"].as_str() {
+        Some(name) => name.to_string(),
+        None => index.to_string(),
+    }
+}
+
+fn is_non_exhaustive(item: &Value) -> bool {
+    item["attrs"].to_string().contains("non_exhaustive")
+}
+
+fn join(path: &str, name: &str) -> String {
+    format!("{path}::{name}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{breaking_bump, is_breaking_bump};
+    use semver::Version;
+
+    #[test]
+    fn breaking_bumps() {
+        let v = |s| Version::parse(s).unwrap();
+        assert!(!is_breaking_bump(&v("1.2.3"), &v("1.3.0")));
+        assert!(is_breaking_bump(&v("1.2.3"), &v("2.0.0")));
+        assert!(!is_breaking_bump(&v("0.2.3"), &v("0.2.4")));
+        assert!(is_breaking_bump(&v("0.2.3"), &v("0.3.0")));
+        assert!(is_breaking_bump(&v("0.0.3"), &v("0.0.4")));
+        assert_eq!(breaking_bump(&v("1.2.3")), v("2.0.0"));
+        assert_eq!(breaking_bump(&v("0.2.3")), v("0.3.0"));
+        assert_eq!(breaking_bump(&v("0.0.3")), v("0.0.4"));
+    }
+}
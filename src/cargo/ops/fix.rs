@@ -36,7 +36,7 @@
 //!   break anything. The change will be backed out if it fails (unless
 //!   `--broken-code` is used).
 
-use std::collections::{BTreeSet, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::ffi::OsString;
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -51,6 +51,7 @@ use rustfix::CodeFix;
 use semver::Version;
 use tracing::{debug, trace, warn};
 
+use crate::core::compiler::unused_dependencies::UnusedDependency;
 use crate::core::compiler::CompileKind;
 use crate::core::compiler::RustcTargetData;
 use crate::core::resolver::features::{DiffMap, FeatureOpts, FeatureResolver, FeaturesFor};
@@ -61,6 +62,9 @@ use crate::ops::resolve::WorkspaceResolve;
 use crate::ops::{self, CompileOptions};
 use crate::util::diagnostic_server::{Message, RustfixDiagnosticServer};
 use crate::util::errors::CargoResult;
+use crate::util::lints::{self, LintLevel};
+use crate::util::toml_mut::dependency::{Dependency, RegistrySource, WorkspaceSource};
+use crate::util::toml_mut::manifest::LocalManifest;
 use crate::util::GlobalContext;
 use crate::util::{existing_vcs_repo, LockServer, LockServerClient};
 use crate::{drop_eprint, drop_eprintln};
@@ -109,16 +113,19 @@ pub fn fix(
 
     let mut target_data =
         RustcTargetData::new(original_ws, &opts.compile_opts.build_config.requested_kinds)?;
+    let specs = opts.compile_opts.spec.to_package_id_specs(&original_ws)?;
+    let members: Vec<&Package> = original_ws
+        .members()
+        .filter(|m| specs.iter().any(|spec| spec.matches(m.package_id())))
+        .collect();
     if opts.edition {
-        let specs = opts.compile_opts.spec.to_package_id_specs(&original_ws)?;
-        let members: Vec<&Package> = original_ws
-            .members()
-            .filter(|m| specs.iter().any(|spec| spec.matches(m.package_id())))
-            .collect();
         migrate_manifests(original_ws, &members)?;
 
         check_resolver_change(&original_ws, &mut target_data, opts)?;
     }
+    if gctx.cli_unstable().cargo_lints {
+        unify_workspace_dependencies(original_ws, &members)?;
+    }
     let mut ws = Workspace::new(&root_manifest, gctx)?;
     ws.set_resolve_honors_rust_version(Some(original_ws.resolve_honors_rust_version()));
     ws.set_requested_lockfile_path(opts.requested_lockfile_path.clone());
@@ -173,7 +180,8 @@ pub fn fix(
     // repeating build until there are no more changes to be applied
     opts.compile_opts.build_config.primary_unit_rustc = Some(wrapper);
 
-    ops::compile(&ws, &opts.compile_opts)?;
+    let compilation = ops::compile(&ws, &opts.compile_opts)?;
+    remove_unused_dependencies(&ws, &compilation.unused_dependencies)?;
     Ok(())
 }
 
@@ -345,6 +353,129 @@ fn migrate_manifests(ws: &Workspace<'_>, pkgs: &[&Package]) -> CargoResult<()> {
     Ok(())
 }
 
+/// Moves the dependencies reported by `cargo::divergent_dependency_versions`
+/// to `[workspace.dependencies]`, and makes the members inherit them.
+fn unify_workspace_dependencies(ws: &Workspace<'_>, pkgs: &[&Package]) -> CargoResult<()> {
+    let gctx = ws.gctx();
+    let mut fixable = HashMap::new();
+    for pkg in pkgs {
+        if lints::divergent_dependency_versions_level(pkg, gctx)? != LintLevel::Allow {
+            fixable.insert(pkg.manifest_path(), pkg.manifest().unstable_features());
+        }
+    }
+    if fixable.is_empty() {
+        return Ok(());
+    }
+
+    let mut manifests: BTreeMap<PathBuf, (LocalManifest, usize)> = BTreeMap::new();
+    fn manifest<'a>(
+        manifests: &'a mut BTreeMap<PathBuf, (LocalManifest, usize)>,
+        path: &Path,
+    ) -> CargoResult<&'a mut (LocalManifest, usize)> {
+        if !manifests.contains_key(path) {
+            manifests.insert(path.to_owned(), (LocalManifest::try_new(path)?, 0));
+        }
+        Ok(manifests.get_mut(path).unwrap())
+    }
+
+    for dep in lints::find_divergent_dependencies(ws) {
+        let requirements = dep
+            .requirements
+            .iter()
+            .filter(|r| fixable.contains_key(r.manifest_path.as_path()))
+            .collect::<Vec<_>>();
+        // Members can't turn off default features the workspace enables.
+        if requirements.is_empty() || requirements.iter().any(|r| !r.default_features) {
+            continue;
+        }
+
+        if dep.workspace_req.as_deref() != Some(dep.unified_req.as_str()) {
+            let mut workspace_dep =
+                Dependency::new(&dep.name).set_source(RegistrySource::new(&dep.unified_req));
+            if let Some(registry) = &dep.registry {
+                workspace_dep = workspace_dep.set_registry(registry);
+            }
+            let (root, fixes) = manifest(&mut manifests, ws.root_manifest())?;
+            root.insert_into_table(
+                &["workspace".to_owned(), "dependencies".to_owned()],
+                &workspace_dep,
+                gctx,
+                ws.root(),
+                ws.unstable_features(),
+            )?;
+            *fixes += 1;
+        }
+
+        for requirement in requirements {
+            let unstable_features = fixable[requirement.manifest_path.as_path()];
+            let crate_root = requirement.manifest_path.parent().unwrap();
+            let (member, fixes) = manifest(&mut manifests, &requirement.manifest_path)?;
+            let item = member.get_table(&requirement.table)?[dep.name.as_str()].clone();
+            let member_dep = Dependency::from_toml(
+                gctx,
+                ws.root(),
+                crate_root,
+                unstable_features,
+                &dep.name,
+                &item,
+            )?
+            .set_source(WorkspaceSource::new());
+            member.insert_into_table(
+                &requirement.table,
+                &member_dep,
+                gctx,
+                ws.root(),
+                unstable_features,
+            )?;
+            *fixes += 1;
+        }
+    }
+
+    for (path, (manifest, fixes)) in manifests {
+        let file = path.strip_prefix(ws.root()).unwrap_or(&path).display();
+        let verb = if fixes == 1 { "fix" } else { "fixes" };
+        gctx.shell()
+            .status("Fixed", format!("{file} ({fixes} {verb})"))?;
+        manifest.write()?;
+    }
+    Ok(())
+}
+
+/// Removes the dependencies reported by `cargo::unused_dependencies`, like
+/// `cargo remove` does, leaving those that unbuilt code may use.
+fn remove_unused_dependencies(
+    ws: &Workspace<'_>,
+    unused: &BTreeMap<PackageId, Vec<UnusedDependency>>,
+) -> CargoResult<()> {
+    for pkg in ws.members() {
+        let Some(unused) = unused.get(&pkg.package_id()) else {
+            continue;
+        };
+        let mut manifest = LocalManifest::try_new(pkg.manifest_path())?;
+        let mut fixes = 0;
+        for dep in unused {
+            for table in lints::unused_dependency_tables(pkg, dep) {
+                if lints::is_fixable_unused_dependency(pkg, dep, &table) {
+                    manifest.remove_from_table(&table, &dep.name)?;
+                    fixes += 1;
+                }
+            }
+            manifest.gc_dep(&dep.name);
+        }
+        if fixes == 0 {
+            continue;
+        }
+        let path = pkg.manifest_path();
+        let file = path.strip_prefix(ws.root()).unwrap_or(path).display();
+        let verb = if fixes == 1 { "fix" } else { "fixes" };
+        ws.gctx()
+            .shell()
+            .status("Fixed", format!("{file} ({fixes} {verb})"))?;
+        manifest.write()?;
+    }
+    Ok(())
+}
+
 fn rename_dep_fields_2024(parent: &mut dyn toml_edit::TableLike, dep_kind: &str) -> usize {
     let mut fixes = 0;
     for target in parent
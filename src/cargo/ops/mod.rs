@@ -6,7 +6,7 @@ pub use self::cargo_compile::{
 };
 pub use self::cargo_compile::{CompileFilter, FilterRule, LibRule, Packages};
 pub use self::cargo_doc::{doc, DocOptions, OutputFormat};
-pub use self::cargo_fetch::{fetch, FetchOptions};
+pub use self::cargo_fetch::{fetch, FetchOptions, FetchStatsFormat};
 pub use self::cargo_install::{install, install_list};
 pub use self::cargo_new::{init, new, NewOptions, NewProjectKind, VersionControl};
 pub use self::cargo_output_metadata::{output_metadata, ExportInfo, OutputMetadataOptions};
@@ -25,20 +25,24 @@ pub use self::cargo_update::UpdateOptions;
 pub use self::common_for_install_and_uninstall::{resolve_root, InstallTracker};
 pub use self::fix::{fix, fix_exec_rustc, fix_get_proxy_lock_addr, FixOptions};
 pub use self::lockfile::{load_pkg_lockfile, resolve_to_string, write_pkg_lockfile};
+pub use self::registry::edit_metadata;
 pub use self::registry::info;
 pub use self::registry::modify_owners;
+pub use self::registry::promote;
 pub use self::registry::publish;
-pub use self::registry::registry_login;
 pub use self::registry::registry_logout;
 pub use self::registry::search;
 pub use self::registry::yank;
+pub use self::registry::EditMetadataOptions;
 pub use self::registry::OwnersOptions;
 pub use self::registry::PublishOpts;
 pub use self::registry::RegistryCredentialConfig;
 pub use self::registry::RegistryOrIndex;
+pub use self::registry::SearchOptions;
+pub use self::registry::{registry_login, registry_login_status};
 pub use self::resolve::{
-    add_overrides, get_resolved_packages, resolve_with_previous, resolve_ws, resolve_ws_with_opts,
-    WorkspaceResolve,
+    add_overrides, get_resolved_packages, resolve_with_previous, resolve_with_registry, resolve_ws,
+    resolve_ws_with_opts, WorkspaceResolve,
 };
 pub use self::vendor::{vendor, VendorOptions};
 
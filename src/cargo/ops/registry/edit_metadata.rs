@@ -0,0 +1,81 @@
+//! Interacts with the registry [edit metadata API][1].
+//!
+//! [1]: https://doc.rust-lang.org/nightly/cargo/reference/registry-web-api.html#edit-metadata
+
+use anyhow::bail;
+use anyhow::Context as _;
+use cargo_credential::Operation;
+use cargo_credential::Secret;
+use crates_io::EditMetadata;
+
+use crate::core::Workspace;
+use crate::util::context::GlobalContext;
+use crate::util::errors::CargoResult;
+use crate::util::important_paths::find_root_manifest_for_wd;
+
+use super::RegistryOrIndex;
+
+pub struct EditMetadataOptions {
+    pub krate: Option<String>,
+    pub version: Option<String>,
+    pub token: Option<Secret<String>>,
+    pub reg_or_index: Option<RegistryOrIndex>,
+    pub metadata: EditMetadata,
+}
+
+pub fn edit_metadata(gctx: &GlobalContext, opts: EditMetadataOptions) -> CargoResult<()> {
+    let metadata = &opts.metadata;
+    if metadata.description.is_none()
+        && metadata.documentation.is_none()
+        && metadata.homepage.is_none()
+        && metadata.keywords.is_none()
+        && metadata.categories.is_none()
+    {
+        bail!("no metadata to edit, at least one of `--description`, `--documentation`, `--homepage`, `--keyword` or `--category` must be specified");
+    }
+
+    let (name, version) = match opts.krate {
+        Some(name) => {
+            let Some(version) = opts.version else {
+                bail!("a version must be specified to edit the metadata of `{name}`")
+            };
+            (name, version)
+        }
+        None => {
+            let manifest_path = find_root_manifest_for_wd(gctx.cwd())?;
+            let ws = Workspace::new(&manifest_path, gctx)?;
+            let pkg_id = ws.current()?.package_id();
+            let version = opts.version.unwrap_or_else(|| pkg_id.version().to_string());
+            (pkg_id.name().to_string(), version)
+        }
+    };
+
+    let operation = Operation::EditMetadata {
+        name: &name,
+        vers: &version,
+    };
+    let source_ids = super::get_source_id(gctx, opts.reg_or_index.as_ref())?;
+    let (mut registry, _) = super::registry(
+        gctx,
+        &source_ids,
+        opts.token.as_ref().map(Secret::as_deref),
+        opts.reg_or_index.as_ref(),
+        true,
+        Some(operation),
+    )?;
+
+    gctx.shell()
+        .status("Editing", format!("metadata of {}@{}", name, version))?;
+    registry
+        .edit_metadata(&name, &version, metadata)
+        .with_context(|| {
+            format!(
+                "failed to edit the metadata of `{}@{}` on registry at {}",
+                name,
+                version,
+                registry.host()
+            )
+        })?;
+
+    Ok(())
+}
@@ -5,7 +5,7 @@ use cargo_util_schemas::core::{PackageIdSpec, PartialVersion};
 
 use crate::core::registry::PackageRegistry;
 use crate::core::{Dependency, Package, PackageId, PackageIdSpecQuery, Registry, Workspace};
-use crate::ops::registry::info::view::pretty_view;
+use crate::ops::registry::info::view::{pretty_view, VersionsView};
 use crate::ops::registry::{get_source_id_with_package_id, RegistryOrIndex, RegistrySourceIds};
 use crate::ops::resolve_ws;
 use crate::sources::source::QueryKind;
@@ -20,6 +20,7 @@ pub fn info(
     spec: &PackageIdSpec,
     gctx: &GlobalContext,
     reg_or_index: Option<RegistryOrIndex>,
+    show_versions: bool,
 ) -> CargoResult<()> {
     let source_config = SourceConfigMap::new(gctx)?;
     let mut registry = PackageRegistry::new_with_source_config(gctx, source_config)?;
@@ -45,6 +46,8 @@ pub fn info(
     if !use_package_source_id {
         package_id = None;
     }
+    // The version in the lockfile, for highlighting in the list of versions.
+    let locked_package_id = package_id.filter(|_| !is_member);
 
     let msrv_from_nearest_manifest_path_or_ws =
         try_get_msrv_from_nearest_manifest_or_ws(nearest_package, ws.as_ref());
@@ -76,7 +79,17 @@ pub fn info(
 
     let package = registry.get(&[package_id])?;
     let package = package.get_one(package_id)?;
-    pretty_view(package, &summaries, suggest_cargo_tree_command, gctx)?;
+    let versions = show_versions.then(|| VersionsView {
+        locked: locked_package_id,
+        rust_version: rustc_version,
+    });
+    pretty_view(
+        package,
+        &summaries,
+        suggest_cargo_tree_command,
+        versions.as_ref(),
+        gctx,
+    )?;
 
     Ok(())
 }
@@ -139,7 +152,7 @@ fn find_pkgid_in_summaries(
 ) -> CargoResult<PackageId> {
     let summary = summaries
         .iter()
-        .filter(|s| spec.matches(s.package_id()))
+        .filter(|s| !s.is_yanked() && spec.matches(s.package_id()))
         .max_by(|s1, s2| {
             // Check the MSRV compatibility.
             let s1_matches = s1
@@ -183,7 +196,7 @@ fn query_summaries(
     let dep = Dependency::parse(spec.name(), None, source_ids.original)?;
     loop {
         // Exact to avoid returning all for path/git
-        match registry.query_vec(&dep, QueryKind::Exact) {
+        match registry.query_vec(&dep, QueryKind::ExactWithYanked) {
             std::task::Poll::Ready(res) => {
                 break res;
             }
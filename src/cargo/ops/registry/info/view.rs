@@ -1,8 +1,12 @@
 use std::collections::HashMap;
 use std::io::Write;
 
+use cargo_util_schemas::core::PartialVersion;
+use time::format_description::well_known::Rfc3339;
+use time::{OffsetDateTime, UtcOffset};
+
 use crate::core::Shell;
-use crate::util::style::{ERROR, HEADER, LITERAL, NOP, NOTE, WARN};
+use crate::util::style::{ERROR, GOOD, HEADER, LITERAL, NOP, NOTE, WARN};
 use crate::{
     core::{
         dependency::DepKind, shell::Verbosity, Dependency, FeatureMap, Package, PackageId, SourceId,
@@ -12,11 +16,20 @@ use crate::{
     CargoResult, GlobalContext,
 };
 
+/// What to highlight in the list of versions shown by `cargo info --versions`.
+pub(super) struct VersionsView {
+    /// The version locked in the workspace, if any.
+    pub(super) locked: Option<PackageId>,
+    /// The Rust version to find the newest compatible version for.
+    pub(super) rust_version: PartialVersion,
+}
+
 // Pretty print the package information.
 pub(super) fn pretty_view(
     package: &Package,
     summaries: &[IndexSummary],
     suggest_cargo_tree_command: bool,
+    versions: Option<&VersionsView>,
     gctx: &GlobalContext,
 ) -> CargoResult<()> {
     let summary = package.manifest().summary();
@@ -62,7 +75,10 @@ pub(super) fn pretty_view(
     // 1. The package version is not the latest available version.
     // 2. The package source is not crates.io.
     match (
-        summaries.iter().max_by_key(|s| s.as_summary().version()),
+        summaries
+            .iter()
+            .filter(|s| !s.is_yanked())
+            .max_by_key(|s| s.as_summary().version()),
         is_package_from_crates_io,
     ) {
         (Some(latest), false) if latest.as_summary().version() != package_id.version() => {
@@ -153,6 +169,10 @@ pub(super) fn pretty_view(
         gctx,
     )?;
 
+    if let Some(versions) = versions {
+        pretty_versions(summaries, versions, stdout)?;
+    }
+
     if suggest_cargo_tree_command {
         suggest_cargo_tree(package_id, &mut shell)?;
     }
@@ -160,6 +180,90 @@ pub(super) fn pretty_view(
     Ok(())
 }
 
+fn pretty_versions(
+    summaries: &[IndexSummary],
+    view: &VersionsView,
+    stdout: &mut dyn Write,
+) -> CargoResult<()> {
+    let header = HEADER;
+    let good = GOOD;
+    let warn = WARN;
+    let note = NOTE;
+
+    let mut summaries = summaries.iter().collect::<Vec<_>>();
+    summaries.sort_by(|s1, s2| s2.package_id().version().cmp(s1.package_id().version()));
+    // Like when picking the version to show, only versions with a compatible
+    // `rust-version` count as compatible.
+    let newest_compatible = summaries
+        .iter()
+        .filter(|s| !s.is_yanked())
+        .find(|s| {
+            s.as_summary()
+                .rust_version()
+                .map(|v| v.is_compatible_with(&view.rust_version))
+                .unwrap_or(false)
+        })
+        .map(|s| s.package_id());
+
+    let rows = summaries
+        .iter()
+        .map(|s| {
+            let summary = s.as_summary();
+            let version = summary.version().to_string();
+            // Only show the date, the time is noise when comparing versions.
+            // Registries may put anything there, so invalid times are ignored.
+            let published = summary
+                .pubtime()
+                .and_then(|t| OffsetDateTime::parse(t, &Rfc3339).ok())
+                .map(|t| t.to_offset(UtcOffset::UTC).date().to_string());
+            let rust_version = summary
+                .rust_version()
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| "unknown".to_owned());
+            let mut notes = Vec::new();
+            if Some(s.package_id()) == view.locked {
+                notes.push(format!("{good}locked{good:#}"));
+            }
+            if s.is_yanked() {
+                notes.push(format!("{warn}yanked{warn:#}"));
+            }
+            if Some(s.package_id()) == newest_compatible {
+                notes.push(format!(
+                    "{note}newest compatible with Rust {}{note:#}",
+                    view.rust_version
+                ));
+            }
+            (version, published, rust_version, notes)
+        })
+        .collect::<Vec<_>>();
+    let version_width = rows.iter().map(|r| r.0.len()).max().unwrap_or_default();
+    let published_width = rows
+        .iter()
+        .filter_map(|r| r.1.as_ref().map(|p| p.len()))
+        .max();
+    let rust_version_width = rows.iter().map(|r| r.2.len()).max().unwrap_or_default();
+
+    writeln!(stdout, "{header}versions:{header:#}")?;
+    for (version, published, rust_version, notes) in rows {
+        write!(stdout, "  {version:<version_width$}")?;
+        // Registries that don't record publish times get no column for them.
+        if let Some(width) = published_width {
+            let published = published.as_deref().unwrap_or("unknown");
+            write!(stdout, "  {published:<width$}")?;
+        }
+        if notes.is_empty() {
+            writeln!(stdout, "  rust-version: {rust_version}")?;
+        } else {
+            writeln!(
+                stdout,
+                "  rust-version: {rust_version:<rust_version_width$}  ({})",
+                notes.join(", ")
+            )?;
+        }
+    }
+    Ok(())
+}
+
 fn pretty_source(source: SourceId, ctx: &GlobalContext) -> String {
     if let Some(relpath) = source
         .local_path()
@@ -7,12 +7,18 @@
 
 use std::io::IsTerminal;
 
+use crate::drop_println;
 use crate::util::auth;
 use crate::util::auth::AuthorizationError;
+use crate::util::auth::AuthorizationErrorReason;
+use crate::util::auth::CredentialStatus;
 use crate::CargoResult;
 use crate::GlobalContext;
+use cargo_credential::CacheControl;
 use cargo_credential::LoginOptions;
 use cargo_credential::Secret;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
 
 use super::get_source_id;
 use super::registry;
@@ -23,9 +29,24 @@ pub fn registry_login(
     token_from_cmdline: Option<Secret<&str>>,
     reg_or_index: Option<&RegistryOrIndex>,
     args: &[&str],
+    rotate_key: bool,
 ) -> CargoResult<()> {
     let source_ids = get_source_id(gctx, reg_or_index)?;
 
+    let mut args = args.to_vec();
+    if rotate_key {
+        // Only `cargo:paseto` has a key to rotate.
+        let provider = auth::login_provider(gctx, &source_ids.original)?;
+        if provider != "cargo:paseto" {
+            anyhow::bail!(
+                "`--rotate-key` requires the `cargo:paseto` credential provider, \
+                but `{}` uses `{provider}`",
+                source_ids.original.display_registry_name()
+            );
+        }
+        args.push("--rotate-key");
+    }
+
     let login_url = match registry(
         gctx,
         &source_ids,
@@ -59,6 +80,74 @@ pub fn registry_login(
         login_url: login_url.as_deref(),
     };
 
-    auth::login(gctx, &source_ids.original, options, args)?;
+    auth::login(gctx, &source_ids.original, options, &args)?;
     Ok(())
 }
+
+/// Reports which credential providers are configured for a registry and what
+/// each of them answered when asked for a token, without showing the token.
+pub fn registry_login_status(
+    gctx: &GlobalContext,
+    reg_or_index: Option<&RegistryOrIndex>,
+) -> CargoResult<()> {
+    let sid = get_source_id(gctx, reg_or_index)?.original;
+    let statuses = auth::credential_status(gctx, &sid)?;
+
+    drop_println!(gctx, "{} ({})", sid.display_registry_name(), sid.url());
+    for (provider, status) in &statuses {
+        let status = match status {
+            CredentialStatus::Found {
+                cache,
+                operation_independent,
+            } => {
+                let cache = match cache {
+                    CacheControl::Never => "not cached".to_string(),
+                    CacheControl::Session => "cached for the rest of the command".to_string(),
+                    CacheControl::Expires { expiration } => {
+                        format!("cached until {}", format_time(expiration))
+                    }
+                    CacheControl::Refresh {
+                        expiration,
+                        refresh_after,
+                    } => format!(
+                        "cached until {}, refreshed after {}",
+                        format_time(expiration),
+                        format_time(refresh_after)
+                    ),
+                    _ => "unknown caching".to_string(),
+                };
+                let scope = if *operation_independent {
+                    ""
+                } else {
+                    ", scoped to the operation"
+                };
+                format!("token found ({cache}{scope})")
+            }
+            CredentialStatus::NotFound => "no token found".to_string(),
+            CredentialStatus::UrlNotSupported => "registry not supported".to_string(),
+            CredentialStatus::NotUsed => "not used".to_string(),
+            CredentialStatus::Error(e) => format!("failed: {e:#}"),
+        };
+        // Only the provider is named, as its arguments may hold secrets.
+        drop_println!(gctx, "  {}: {status}", provider[0]);
+    }
+
+    if !statuses
+        .iter()
+        .any(|(_, status)| matches!(status, CredentialStatus::Found { .. }))
+    {
+        return Err(AuthorizationError::new(
+            gctx,
+            sid,
+            None,
+            AuthorizationErrorReason::TokenMissing,
+        )?
+        .into());
+    }
+    Ok(())
+}
+
+fn format_time(time: &OffsetDateTime) -> String {
+    time.format(&Rfc3339)
+        .unwrap_or_else(|_| time.unix_timestamp().to_string())
+}
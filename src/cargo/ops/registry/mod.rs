@@ -2,6 +2,7 @@
 //!
 //! [1]: https://doc.rust-lang.org/nightly/cargo/reference/registry-web-api.html
 
+mod edit_metadata;
 mod info;
 mod login;
 mod logout;
@@ -26,16 +27,21 @@ use crate::util::auth;
 use crate::util::cache_lock::CacheLockMode;
 use crate::util::context::{GlobalContext, PathAndArgs};
 use crate::util::errors::CargoResult;
+use crate::util::network::http::configure_registry_client_cert;
 use crate::util::network::http::http_handle;
 
+pub use self::edit_metadata::edit_metadata;
+pub use self::edit_metadata::EditMetadataOptions;
 pub use self::info::info;
-pub use self::login::registry_login;
+pub use self::login::{registry_login, registry_login_status};
 pub use self::logout::registry_logout;
 pub use self::owner::modify_owners;
 pub use self::owner::OwnersOptions;
+pub use self::publish::promote;
 pub use self::publish::publish;
 pub use self::publish::PublishOpts;
 pub use self::search::search;
+pub use self::search::SearchOptions;
 pub use self::yank::yank;
 
 pub(crate) use self::publish::prepare_transmit;
@@ -63,8 +69,8 @@ pub enum RegistryCredentialConfig {
     Token(Secret<String>),
     /// Process used for fetching a token.
     Process(Vec<PathAndArgs>),
-    /// Secret Key and subject for Asymmetric tokens.
-    AsymmetricKey((Secret<String>, Option<String>)),
+    /// Secret Key, subject and previous secret keys for Asymmetric tokens.
+    AsymmetricKey((Secret<String>, Option<String>, Vec<Secret<String>>)),
 }
 
 impl RegistryCredentialConfig {
@@ -100,7 +106,9 @@ impl RegistryCredentialConfig {
             None
         }
     }
-    pub fn as_asymmetric_key(&self) -> Option<&(Secret<String>, Option<String>)> {
+    pub fn as_asymmetric_key(
+        &self,
+    ) -> Option<&(Secret<String>, Option<String>, Vec<Secret<String>>)> {
         if let Self::AsymmetricKey(v) = self {
             Some(v)
         } else {
@@ -169,7 +177,8 @@ fn registry<'gctx>(
     } else {
         None
     };
-    let handle = http_handle(gctx)?;
+    let mut handle = http_handle(gctx)?;
+    configure_registry_client_cert(gctx, &mut handle, source_ids.original)?;
     Ok((
         Registry::new_handle(api_host, token, handle, cfg.auth_required),
         src,
@@ -247,7 +256,7 @@ fn get_source_id_with_package_id(
     }
 }
 
-fn get_initial_source_id(
+pub(crate) fn get_initial_source_id(
     gctx: &GlobalContext,
     reg_or_index: Option<&RegistryOrIndex>,
 ) -> CargoResult<SourceId> {
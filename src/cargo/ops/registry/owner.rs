@@ -20,6 +20,7 @@ pub struct OwnersOptions {
     pub token: Option<Secret<String>>,
     pub reg_or_index: Option<RegistryOrIndex>,
     pub to_add: Option<Vec<String>>,
+    pub role: Option<String>,
     pub to_remove: Option<Vec<String>>,
     pub list: bool,
 }
@@ -47,7 +48,11 @@ pub fn modify_owners(gctx: &GlobalContext, opts: &OwnersOptions) -> CargoResult<
 
     if let Some(ref v) = opts.to_add {
         let v = v.iter().map(|s| &s[..]).collect::<Vec<_>>();
-        let msg = registry.add_owners(&name, &v).with_context(|| {
+        let msg = match opts.role.as_deref() {
+            Some(role) => registry.add_owners_with_role(&name, &v, role),
+            None => registry.add_owners(&name, &v),
+        };
+        let msg = msg.with_context(|| {
             format!(
                 "failed to invite owners to crate `{}` on registry at {}",
                 name,
@@ -82,9 +87,13 @@ pub fn modify_owners(gctx: &GlobalContext, opts: &OwnersOptions) -> CargoResult<
         for owner in owners.iter() {
             drop_print!(gctx, "{}", owner.login);
             match (owner.name.as_ref(), owner.email.as_ref()) {
-                (Some(name), Some(email)) => drop_println!(gctx, " ({} <{}>)", name, email),
-                (Some(s), None) | (None, Some(s)) => drop_println!(gctx, " ({})", s),
-                (None, None) => drop_println!(gctx),
+                (Some(name), Some(email)) => drop_print!(gctx, " ({} <{}>)", name, email),
+                (Some(s), None) | (None, Some(s)) => drop_print!(gctx, " ({})", s),
+                (None, None) => {}
+            }
+            match owner.role.as_ref() {
+                Some(role) => drop_println!(gctx, " [{}]", role),
+                None => drop_println!(gctx),
             }
         }
     }
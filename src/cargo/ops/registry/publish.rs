@@ -16,10 +16,13 @@ use anyhow::Context as _;
 use cargo_credential::Operation;
 use cargo_credential::Secret;
 use cargo_util::paths;
+use cargo_util::ProcessBuilder;
 use crates_io::NewCrate;
 use crates_io::NewCrateDependency;
 use crates_io::Registry;
+use flate2::read::GzDecoder;
 use itertools::Itertools;
+use tar::Archive;
 
 use crate::core::dependency::DepKind;
 use crate::core::manifest::ManifestMetadata;
@@ -27,6 +30,7 @@ use crate::core::resolver::CliFeatures;
 use crate::core::Dependency;
 use crate::core::Package;
 use crate::core::PackageId;
+use crate::core::PackageIdSpec;
 use crate::core::PackageIdSpecQuery;
 use crate::core::SourceId;
 use crate::core::Workspace;
@@ -43,6 +47,8 @@ use crate::sources::CRATES_IO_REGISTRY;
 use crate::util::auth;
 use crate::util::cache_lock::CacheLockMode;
 use crate::util::context::JobsConfig;
+use crate::util::context::PathAndArgs;
+use crate::util::context::Value;
 use crate::util::toml::prepare_for_publish;
 use crate::util::Graph;
 use crate::util::Progress;
@@ -64,6 +70,8 @@ pub struct PublishOpts<'gctx> {
     pub to_publish: ops::Packages,
     pub targets: Vec<String>,
     pub dry_run: bool,
+    /// Uploads the packages without making them available, see [`promote`].
+    pub stage: bool,
     pub cli_features: CliFeatures,
 }
 
@@ -154,10 +162,22 @@ pub fn publish(ws: &Workspace<'_>, opts: &PublishOpts<'_>) -> CargoResult<()> {
             keep_going: opts.keep_going,
             cli_features: opts.cli_features.clone(),
             reg_or_index: reg_or_index.clone(),
+            reproducible_check: false,
+            diff_published: false,
         },
         pkgs,
     )?;
 
+    let checks = publish_checks(opts.gctx)?;
+    if !checks.is_empty() {
+        let mut pkg_ids = pkg_dep_graph.packages.keys().collect::<Vec<_>>();
+        pkg_ids.sort();
+        for pkg_id in pkg_ids {
+            let (pkg, (_features, tarball)) = &pkg_dep_graph.packages[pkg_id];
+            run_publish_checks(ws, pkg, tarball.file(), source_ids.original, &checks)?;
+        }
+    }
+
     let mut plan = PublishPlan::new(&pkg_dep_graph.graph);
     // May contains packages from previous rounds as `wait_for_any_publish_confirmation` returns
     // after it confirms any packages, not all packages, requiring us to handle the rest in the next
@@ -206,10 +226,22 @@ pub fn publish(ws: &Workspace<'_>, opts: &PublishOpts<'_>) -> CargoResult<()> {
                 &mut registry,
                 source_ids.original,
                 opts.dry_run,
+                opts.stage,
             )?;
             to_confirm.insert(pkg_id);
 
-            if !opts.dry_run {
+            if opts.stage && !opts.dry_run {
+                let source_description = source_ids.original.to_string();
+                ws.gctx().shell().status(
+                    "Staged",
+                    format!("{} v{} at {source_description}", pkg.name(), pkg.version()),
+                )?;
+                ws.gctx().shell().note(format!(
+                    "run `cargo publish -Zunstable-options --promote {}@{}` to make it available",
+                    pkg.name(),
+                    pkg.version()
+                ))?;
+            } else if !opts.dry_run {
                 // Short does not include the registry name.
                 let short_pkg_description = format!("{} v{}", pkg.name(), pkg.version());
                 let source_description = source_ids.original.to_string();
@@ -220,16 +252,12 @@ pub fn publish(ws: &Workspace<'_>, opts: &PublishOpts<'_>) -> CargoResult<()> {
             }
         }
 
-        let confirmed = if opts.dry_run {
+        // Staged packages only become available once promoted, so there is
+        // nothing to wait for.
+        let confirmed = if opts.dry_run || opts.stage {
             to_confirm.clone()
         } else {
-            const DEFAULT_TIMEOUT: u64 = 60;
-            let timeout = if opts.gctx.cli_unstable().publish_timeout {
-                let timeout: Option<u64> = opts.gctx.get("publish.timeout")?;
-                timeout.unwrap_or(DEFAULT_TIMEOUT)
-            } else {
-                DEFAULT_TIMEOUT
-            };
+            let timeout = publish_timeout(opts.gctx)?;
             if 0 < timeout {
                 let timeout = Duration::from_secs(timeout);
                 wait_for_any_publish_confirmation(
@@ -263,6 +291,67 @@ pub fn publish(ws: &Workspace<'_>, opts: &PublishOpts<'_>) -> CargoResult<()> {
     Ok(())
 }
 
+/// Makes the version of a crate uploaded with `cargo publish --stage`
+/// available, and waits for it to appear in the index.
+pub fn promote(
+    gctx: &GlobalContext,
+    spec: &str,
+    token: Option<Secret<String>>,
+    reg_or_index: Option<RegistryOrIndex>,
+) -> CargoResult<()> {
+    let spec = PackageIdSpec::parse(spec)?;
+    let Some(version) = spec.version() else {
+        bail!(
+            "`{spec}` must specify the version of the crate to promote, like `{}@1.0.0`",
+            spec.name()
+        );
+    };
+    let name = spec.name();
+    let vers = version.to_string();
+
+    let source_ids = super::get_source_id(gctx, reg_or_index.as_ref())?;
+    let (mut registry, _) = super::registry(
+        gctx,
+        &source_ids,
+        token.as_ref().map(Secret::as_deref),
+        reg_or_index.as_ref(),
+        true,
+        Some(Operation::Promote { name, vers: &vers }),
+    )?;
+
+    gctx.shell()
+        .status("Promoting", format!("{name} v{vers}"))?;
+    registry.promote(name, &vers).with_context(|| {
+        format!(
+            "failed to promote `{name}@{vers}` in the registry at {}",
+            registry.host()
+        )
+    })?;
+
+    let timeout = publish_timeout(gctx)?;
+    if 0 < timeout {
+        let pkg_id = PackageId::new(name.into(), version, source_ids.original);
+        wait_for_any_publish_confirmation(
+            gctx,
+            source_ids.original,
+            &BTreeSet::from([pkg_id]),
+            Duration::from_secs(timeout),
+        )?;
+    }
+    Ok(())
+}
+
+/// How many seconds to wait for published packages to appear in the index.
+fn publish_timeout(gctx: &GlobalContext) -> CargoResult<u64> {
+    const DEFAULT_TIMEOUT: u64 = 60;
+    Ok(if gctx.cli_unstable().publish_timeout {
+        let timeout: Option<u64> = gctx.get("publish.timeout")?;
+        timeout.unwrap_or(DEFAULT_TIMEOUT)
+    } else {
+        DEFAULT_TIMEOUT
+    })
+}
+
 /// Poll the registry for any packages that are ready for use.
 ///
 /// Returns the subset of `pkgs` that are ready for use.
@@ -562,6 +651,7 @@ fn transmit(
     registry: &mut Registry,
     registry_id: SourceId,
     dry_run: bool,
+    stage: bool,
 ) -> CargoResult<()> {
     let new_crate = prepare_transmit(gctx, ws, pkg, registry_id)?;
 
@@ -571,9 +661,12 @@ fn transmit(
         return Ok(());
     }
 
-    let warnings = registry
-        .publish(&new_crate, tarball)
-        .with_context(|| format!("failed to publish to registry at {}", registry.host()))?;
+    let warnings = if stage {
+        registry.publish_staged(&new_crate, tarball)
+    } else {
+        registry.publish(&new_crate, tarball)
+    }
+    .with_context(|| format!("failed to publish to registry at {}", registry.host()))?;
 
     if !warnings.invalid_categories.is_empty() {
         let msg = format!(
@@ -607,6 +700,79 @@ fn transmit(
     Ok(())
 }
 
+/// Reads the commands of `publish.checks`.
+///
+/// Only config files are read, as an environment variable can't hold a list
+/// of commands with arguments. They are ignored without `-Zpublish-checks`.
+fn publish_checks(gctx: &GlobalContext) -> CargoResult<Vec<PathAndArgs>> {
+    if !gctx.cli_unstable().publish_checks {
+        return Ok(Vec::new());
+    }
+    Ok(gctx
+        .get_list("publish.checks")?
+        .map(|checks| checks.val)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(val, definition)| {
+            PathAndArgs::from_whitespace_separated_string(&Value { val, definition })
+        })
+        .collect())
+}
+
+/// Runs the `publish.checks` commands against the `.crate` file of `pkg`.
+///
+/// Each command runs in a directory where the `.crate` file is unpacked, and
+/// receives the JSON description of the package sent to the registry on its
+/// standard input. Publishing is aborted if any command fails.
+fn run_publish_checks(
+    ws: &Workspace<'_>,
+    pkg: &Package,
+    tarball: &File,
+    registry_id: SourceId,
+    checks: &[PathAndArgs],
+) -> CargoResult<()> {
+    let gctx = ws.gctx();
+    let new_crate = prepare_transmit(gctx, ws, pkg, registry_id)?;
+    let new_crate = serde_json::to_string(&new_crate)?;
+
+    let dir = ws
+        .target_dir()
+        .join("package")
+        .join("publish-checks")
+        .into_path_unlocked();
+    let dst = dir.join(format!("{}-{}", pkg.name(), pkg.version()));
+    if dir.exists() {
+        paths::remove_dir_all(&dir)?;
+    }
+    let result = (|| {
+        let mut tarball = tarball;
+        tarball.seek(SeekFrom::Start(0))?;
+        let mut archive = Archive::new(GzDecoder::new(tarball));
+        // The modification time isn't relevant to the checks, and setting it
+        // fails on some filesystems.
+        archive.set_preserve_mtime(false);
+        archive.unpack(&dir)?;
+
+        for check in checks {
+            let mut process = ProcessBuilder::new(check.path.resolve_program(gctx));
+            process.args(&check.args).cwd(&dst).stdin(new_crate.clone());
+            gctx.shell().status("Running", &process)?;
+            process.exec_with_output().with_context(|| {
+                format!(
+                    "publish check {} failed for `{}`, defined in {}",
+                    process,
+                    pkg.package_id(),
+                    check.path.value().definition
+                )
+            })?;
+        }
+        Ok(())
+    })();
+    // Report the failing check rather than a failure to clean up after it.
+    let cleanup = paths::remove_dir_all(&dir);
+    result.and(cleanup)
+}
+
 /// State for tracking dependencies during upload.
 struct PublishPlan {
     /// Graph of publishable packages where the edges are `(dependency -> dependent)`
@@ -3,8 +3,12 @@
 //! [1]: https://doc.rust-lang.org/nightly/cargo/reference/registry-web-api.html#search
 
 use std::cmp;
+use std::collections::HashSet;
 
 use anyhow::Context as _;
+use crates_io::Crate;
+use crates_io::SearchQuery;
+use serde::Serialize;
 use url::Url;
 
 use crate::util::style;
@@ -15,21 +19,87 @@ use crate::GlobalContext;
 
 use super::RegistryOrIndex;
 
-pub fn search(
-    query: &str,
-    gctx: &GlobalContext,
-    reg_or_index: Option<RegistryOrIndex>,
-    limit: u32,
-) -> CargoResult<()> {
-    let source_ids = super::get_source_id(gctx, reg_or_index.as_ref())?;
-    let (mut registry, _) =
-        super::registry(gctx, &source_ids, None, reg_or_index.as_ref(), false, None)?;
-    let (crates, total_crates) = registry.search(query, limit).with_context(|| {
-        format!(
-            "failed to retrieve search results from the registry at {}",
-            registry.host()
-        )
-    })?;
+/// The maximum number of results requested from the registry at once.
+const MAX_PER_PAGE: u32 = 100;
+
+/// The maximum number of results shown, over all pages.
+const MAX_LIMIT: u32 = 1000;
+
+pub struct SearchOptions {
+    pub query: String,
+    pub reg_or_index: Option<RegistryOrIndex>,
+    /// The maximum number of results, which may span several pages. It is
+    /// capped at 1000.
+    pub limit: u32,
+    pub sort: Option<String>,
+    pub category: Option<String>,
+    pub keyword: Option<String>,
+    /// Print the results as JSON instead of TOML.
+    pub json: bool,
+}
+
+/// The output of `cargo search --json`.
+#[derive(Serialize)]
+struct SearchOutput<'a> {
+    crates: &'a [Crate],
+    total: u32,
+}
+
+pub fn search(gctx: &GlobalContext, opts: &SearchOptions) -> CargoResult<()> {
+    let query = opts.query.as_str();
+    let reg_or_index = opts.reg_or_index.as_ref();
+    let limit = cmp::min(opts.limit, MAX_LIMIT);
+    let source_ids = super::get_source_id(gctx, reg_or_index)?;
+    let (mut registry, _) = super::registry(gctx, &source_ids, None, reg_or_index, false, None)?;
+    let search_query = SearchQuery {
+        query,
+        sort: opts.sort.as_deref(),
+        category: opts.category.as_deref(),
+        keyword: opts.keyword.as_deref(),
+    };
+    let per_page = cmp::min(limit, MAX_PER_PAGE);
+    let mut crates = Vec::new();
+    let mut names = HashSet::new();
+    let mut total_crates;
+    // Whether the registry returned all the pages it was asked for.
+    let mut paged = true;
+    let mut page = 1;
+    loop {
+        let (results, total) = registry
+            .search_page(&search_query, page, per_page)
+            .with_context(|| {
+                format!(
+                    "failed to retrieve search results from the registry at {}",
+                    registry.host()
+                )
+            })?;
+        total_crates = total;
+        let last_page = (results.len() as u32) < per_page;
+        let found = crates.len();
+        crates.extend(
+            results
+                .into_iter()
+                .filter(|krate| names.insert(krate.name.clone())),
+        );
+        if crates.len() == found && !last_page {
+            // Registries which don't support paging return the first page
+            // again, so give up on finding more results.
+            paged = false;
+            break;
+        }
+        if last_page || crates.len() as u32 >= cmp::min(limit, total_crates) {
+            break;
+        }
+        page += 1;
+    }
+    crates.truncate(limit as usize);
+
+    if opts.json {
+        return gctx.shell().print_json(&SearchOutput {
+            crates: &crates,
+            total: total_crates,
+        });
+    }
 
     let names = crates
         .iter()
@@ -56,6 +126,10 @@ pub fn search(
             Some(desc) => format!("{name: <description_margin$}# {desc}"),
             None => name,
         };
+        if query.is_empty() {
+            let _ = writeln!(stdout, "{line}");
+            continue;
+        }
         let mut fragments = line.split(query).peekable();
         while let Some(fragment) = fragments.next() {
             let _ = write!(stdout, "{fragment}");
@@ -66,14 +140,14 @@ pub fn search(
         let _ = writeln!(stdout);
     }
 
-    let search_max_limit = 100;
-    if total_crates > limit && limit < search_max_limit {
+    let shown = crates.len() as u32;
+    if total_crates > shown && paged && opts.limit < MAX_LIMIT {
         let _ = writeln!(
             stdout,
             "... and {} crates more (use --limit N to see more)",
-            total_crates - limit
+            total_crates - shown
         );
-    } else if total_crates > limit && limit >= search_max_limit {
+    } else if total_crates > shown {
         let extra = if source_ids.original.is_crates_io() {
             let url = Url::parse_with_params("https://crates.io/search", &[("q", query)])?;
             format!(" (go to {url} to see more)")
@@ -83,7 +157,7 @@ pub fn search(
         let _ = writeln!(
             stdout,
             "... and {} crates more{}",
-            total_crates - limit,
+            total_crates - shown,
             extra
         );
     }
@@ -7,6 +7,8 @@ use anyhow::bail;
 use anyhow::Context as _;
 use cargo_credential::Operation;
 use cargo_credential::Secret;
+use crates_io::YankReason;
+use url::Url;
 
 use crate::core::Workspace;
 use crate::util::context::GlobalContext;
@@ -22,6 +24,8 @@ pub fn yank(
     token: Option<Secret<String>>,
     reg_or_index: Option<RegistryOrIndex>,
     undo: bool,
+    reason: Option<String>,
+    advisory: Option<String>,
 ) -> CargoResult<()> {
     let name = match krate {
         Some(name) => name,
@@ -34,6 +38,13 @@ pub fn yank(
     let Some(version) = version else {
         bail!("a version must be specified to yank")
     };
+    if let Some(advisory) = &advisory {
+        if let Err(e) = Url::parse(advisory) {
+            bail!("invalid advisory URL `{advisory}`: {e}");
+        }
+    }
+    let yank_reason =
+        (reason.is_some() || advisory.is_some()).then(|| YankReason { reason, advisory });
 
     let message = if undo {
         Operation::Unyank {
@@ -68,7 +79,7 @@ pub fn yank(
     } else {
         gctx.shell().status("Yank", package_spec)?;
         registry
-            .yank(&name, &version)
+            .yank(&name, &version, yank_reason.as_ref())
             .with_context(|| format!("failed to yank from the registry at {}", registry.host()))?;
     }
 
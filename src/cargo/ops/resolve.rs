@@ -268,8 +268,9 @@ pub fn resolve_ws_with_opts<'gctx>(
     })
 }
 
+/// Like [`resolve_ws`], but with the given registry, which stays usable after.
 #[tracing::instrument(skip_all)]
-fn resolve_with_registry<'gctx>(
+pub fn resolve_with_registry<'gctx>(
     ws: &Workspace<'gctx>,
     registry: &mut PackageRegistry<'gctx>,
     dry_run: bool,
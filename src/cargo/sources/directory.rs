@@ -108,7 +108,7 @@ impl<'gctx> Source for DirectorySource<'gctx> {
         }
         let packages = self.packages.values().map(|p| &p.0);
         let matches = packages.filter(|pkg| match kind {
-            QueryKind::Exact => dep.matches(pkg.summary()),
+            QueryKind::Exact | QueryKind::ExactWithYanked => dep.matches(pkg.summary()),
             QueryKind::Alternatives => true,
             QueryKind::Normalized => dep.matches(pkg.summary()),
         });
@@ -12,6 +12,7 @@ pub use self::utils::{fetch, resolve_ref, GitCheckout, GitDatabase, GitRemote};
 mod known_hosts;
 mod oxide;
 mod source;
+mod sparse;
 mod utils;
 
 /// For `-Zgitoxide` integration.
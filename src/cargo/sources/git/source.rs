@@ -4,6 +4,8 @@ use crate::core::global_cache_tracker;
 use crate::core::GitReference;
 use crate::core::SourceId;
 use crate::core::{Dependency, Package, PackageId};
+use crate::sources::git::sparse::RepoManifests;
+use crate::sources::git::sparse::SparseCheckout;
 use crate::sources::git::utils::rev_to_oid;
 use crate::sources::git::utils::GitRemote;
 use crate::sources::source::MaybePackage;
@@ -18,6 +20,7 @@ use crate::util::interning::InternedString;
 use crate::util::GlobalContext;
 use anyhow::Context as _;
 use cargo_util::paths::exclude_from_backups_and_indexing;
+use std::collections::BTreeSet;
 use std::fmt::{self, Debug, Formatter};
 use std::task::Poll;
 use tracing::trace;
@@ -56,6 +59,17 @@ use url::Url;
 /// For more on the directory format `<pkg>-<hash>[-shallow]`, see [`ident`]
 /// and [`ident_shallow`].
 ///
+/// ## Sparse checkouts
+///
+/// With `-Zgit=sparse-checkout`, only the directories of the packages queried
+/// from this source are checked out, along with their in-repository `path`
+/// dependencies and the manifest of their workspace root. Those checkouts
+/// reside at `-sparse` suffixed directories, e.g. `checkouts/gimli-*/8e73ef0-sparse`.
+/// See [`sparse`](super::sparse) for how the paths are picked.
+///
+/// Packages queried after the checkout was made extend it with their own
+/// directories.
+///
 /// ## Locked to a revision
 ///
 /// Once a `GitSource` is fetched, it will resolve to a specific commit revision.
@@ -83,7 +97,8 @@ pub struct GitSource<'gctx> {
     /// A short string that uniquely identifies the version of the checkout.
     ///
     /// This is typically a 7-character string of the OID hash, automatically
-    /// increasing in size if it is ambiguous.
+    /// increasing in size if it is ambiguous, and suffixed with `-sparse` for
+    /// sparse checkouts.
     ///
     /// This is set to `Some` after the git repo has been checked out
     /// (automatically handled via [`GitSource::block_until_ready`]).
@@ -91,6 +106,16 @@ pub struct GitSource<'gctx> {
     /// The identifier of this source for Cargo's Git cache directory.
     /// See [`ident`] for more.
     ident: InternedString,
+    /// Names of the packages queried from this source, if sparse checkouts are
+    /// enabled.
+    requested: Option<BTreeSet<InternedString>>,
+    /// The current sparse checkout, or `None` for a full checkout.
+    sparse: Option<SparseState>,
+    /// Whether a dependency queried from this source requires the locked
+    /// revision to be signed.
+    verify_signature: bool,
+    /// The revision whose signature has been verified.
+    verified_rev: Option<git2::Oid>,
     gctx: &'gctx GlobalContext,
     /// Disables status messages.
     quiet: bool,
@@ -115,6 +140,12 @@ impl<'gctx> GitSource<'gctx> {
                 .map_or(false, |features| features.shallow_deps),
         );
 
+        let requested = gctx
+            .cli_unstable()
+            .git
+            .map_or(false, |features| features.sparse_checkout)
+            .then(BTreeSet::new);
+
         let source = GitSource {
             remote,
             locked_rev,
@@ -122,6 +153,10 @@ impl<'gctx> GitSource<'gctx> {
             path_source: None,
             short_id: None,
             ident: ident.into(),
+            requested,
+            sparse: None,
+            verify_signature: false,
+            verified_rev: None,
             gctx,
             quiet: false,
         };
@@ -138,13 +173,48 @@ impl<'gctx> GitSource<'gctx> {
     /// repository as well as walk the filesystem if package information
     /// haven't yet updated.
     pub fn read_packages(&mut self) -> CargoResult<Vec<Package>> {
-        if self.path_source.is_none() {
+        if self.path_source.is_none() || self.sparse.is_some() {
+            // All packages are needed, hence a full checkout.
+            self.requested = None;
+            self.sparse = None;
+            self.path_source = None;
             self.invalidate_cache();
             self.block_until_ready()?;
         }
         self.path_source.as_mut().unwrap().read_packages()
     }
 
+    /// Verifies the signature of the locked revision, once the Git database
+    /// has it.
+    ///
+    /// See [`GitDatabase::verify_signature`](super::GitDatabase::verify_signature).
+    fn check_signature(&mut self) -> CargoResult<()> {
+        let Revision::Locked(rev) = self.locked_rev else {
+            return Ok(());
+        };
+        if !self.verify_signature || self.verified_rev == Some(rev) {
+            return Ok(());
+        }
+        let db_path = self.gctx.git_db_path().join(&self.ident);
+        let db = self.remote.db_at(db_path.as_path_unlocked())?;
+        let allowed_signers = self
+            .gctx
+            .net_config()?
+            .git_allowed_signers
+            .as_ref()
+            .map(|path| path.resolve_path(self.gctx));
+        let reference = self.source_id.git_reference().unwrap();
+        db.verify_signature(rev, reference, allowed_signers.as_deref(), self.gctx)
+            .with_context(|| {
+                format!(
+                    "revision `{rev}` of git repository `{}` is not signed by an allowed signer",
+                    self.remote.url()
+                )
+            })?;
+        self.verified_rev = Some(rev);
+        Ok(())
+    }
+
     fn mark_used(&self) -> CargoResult<()> {
         self.gctx
             .deferred_global_last_use()?
@@ -157,6 +227,23 @@ impl<'gctx> GitSource<'gctx> {
     }
 }
 
+/// A sparse checkout of a [`GitSource`].
+struct SparseState {
+    /// All manifests of the checked out revision.
+    manifests: RepoManifests,
+    /// Names of the packages available in the checkout.
+    packages: BTreeSet<InternedString>,
+}
+
+impl SparseState {
+    /// Checks if a query for the package `name` can be answered by the
+    /// checkout, which is the case if the package is either checked out or
+    /// doesn't exist at all.
+    fn covers(&self, name: InternedString) -> bool {
+        self.packages.contains(&name) || !self.manifests.contains_package(name)
+    }
+}
+
 /// Indicates a [Git revision] that might be locked or deferred to be resolved.
 ///
 /// [Git revision]: https://git-scm.com/docs/revisions
@@ -243,6 +330,26 @@ impl<'gctx> Source for GitSource<'gctx> {
         kind: QueryKind,
         f: &mut dyn FnMut(IndexSummary),
     ) -> Poll<CargoResult<()>> {
+        if let Some(requested) = self.requested.as_mut() {
+            requested.insert(dep.package_name());
+        }
+        if dep.verify_signature() {
+            self.verify_signature = true;
+        }
+        if let Some(sparse) = &self.sparse {
+            if !sparse.covers(dep.package_name()) {
+                // Extend the checkout with this package next time we're
+                // ready.
+                self.path_source = None;
+                self.sparse = None;
+                return Poll::Pending;
+            }
+        }
+        if self.path_source.is_some() {
+            if let Err(e) = self.check_signature() {
+                return Poll::Ready(Err(e));
+            }
+        }
         if let Some(src) = self.path_source.as_mut() {
             src.query(dep, kind, f)
         } else {
@@ -328,8 +435,34 @@ impl<'gctx> Source for GitSource<'gctx> {
 
                 trace!("updating git source `{:?}`", self.remote);
 
+                // With `-Zgit=ls-remote`, ask the remote where the reference
+                // points to before fetching. If the database has that commit
+                // already, there is nothing to fetch.
                 let locked_rev = locked_rev.clone().into();
-                self.remote.checkout(&db_path, db, &locked_rev, self.gctx)?
+                let ls_remote = self
+                    .gctx
+                    .cli_unstable()
+                    .git
+                    .map_or(false, |features| features.ls_remote);
+                let up_to_date = match &db {
+                    Some(db) if ls_remote => self.remote.up_to_date(db, &locked_rev, self.gctx),
+                    _ => None,
+                };
+
+                match (db, up_to_date) {
+                    (Some(db), Some(rev)) => {
+                        if !self.quiet {
+                            self.gctx.shell().verbose(|shell| {
+                                shell.status(
+                                    "Fresh",
+                                    format!("git repository `{}` is up to date", self.remote.url()),
+                                )
+                            })?;
+                        }
+                        (db, rev)
+                    }
+                    (db, _) => self.remote.checkout(&db_path, db, &locked_rev, self.gctx)?,
+                }
             }
         };
 
@@ -338,16 +471,49 @@ impl<'gctx> Source for GitSource<'gctx> {
         // <https://github.com/servo/servo/pull/14397>.
         let short_id = db.to_short_id(actual_rev)?;
 
+        self.locked_rev = Revision::Locked(actual_rev);
+        self.check_signature()?;
+
+        let sparse = match &self.requested {
+            // Nothing has been queried yet, so we can't tell which packages to
+            // check out. Defer the checkout until the first query.
+            Some(requested) if requested.is_empty() => return Ok(()),
+            Some(requested) => {
+                let manifests = RepoManifests::read(&db, actual_rev)?;
+                manifests
+                    .sparse_checkout(requested)
+                    .map(|checkout| (manifests, checkout))
+            }
+            None => None,
+        };
+
         // Check out `actual_rev` from the database to a scoped location on the
         // filesystem. This will use hard links and such to ideally make the
         // checkout operation here pretty fast.
+        let checkout_name = match &sparse {
+            Some(_) => format!("{}-sparse", short_id.as_str()),
+            None => short_id.as_str().to_string(),
+        };
         let checkout_path = self
             .gctx
             .git_checkouts_path()
             .join(&self.ident)
-            .join(short_id.as_str());
+            .join(&checkout_name);
         let checkout_path = checkout_path.into_path_unlocked();
-        db.copy_to(actual_rev, &checkout_path, self.gctx)?;
+        self.sparse = match sparse {
+            Some((manifests, SparseCheckout { paths, packages })) => {
+                trace!("sparse checkout of `{:?}`: {:?}", self.remote, paths);
+                db.copy_to_sparse(actual_rev, &checkout_path, &paths, self.gctx)?;
+                Some(SparseState {
+                    manifests,
+                    packages,
+                })
+            }
+            None => {
+                db.copy_to(actual_rev, &checkout_path, self.gctx)?;
+                None
+            }
+        };
 
         let source_id = self
             .source_id
@@ -355,8 +521,7 @@ impl<'gctx> Source for GitSource<'gctx> {
         let path_source = RecursivePathSource::new(&checkout_path, source_id, self.gctx);
 
         self.path_source = Some(path_source);
-        self.short_id = Some(short_id.as_str().into());
-        self.locked_rev = Revision::Locked(actual_rev);
+        self.short_id = Some(checkout_name.as_str().into());
         self.path_source.as_mut().unwrap().load()?;
 
         self.mark_used()?;
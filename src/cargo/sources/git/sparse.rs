@@ -0,0 +1,226 @@
+//! Selection of the files to check out for a sparse checkout of a git
+//! dependency (`-Zgit=sparse-checkout`).
+//!
+//! Instead of checking out the whole tree of a revision, only the directories
+//! of the packages requested from a [`GitSource`] are checked out, together
+//! with the directories of their in-repository `path` dependencies, and the
+//! manifest of the workspace root they inherit from.
+//!
+//! This is decided by reading every `Cargo.toml` from the Git database,
+//! without touching the filesystem. When the requested packages can't be
+//! located, or one of them lives at the root of the repository, the caller
+//! falls back to a full checkout.
+//!
+//! [`GitSource`]: super::GitSource
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+use cargo_util_schemas::manifest::InheritableDependency;
+use cargo_util_schemas::manifest::TomlDependency;
+use cargo_util_schemas::manifest::TomlManifest;
+use tracing::debug;
+
+use crate::sources::git::GitDatabase;
+use crate::util::errors::CargoResult;
+use crate::util::interning::InternedString;
+
+/// All manifests found in the tree of a revision of a Git repository.
+pub struct RepoManifests {
+    /// Parsed manifests, keyed by their directory relative to the repository
+    /// root, using `/` as separator. The root directory is the empty string.
+    manifests: BTreeMap<String, TomlManifest>,
+}
+
+/// The outcome of [`RepoManifests::sparse_checkout`].
+pub struct SparseCheckout {
+    /// Paths to check out, relative to the repository root.
+    pub paths: BTreeSet<String>,
+    /// Names of the packages whose directories are part of the checkout.
+    pub packages: BTreeSet<InternedString>,
+}
+
+impl RepoManifests {
+    /// Reads every `Cargo.toml` in the tree of `rev`.
+    ///
+    /// Manifests which fail to parse are ignored, like the [`RecursivePathSource`]
+    /// does for manifests of a git repository.
+    ///
+    /// [`RecursivePathSource`]: crate::sources::RecursivePathSource
+    pub fn read(db: &GitDatabase, rev: git2::Oid) -> CargoResult<RepoManifests> {
+        let mut manifests = BTreeMap::new();
+        for (dir, contents) in db.manifests(rev)? {
+            let manifest = std::str::from_utf8(&contents)
+                .ok()
+                .and_then(|contents| toml::from_str::<TomlManifest>(contents).ok());
+            match manifest {
+                Some(manifest) => {
+                    manifests.insert(dir, manifest);
+                }
+                None => debug!("skipping malformed manifest in `{dir}`"),
+            }
+        }
+        Ok(RepoManifests { manifests })
+    }
+
+    /// Checks if a package called `name` exists anywhere in the repository.
+    pub fn contains_package(&self, name: InternedString) -> bool {
+        self.manifests
+            .values()
+            .any(|manifest| package_name(manifest) == Some(name.as_str()))
+    }
+
+    /// Computes the paths to check out for the packages called `names`.
+    ///
+    /// Returns `None` if a full checkout is needed instead.
+    pub fn sparse_checkout(&self, names: &BTreeSet<InternedString>) -> Option<SparseCheckout> {
+        let mut queue = Vec::new();
+        for name in names {
+            let dirs: Vec<_> = self
+                .manifests
+                .iter()
+                .filter(|(_, manifest)| package_name(manifest) == Some(name.as_str()))
+                .map(|(dir, _)| dir.clone())
+                .collect();
+            if dirs.is_empty() {
+                debug!("package `{name}` not found, falling back to a full checkout");
+                return None;
+            }
+            queue.extend(dirs);
+        }
+
+        let mut dirs = BTreeSet::new();
+        let mut paths = BTreeSet::new();
+        let mut packages = BTreeSet::new();
+        while let Some(dir) = queue.pop() {
+            if dir.is_empty() {
+                debug!("a package lives at the repository root, falling back to a full checkout");
+                return None;
+            }
+            if !dirs.insert(dir.clone()) {
+                continue;
+            }
+            let manifest = &self.manifests[&dir];
+            if let Some(name) = package_name(manifest) {
+                packages.insert(name.into());
+            }
+
+            let ws_root = self.workspace_root(&dir, manifest);
+            if let Some(root) = &ws_root {
+                paths.insert(join(root, "Cargo.toml"));
+            }
+            let ws_deps = ws_root
+                .as_ref()
+                .and_then(|root| Some((root, self.manifests[root].workspace.as_ref()?)))
+                .and_then(|(root, ws)| Some((root, ws.dependencies.as_ref()?)));
+
+            for (name, dep) in dependencies(manifest) {
+                let path = match dep {
+                    InheritableDependency::Value(TomlDependency::Detailed(dep)) => {
+                        dep.path.as_ref().and_then(|path| normalize(&dir, path))
+                    }
+                    InheritableDependency::Inherit(_) => ws_deps
+                        .and_then(|(root, deps)| match deps.get(name)? {
+                            TomlDependency::Detailed(dep) => Some((root, dep.path.as_ref()?)),
+                            TomlDependency::Simple(_) => None,
+                        })
+                        .and_then(|(root, path)| normalize(root, path)),
+                    InheritableDependency::Value(TomlDependency::Simple(_)) => None,
+                };
+                if let Some(path) = path.filter(|path| self.manifests.contains_key(path)) {
+                    queue.push(path);
+                }
+            }
+        }
+
+        paths.extend(dirs);
+        Some(SparseCheckout { paths, packages })
+    }
+
+    /// Finds the directory of the workspace root of the package in `dir`,
+    /// either set explicitly with `package.workspace`, or the closest parent
+    /// directory with a `[workspace]` table.
+    fn workspace_root(&self, dir: &str, manifest: &TomlManifest) -> Option<String> {
+        if manifest.workspace.is_some() {
+            return None;
+        }
+        if let Some(ws) = manifest.package().and_then(|p| p.workspace.as_ref()) {
+            return normalize(dir, ws).filter(|root| self.manifests.contains_key(root));
+        }
+        let mut dir = dir;
+        while !dir.is_empty() {
+            dir = dir.rsplit_once('/').map_or("", |(parent, _)| parent);
+            if self
+                .manifests
+                .get(dir)
+                .map_or(false, |manifest| manifest.workspace.is_some())
+            {
+                return Some(dir.to_string());
+            }
+        }
+        None
+    }
+}
+
+fn package_name(manifest: &TomlManifest) -> Option<&str> {
+    manifest.package().map(|p| p.name.as_str())
+}
+
+/// Dependencies which may be needed to build the package, which excludes
+/// `dev-dependencies`.
+fn dependencies(
+    manifest: &TomlManifest,
+) -> impl Iterator<Item = (&str, &InheritableDependency)> + '_ {
+    manifest
+        .dependencies
+        .iter()
+        .chain(manifest.build_dependencies())
+        .chain(
+            manifest
+                .target
+                .iter()
+                .flat_map(|t| t.values())
+                .flat_map(|t| t.dependencies.iter().chain(t.build_dependencies())),
+        )
+        .flat_map(|deps| deps.iter())
+        .map(|(name, dep)| (name.as_str(), dep))
+}
+
+/// Joins `path` to the repository relative `dir`, resolving `.` and `..`.
+///
+/// Returns `None` if the result is outside of the repository.
+fn normalize(dir: &str, path: &str) -> Option<String> {
+    let mut components: Vec<&str> = dir.split('/').filter(|c| !c.is_empty()).collect();
+    for component in path.split(['/', '\\']) {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop()?;
+            }
+            c => components.push(c),
+        }
+    }
+    Some(components.join("/"))
+}
+
+fn join(dir: &str, file: &str) -> String {
+    if dir.is_empty() {
+        file.to_string()
+    } else {
+        format!("{dir}/{file}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize;
+
+    #[test]
+    fn normalize_paths() {
+        assert_eq!(normalize("a/b", "../c").as_deref(), Some("a/c"));
+        assert_eq!(normalize("a", "./b/../c/").as_deref(), Some("a/c"));
+        assert_eq!(normalize("", "a").as_deref(), Some("a"));
+        assert_eq!(normalize("a", "..").as_deref(), Some(""));
+        assert_eq!(normalize("a", "../.."), None);
+    }
+}
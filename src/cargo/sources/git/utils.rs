@@ -16,6 +16,7 @@ use git2::{ErrorClass, ObjectType, Oid};
 use serde::ser;
 use serde::Serialize;
 use std::borrow::Cow;
+use std::collections::BTreeSet;
 use std::fmt;
 use std::path::{Path, PathBuf};
 use std::process::Command;
@@ -151,6 +152,51 @@ impl GitRemote {
         ))
     }
 
+    /// Checks whether `db` already has the commit the named `reference`
+    /// points to on this remote, by listing the references of the remote
+    /// like `git ls-remote` does, rather than fetching.
+    ///
+    /// Returns the revision `reference` resolves to if no fetch is needed.
+    /// Like [`github_fast_path`], this never returns an error: when the
+    /// remote can't be queried, the caller is expected to fetch anyway.
+    pub fn up_to_date(
+        &self,
+        db: &GitDatabase,
+        reference: &GitReference,
+        gctx: &GlobalContext,
+    ) -> Option<git2::Oid> {
+        let ref_name = match reference {
+            GitReference::Branch(branch) => format!("refs/heads/{branch}"),
+            GitReference::Tag(tag) => format!("refs/tags/{tag}"),
+            GitReference::DefaultBranch => String::from("HEAD"),
+            GitReference::Rev(rev) if rev.starts_with("refs/") => rev.clone(),
+            // Commits are handled by checking whether the database contains
+            // them already.
+            GitReference::Rev(_) => return None,
+        };
+        if gctx.frozen() || !gctx.network_allowed() {
+            return None;
+        }
+        let local = resolve_ref(reference, &db.repo).ok()?;
+        match ls_remote(&db.repo, self.url.as_str(), &ref_name, gctx) {
+            Ok(Some(remote)) if remote == local => {
+                debug!("`{ref_name}` of {} is up to date at {local}", self.url);
+                Some(local)
+            }
+            Ok(remote) => {
+                debug!(
+                    "`{ref_name}` of {} needs a fetch, local {local}, remote {remote:?}",
+                    self.url
+                );
+                None
+            }
+            Err(e) => {
+                debug!("failed to list references of {}: {e:?}", self.url);
+                None
+            }
+        }
+    }
+
     /// Creates a [`GitDatabase`] of this remote at `db_path`.
     pub fn db_at(&self, db_path: &Path) -> CargoResult<GitDatabase> {
         let repo = git2::Repository::open(db_path)?;
@@ -181,12 +227,75 @@ impl GitDatabase {
             .filter(|co| co.is_fresh())
         {
             Some(co) => co,
-            None => GitCheckout::clone_into(dest, self, rev, gctx)?,
+            None => GitCheckout::clone_into(dest, self, rev, None, gctx)?,
+        };
+        checkout.update_submodules(gctx, None)?;
+        Ok(checkout)
+    }
+
+    /// Like [`GitDatabase::copy_to`], but only checks out the files under
+    /// `paths`, which are relative to the root of the repository.
+    ///
+    /// An existing checkout at `dest` is extended if it doesn't contain all
+    /// of `paths` yet.
+    #[tracing::instrument(skip(self, gctx))]
+    pub fn copy_to_sparse(
+        &self,
+        rev: git2::Oid,
+        dest: &Path,
+        paths: &BTreeSet<String>,
+        gctx: &GlobalContext,
+    ) -> CargoResult<GitCheckout<'_>> {
+        let checkout = match git2::Repository::open(dest)
+            .ok()
+            .map(|repo| GitCheckout::new(self, rev, repo))
+            .filter(|co| co.is_fresh())
+        {
+            Some(co) => {
+                let mut sparse_paths = co.sparse_paths();
+                if !paths.is_subset(&sparse_paths) {
+                    sparse_paths.extend(paths.iter().cloned());
+                    co.reset(Some(&sparse_paths), gctx)?;
+                }
+                co
+            }
+            None => GitCheckout::clone_into(dest, self, rev, Some(paths), gctx)?,
         };
-        checkout.update_submodules(gctx)?;
+        checkout.update_submodules(gctx, Some(&checkout.sparse_paths()))?;
         Ok(checkout)
     }
 
+    /// Reads the contents of every `Cargo.toml` in the tree of `rev`, keyed by
+    /// the directory containing it, relative to the root of the repository.
+    pub fn manifests(&self, rev: git2::Oid) -> CargoResult<Vec<(String, Vec<u8>)>> {
+        let tree = self.repo.find_commit(rev)?.tree()?;
+        let mut manifests = Vec::new();
+        let mut error = None;
+        tree.walk(git2::TreeWalkMode::PreOrder, |dir, entry| {
+            if entry.kind() != Some(ObjectType::Blob) || entry.name_bytes() != b"Cargo.toml" {
+                return git2::TreeWalkResult::Ok;
+            }
+            let Ok(dir) = str::from_utf8(dir.as_bytes()) else {
+                return git2::TreeWalkResult::Ok;
+            };
+            match self.repo.find_blob(entry.id()) {
+                Ok(blob) => {
+                    let dir = dir.trim_end_matches('/').to_string();
+                    manifests.push((dir, blob.content().to_vec()));
+                    git2::TreeWalkResult::Ok
+                }
+                Err(e) => {
+                    error = Some(e);
+                    git2::TreeWalkResult::Abort
+                }
+            }
+        })?;
+        if let Some(e) = error {
+            return Err(e.into());
+        }
+        Ok(manifests)
+    }
+
     /// Get a short OID for a `revision`, usually 7 chars or more if ambiguous.
     pub fn to_short_id(&self, revision: git2::Oid) -> CargoResult<GitShortID> {
         let obj = self.repo.find_object(revision, None)?;
@@ -202,6 +311,61 @@ impl GitDatabase {
     pub fn resolve(&self, r: &GitReference) -> CargoResult<git2::Oid> {
         resolve_ref(r, &self.repo)
     }
+
+    /// Verifies the GPG or SSH signature of the commit `rev` with the `git` CLI,
+    /// as libgit2 is unable to do so.
+    ///
+    /// For a [`GitReference::Tag`], a valid signature of the annotated tag
+    /// pointing to `rev` is accepted as well. SSH signatures are checked against
+    /// the `allowed_signers` file, if any.
+    pub fn verify_signature(
+        &self,
+        rev: git2::Oid,
+        reference: &GitReference,
+        allowed_signers: Option<&Path>,
+        gctx: &GlobalContext,
+    ) -> CargoResult<()> {
+        let verify = |subcommand: &str, object: &str| -> CargoResult<()> {
+            let mut cmd = ProcessBuilder::new("git");
+            if let Some(allowed_signers) = allowed_signers {
+                cmd.arg("-c").arg(format!(
+                    "gpg.ssh.allowedSignersFile={}",
+                    allowed_signers.display()
+                ));
+            }
+            cmd.arg(subcommand)
+                .arg(object)
+                // See `fetch_with_cli` for why these are removed.
+                .env_remove("GIT_DIR")
+                .env_remove("GIT_WORK_TREE")
+                .env_remove("GIT_INDEX_FILE")
+                .env_remove("GIT_OBJECT_DIRECTORY")
+                .env_remove("GIT_ALTERNATE_OBJECT_DIRECTORIES")
+                .cwd(&self.path);
+            gctx.shell()
+                .verbose(|s| s.status("Running", &cmd.to_string()))?;
+            cmd.exec_with_output()?;
+            Ok(())
+        };
+
+        if let GitReference::Tag(tag) = reference {
+            // Tags are fetched to this location, see `fetch`.
+            let refname = format!("refs/remotes/origin/tags/{tag}");
+            let tag_points_to_rev = self
+                .repo
+                .refname_to_id(&refname)
+                .and_then(|id| self.repo.find_tag(id))
+                .and_then(|tag| tag.target()?.peel(ObjectType::Commit))
+                .map_or(false, |commit| commit.id() == rev);
+            if tag_points_to_rev {
+                match verify("verify-tag", &refname) {
+                    Ok(()) => return Ok(()),
+                    Err(e) => debug!("tag `{tag}` is not signed, verifying its commit: {e:?}"),
+                }
+            }
+        }
+        verify("verify-commit", &rev.to_string())
+    }
 }
 
 /// Resolves [`GitReference`] to an object ID with objects the `repo` currently has.
@@ -275,10 +439,13 @@ impl<'a> GitCheckout<'a> {
 
     /// Clone a repo for a `revision` into a local path from a `datatabase`.
     /// This is a filesystem-to-filesystem clone.
+    ///
+    /// If `sparse_paths` is set, only the files under those paths are checked out.
     fn clone_into(
         into: &Path,
         database: &'a GitDatabase,
         revision: git2::Oid,
+        sparse_paths: Option<&BTreeSet<String>>,
         gctx: &GlobalContext,
     ) -> CargoResult<GitCheckout<'a>> {
         let dirname = into.parent().unwrap();
@@ -329,7 +496,7 @@ impl<'a> GitCheckout<'a> {
         let repo = repo.unwrap();
 
         let checkout = GitCheckout::new(database, revision, repo);
-        checkout.reset(gctx)?;
+        checkout.reset(sparse_paths, gctx)?;
         Ok(checkout)
     }
 
@@ -357,8 +524,15 @@ impl<'a> GitCheckout<'a> {
     /// ready to go. Hence if we start to do a reset, we make sure this file
     /// *doesn't* exist, and then once we're done we create the file.
     ///
+    /// For a sparse checkout, only the files under `sparse_paths` are checked
+    /// out, and those paths are recorded in [`.cargo-ok`], one per line.
+    ///
     /// [`.cargo-ok`]: CHECKOUT_READY_LOCK
-    fn reset(&self, gctx: &GlobalContext) -> CargoResult<()> {
+    fn reset(
+        &self,
+        sparse_paths: Option<&BTreeSet<String>>,
+        gctx: &GlobalContext,
+    ) -> CargoResult<()> {
         let ok_file = self.path.join(CHECKOUT_READY_LOCK);
         let _ = paths::remove_file(&ok_file);
         info!("reset {} to {}", self.repo.path().display(), self.revision);
@@ -369,29 +543,59 @@ impl<'a> GitCheckout<'a> {
         }
 
         let object = self.repo.find_object(self.revision, None)?;
-        reset(&self.repo, &object, gctx)?;
-        paths::create(ok_file)?;
+        match sparse_paths {
+            Some(sparse_paths) => {
+                reset_sparse(&self.repo, &object, sparse_paths, gctx)?;
+                let contents: String = sparse_paths.iter().map(|p| format!("{p}\n")).collect();
+                paths::write(ok_file, contents)?;
+            }
+            None => {
+                reset(&self.repo, &object, gctx)?;
+                paths::create(ok_file)?;
+            }
+        }
         Ok(())
     }
 
+    /// Paths checked out by a sparse checkout, as recorded by
+    /// [`GitCheckout::reset`]. Empty for a full checkout.
+    fn sparse_paths(&self) -> BTreeSet<String> {
+        paths::read(&self.path.join(CHECKOUT_READY_LOCK))
+            .map(|contents| contents.lines().map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+
     /// Like `git submodule update --recursive` but for this git checkout.
     ///
     /// This function respects `submodule.<name>.update = none`[^1] git config.
     /// Submodules set to `none` won't be fetched.
     ///
     /// [^1]: <https://git-scm.com/docs/git-submodule#Documentation/git-submodule.txt-none>
-    fn update_submodules(&self, gctx: &GlobalContext) -> CargoResult<()> {
-        return update_submodules(&self.repo, gctx, self.remote_url().as_str());
+    ///
+    /// For a sparse checkout, only the submodules under `sparse_paths` are
+    /// updated.
+    fn update_submodules(
+        &self,
+        gctx: &GlobalContext,
+        sparse_paths: Option<&BTreeSet<String>>,
+    ) -> CargoResult<()> {
+        return update_submodules(&self.repo, gctx, self.remote_url().as_str(), sparse_paths);
 
         /// Recursive helper for [`GitCheckout::update_submodules`].
         fn update_submodules(
             repo: &git2::Repository,
             gctx: &GlobalContext,
             parent_remote_url: &str,
+            sparse_paths: Option<&BTreeSet<String>>,
         ) -> CargoResult<()> {
             debug!("update submodules for: {:?}", repo.workdir().unwrap());
 
             for mut child in repo.submodules()? {
+                if let Some(sparse_paths) = sparse_paths {
+                    if !sparse_paths.iter().any(|p| child.path().starts_with(p)) {
+                        continue;
+                    }
+                }
                 update_submodule(repo, &mut child, gctx, parent_remote_url).with_context(|| {
                     format!(
                         "failed to update submodule `{}`",
@@ -446,7 +650,7 @@ impl<'a> GitCheckout<'a> {
             let mut repo = match head_and_repo {
                 Ok((head, repo)) => {
                     if child.head_id() == head {
-                        return update_submodules(&repo, gctx, &child_remote_url);
+                        return update_submodules(&repo, gctx, &child_remote_url, None);
                     }
                     repo
                 }
@@ -474,7 +678,7 @@ impl<'a> GitCheckout<'a> {
 
             let obj = repo.find_object(head, None)?;
             reset(&repo, &obj, gctx)?;
-            update_submodules(&repo, gctx, &child_remote_url)
+            update_submodules(&repo, gctx, &child_remote_url, None)
         }
     }
 }
@@ -813,6 +1017,30 @@ fn reset(repo: &git2::Repository, obj: &git2::Object<'_>, gctx: &GlobalContext)
     Ok(())
 }
 
+/// Like [`reset()`], but only checks out the files under `sparse_paths`.
+///
+/// The index still records the whole tree, so files outside of
+/// `sparse_paths` merely appear as deleted in the working tree.
+fn reset_sparse(
+    repo: &git2::Repository,
+    obj: &git2::Object<'_>,
+    sparse_paths: &BTreeSet<String>,
+    gctx: &GlobalContext,
+) -> CargoResult<()> {
+    let mut pb = Progress::new("Checkout", gctx);
+    let mut opts = git2::build::CheckoutBuilder::new();
+    opts.progress(|_, cur, max| {
+        drop(pb.tick(cur, max, ""));
+    });
+    for path in sparse_paths {
+        opts.path(path);
+    }
+    debug!("doing sparse reset");
+    repo.reset(obj, git2::ResetType::Hard, Some(&mut opts))?;
+    debug!("sparse reset done");
+    Ok(())
+}
+
 /// Prepares the callbacks for fetching a git repository.
 ///
 /// The main purpose of this function is to construct everything before a fetch.
@@ -912,6 +1140,136 @@ pub fn with_fetch_options(
     })
 }
 
+/// Looks up the commit the reference `ref_name` points to on the remote at
+/// `remote_url`, without fetching any objects. Annotated tags are peeled.
+///
+/// Like [`fetch`], this uses the git CLI, gitoxide, or libgit2. Unlike it,
+/// network errors are not retried, as the caller falls back to a fetch then.
+fn ls_remote(
+    repo: &git2::Repository,
+    remote_url: &str,
+    ref_name: &str,
+    gctx: &GlobalContext,
+) -> CargoResult<Option<Oid>> {
+    // Advertised references as `(name, object, peeled object)`.
+    let mut refs: Vec<(String, Oid, Option<Oid>)> = Vec::new();
+
+    if let Some(true) = gctx.net_config()?.git_fetch_with_cli {
+        let mut cmd = ProcessBuilder::new("git");
+        cmd.arg("ls-remote")
+            .arg(remote_url)
+            .arg(ref_name)
+            // Annotated tags are only peeled when asked for explicitly.
+            .arg(format!("{ref_name}^{{}}"))
+            // See `fetch_with_cli` for why these are removed.
+            .env_remove("GIT_DIR")
+            .env_remove("GIT_WORK_TREE")
+            .env_remove("GIT_INDEX_FILE")
+            .env_remove("GIT_OBJECT_DIRECTORY")
+            .env_remove("GIT_ALTERNATE_OBJECT_DIRECTORIES")
+            .cwd(repo.path());
+        gctx.shell()
+            .verbose(|s| s.status("Running", &cmd.to_string()))?;
+        let output = cmd.exec_with_output()?;
+        let mut peeled = Vec::new();
+        for line in str::from_utf8(&output.stdout)?.lines() {
+            let Some((oid, name)) = line.split_once('\t') else {
+                continue;
+            };
+            let oid = oid.parse::<Oid>()?;
+            match name.strip_suffix("^{}") {
+                Some(name) => peeled.push((name.to_string(), oid)),
+                None => refs.push((name.to_string(), oid, None)),
+            }
+        }
+        for (name, oid) in peeled {
+            if let Some(r) = refs.iter_mut().find(|r| r.0 == name) {
+                r.2 = Some(oid);
+            }
+        }
+    } else if gctx.cli_unstable().gitoxide.map_or(false, |git| git.fetch) {
+        let config_overrides = cargo_config_to_gitoxide_overrides(gctx)?;
+        let repo = oxide::open_repo(repo.path(), config_overrides, oxide::OpenMode::ForFetch)?;
+        let remote = repo
+            .remote_at(remote_url)?
+            .with_fetch_tags(gix::remote::fetch::Tags::None)
+            .with_refspecs([ref_name], gix::remote::Direction::Fetch)?;
+        let url = remote
+            .url(gix::remote::Direction::Fetch)
+            .expect("set at init")
+            .to_owned();
+        let connection = remote.connect(gix::remote::Direction::Fetch)?;
+        let authenticate = connection.configured_credentials(url)?;
+        let ref_map = connection.with_credentials(authenticate).ref_map(
+            &mut gix::progress::Discard,
+            gix::remote::ref_map::Options {
+                prefix_from_spec_as_filter_on_remote: true,
+                ..Default::default()
+            },
+        )?;
+        for r in &ref_map.remote_refs {
+            let (name, target, peeled) = r.unpack();
+            let (Some(target), Ok(name)) = (target, str::from_utf8(name)) else {
+                continue;
+            };
+            let oid = |id: &gix::oid| Oid::from_bytes(id.as_bytes()).ok();
+            if let Some(target) = oid(target) {
+                refs.push((name.to_string(), target, peeled.and_then(oid)));
+            }
+        }
+    } else {
+        let git_config = git2::Config::open_default()?;
+        let ssh_config = gctx.net_config()?.ssh.as_ref();
+        let config_known_hosts = ssh_config.and_then(|ssh| ssh.known_hosts.as_ref());
+        let diagnostic_home_config = gctx.diagnostic_home_config();
+        // See `with_fetch_options` for why the error is stored here.
+        let mut check_cb_result = Ok(());
+        let auth_result = with_authentication(gctx, remote_url, &git_config, |f| {
+            let port = Url::parse(remote_url).ok().and_then(|url| url.port());
+            let mut rcb = git2::RemoteCallbacks::new();
+            rcb.credentials(f);
+            rcb.certificate_check(|cert, host| {
+                super::known_hosts::certificate_check(
+                    gctx,
+                    cert,
+                    host,
+                    port,
+                    config_known_hosts,
+                    &diagnostic_home_config,
+                )
+                .map_err(|e| {
+                    check_cb_result = Err(e);
+                    git2::Error::from_str("invalid or unknown remote ssh hostkey")
+                })
+            });
+            let mut remote = repo.remote_anonymous(remote_url)?;
+            let connection = remote.connect_auth(git2::Direction::Fetch, Some(rcb), None)?;
+            let mut peeled = Vec::new();
+            for head in connection.list()? {
+                match head.name().strip_suffix("^{}") {
+                    Some(name) => peeled.push((name.to_string(), head.oid())),
+                    None => refs.push((head.name().to_string(), head.oid(), None)),
+                }
+            }
+            for (name, oid) in peeled {
+                if let Some(r) = refs.iter_mut().find(|r| r.0 == name) {
+                    r.2 = Some(oid);
+                }
+            }
+            Ok(())
+        });
+        if auth_result.is_err() {
+            check_cb_result?;
+        }
+        auth_result?;
+    }
+
+    Ok(refs
+        .into_iter()
+        .find(|(name, _, _)| name == ref_name)
+        .map(|(_, oid, peeled)| peeled.unwrap_or(oid)))
+}
+
 /// Attempts to fetch the given git `reference` for a Git repository.
 ///
 /// This is the main entry for git clone/fetch. It does the followings:
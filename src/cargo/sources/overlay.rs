@@ -141,6 +141,13 @@ impl<'gctx> Source for DependencyConfusionThreatOverlaySource<'gctx> {
         self.remote.is_yanked(pkg)
     }
 
+    fn yank_reason(
+        &mut self,
+        pkg: crate::core::PackageId,
+    ) -> std::task::Poll<crate::CargoResult<Option<crate::core::YankReason>>> {
+        self.remote.yank_reason(pkg)
+    }
+
     fn block_until_ready(&mut self) -> crate::CargoResult<()> {
         self.local.block_until_ready()?;
         self.remote.block_until_ready()
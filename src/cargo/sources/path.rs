@@ -145,7 +145,7 @@ impl<'gctx> Source for PathSource<'gctx> {
         self.load()?;
         if let Some(s) = self.package.as_ref().map(|p| p.summary()) {
             let matched = match kind {
-                QueryKind::Exact => dep.matches(s),
+                QueryKind::Exact | QueryKind::ExactWithYanked => dep.matches(s),
                 QueryKind::Alternatives => true,
                 QueryKind::Normalized => dep.matches(s),
             };
@@ -332,7 +332,7 @@ impl<'gctx> Source for RecursivePathSource<'gctx> {
             .map(|p| p.summary())
         {
             let matched = match kind {
-                QueryKind::Exact => dep.matches(s),
+                QueryKind::Exact | QueryKind::ExactWithYanked => dep.matches(s),
                 QueryKind::Alternatives => true,
                 QueryKind::Normalized => dep.matches(s),
             };
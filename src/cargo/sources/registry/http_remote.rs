@@ -8,9 +8,10 @@ use crate::sources::registry::{LoadResponse, RegistryConfig, RegistryData};
 use crate::util::cache_lock::CacheLockMode;
 use crate::util::errors::{CargoResult, HttpNotSuccessful};
 use crate::util::interning::InternedString;
-use crate::util::network::http::http_handle;
+use crate::util::network::http::{configure_registry_client_cert, explain_curl_error, http_handle};
 use crate::util::network::retry::{Retry, RetryResult};
 use crate::util::network::sleep::SleepTracker;
+use crate::util::network::stats::RequestKind;
 use crate::util::{auth, Filesystem, GlobalContext, IntoUrl, Progress, ProgressStyle};
 use anyhow::Context as _;
 use cargo_credential::Operation;
@@ -314,8 +315,17 @@ impl<'gctx> HttpRegistry<'gctx> {
             let mut handle = self.multi.remove(handle)?;
             let data = download.data.take();
             let url = self.full_url(&download.path);
+            self.gctx.network_stats().record_request(
+                &url,
+                RequestKind::Index,
+                data.len() as u64,
+                handle.total_time().unwrap_or_default(),
+                matches!(handle.response_code(), Ok(304)),
+            );
             let result = match download.retry.r#try(|| {
-                result.with_context(|| format!("failed to download from `{}`", url))?;
+                result
+                    .map_err(explain_curl_error)
+                    .with_context(|| format!("failed to download from `{}`", url))?;
                 let code = handle.response_code()?;
                 // Keep this list of expected status codes in sync with the codes handled in `load`
                 let code = match code {
@@ -560,10 +570,17 @@ impl<'gctx> RegistryData for HttpRegistry<'gctx> {
                     return Poll::Ready(Ok(LoadResponse::NotFound));
                 }
                 StatusCode::Unauthorized
-                    if !self.auth_required && path == Path::new(RegistryConfig::NAME) =>
+                    if (!self.auth_required || self.auth_error_headers.is_empty())
+                        && path == Path::new(RegistryConfig::NAME) =>
                 {
                     debug!(target: "network", "re-attempting request for config.json with authorization included.");
                     self.fresh.remove(path);
+                    if self.auth_required {
+                        // A cached config said authorization is required, so
+                        // the rejected token was requested without the
+                        // challenge, which may ask for a specific key.
+                        auth::clear_cached_tokens(self.gctx, &self.source_id);
+                    }
                     self.auth_required = true;
 
                     // Look for a `www-authenticate` header with the `Cargo` scheme.
@@ -627,6 +644,7 @@ impl<'gctx> RegistryData for HttpRegistry<'gctx> {
         self.start_fetch()?;
 
         let mut handle = http_handle(self.gctx)?;
+        configure_registry_client_cert(self.gctx, &mut handle, self.source_id)?;
         let full_url = self.full_url(path);
         debug!(target: "network", "fetch {}", full_url);
         handle.get(true)?;
@@ -720,7 +738,7 @@ impl<'gctx> RegistryData for HttpRegistry<'gctx> {
             path: path.to_path_buf(),
             data: RefCell::new(Vec::new()),
             header_map: Default::default(),
-            retry: Retry::new(self.gctx)?,
+            retry: Retry::new(self.gctx)?.with_url(full_url),
         };
 
         // Finally add the request we've lined up to the pool of requests that cURL manages.
@@ -22,7 +22,7 @@
 //! see [the documentation of the on-disk index cache](cache).
 use crate::core::dependency::{Artifact, DepKind};
 use crate::core::Dependency;
-use crate::core::{PackageId, SourceId, Summary};
+use crate::core::{PackageId, SourceId, Summary, YankReason};
 use crate::sources::registry::{LoadResponse, RegistryData};
 use crate::util::interning::InternedString;
 use crate::util::IntoUrl;
@@ -233,6 +233,21 @@ pub struct IndexPackage<'a> {
     /// Added in 2023 (see <https://github.com/rust-lang/crates.io/pull/6267>),
     /// can be `None` if published before then or if not set in the manifest.
     pub rust_version: Option<RustVersion>,
+    /// When this version was published, in RFC 3339 format.
+    ///
+    /// Only informational, for `cargo info --versions`. Can be `None` if the
+    /// registry doesn't record it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pubtime: Option<String>,
+    /// Why this version was yanked, as given to `cargo yank --reason`.
+    ///
+    /// Only informational, shown in warnings about yanked versions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub yank_reason: Option<String>,
+    /// A URL of an advisory about why this version was yanked, as given to
+    /// `cargo yank --advisory`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub yank_advisory: Option<String>,
     /// The schema version for this entry.
     ///
     /// If this is None, it defaults to version `1`. Entries with unknown
@@ -514,6 +529,20 @@ impl<'gctx> RegistryIndex<'gctx> {
         let found = ready!(self.summaries(pkg.name(), &req, load))?.any(|s| s.is_yanked());
         Poll::Ready(Ok(found))
     }
+
+    /// Looks into the summaries to check if a package has been yanked, and
+    /// why.
+    pub fn yank_reason(
+        &mut self,
+        pkg: PackageId,
+        load: &mut dyn RegistryData,
+    ) -> Poll<CargoResult<Option<YankReason>>> {
+        let req = OptVersionReq::lock_to_exact(pkg.version());
+        let found = ready!(self.summaries(pkg.name(), &req, load))?
+            .find(|s| s.is_yanked())
+            .map(|s| s.as_summary().yank_reason().cloned().unwrap_or_default());
+        Poll::Ready(Ok(found))
+    }
 }
 
 impl Summaries {
@@ -722,6 +751,9 @@ impl IndexSummary {
             yanked,
             links,
             rust_version,
+            pubtime,
+            yank_reason,
+            yank_advisory,
             v,
         } = serde_json::from_slice(line)?;
         let v = v.unwrap_or(1);
@@ -738,6 +770,15 @@ impl IndexSummary {
         }
         let mut summary = Summary::new(pkgid, deps, &features, links, rust_version)?;
         summary.set_checksum(cksum);
+        if let Some(pubtime) = pubtime {
+            summary.set_pubtime(pubtime);
+        }
+        if yank_reason.is_some() || yank_advisory.is_some() {
+            summary.set_yank_reason(YankReason {
+                reason: yank_reason,
+                advisory: yank_advisory,
+            });
+        }
 
         let v_max = if bindeps {
             INDEX_V_MAX + 1
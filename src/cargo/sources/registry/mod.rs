@@ -202,7 +202,7 @@ use tracing::debug;
 
 use crate::core::dependency::Dependency;
 use crate::core::global_cache_tracker;
-use crate::core::{Package, PackageId, SourceId};
+use crate::core::{Package, PackageId, SourceId, YankReason};
 use crate::sources::source::MaybePackage;
 use crate::sources::source::QueryKind;
 use crate::sources::source::Source;
@@ -467,6 +467,32 @@ fn short_name(id: SourceId, is_shallow: bool) -> String {
     name
 }
 
+/// Generates the name of the directories of a "remote" registry.
+fn remote_name(id: SourceId, gctx: &GlobalContext) -> String {
+    short_name(
+        id,
+        gctx.cli_unstable()
+            .git
+            .map_or(false, |features| features.shallow_index)
+            && !id.is_sparse(),
+    )
+}
+
+/// Returns the path of the `.crate` file of `pkg` once it has been downloaded
+/// from the registry `id`, or `None` if `id` is not a registry.
+pub fn crate_file_path(gctx: &GlobalContext, id: SourceId, pkg: PackageId) -> Option<PathBuf> {
+    let dir = if id.is_remote_registry() {
+        gctx.registry_cache_path()
+            .join(remote_name(id, gctx))
+            .into_path_unlocked()
+    } else if id.is_registry() {
+        id.url().to_file_path().ok()?
+    } else {
+        return None;
+    };
+    Some(dir.join(pkg.tarball_name()))
+}
+
 impl<'gctx> RegistrySource<'gctx> {
     /// Creates a [`Source`] of a "remote" registry.
     /// It could be either an HTTP-based [`http_remote::HttpRegistry`] or
@@ -479,13 +505,7 @@ impl<'gctx> RegistrySource<'gctx> {
         gctx: &'gctx GlobalContext,
     ) -> CargoResult<RegistrySource<'gctx>> {
         assert!(source_id.is_remote_registry());
-        let name = short_name(
-            source_id,
-            gctx.cli_unstable()
-                .git
-                .map_or(false, |features| features.shallow_index)
-                && !source_id.is_sparse(),
-        );
+        let name = remote_name(source_id, gctx);
         let ops = if source_id.is_sparse() {
             Box::new(http_remote::HttpRegistry::new(source_id, gctx, &name)?) as Box<_>
         } else {
@@ -792,12 +812,12 @@ impl<'gctx> Source for RegistrySource<'gctx> {
                 Poll::Pending
             }
         } else {
-            let mut precise_yanked_in_use = false;
+            let mut precise_yanked_in_use = None;
             ready!(self
                 .index
                 .query_inner(dep.package_name(), &req, &mut *self.ops, &mut |s| {
                     let matched = match kind {
-                        QueryKind::Exact => {
+                        QueryKind::Exact | QueryKind::ExactWithYanked => {
                             if req.is_precise() && self.gctx.cli_unstable().unstable_options {
                                 dep.matches_prerelease(s.as_summary())
                             } else {
@@ -813,16 +833,17 @@ impl<'gctx> Source for RegistrySource<'gctx> {
                     // Next filter out all yanked packages. Some yanked packages may
                     // leak through if they're in a whitelist (aka if they were
                     // previously in `Cargo.lock`
-                    if !s.is_yanked() {
+                    if !s.is_yanked() || kind == QueryKind::ExactWithYanked {
                         callback(s);
                     } else if self.yanked_whitelist.contains(&s.package_id()) {
                         callback(s);
                     } else if req.is_precise() {
-                        precise_yanked_in_use = true;
+                        precise_yanked_in_use =
+                            Some(s.as_summary().yank_reason().cloned().unwrap_or_default());
                         callback(s);
                     }
                 }))?;
-            if precise_yanked_in_use {
+            if let Some(yank_reason) = precise_yanked_in_use {
                 let name = dep.package_name();
                 let version = req
                     .precise_version()
@@ -832,6 +853,7 @@ impl<'gctx> Source for RegistrySource<'gctx> {
                     shell.warn(format_args!(
                         "selected package `{name}@{version}` was yanked by the author"
                     ))?;
+                    yank_reason.note(&mut shell)?;
                     shell.note("if possible, try a compatible non-yanked version")?;
                 }
             }
@@ -937,6 +959,10 @@ impl<'gctx> Source for RegistrySource<'gctx> {
         self.index.is_yanked(pkg, &mut *self.ops)
     }
 
+    fn yank_reason(&mut self, pkg: PackageId) -> Poll<CargoResult<Option<YankReason>>> {
+        self.index.yank_reason(pkg, &mut *self.ops)
+    }
+
     fn block_until_ready(&mut self) -> CargoResult<()> {
         // Before starting to work on the registry, make sure that
         // `<cargo_home>/registry` is marked as excluded from indexing and
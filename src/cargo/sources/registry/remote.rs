@@ -11,6 +11,7 @@ use crate::sources::registry::{LoadResponse, RegistryConfig, RegistryData};
 use crate::util::cache_lock::CacheLockMode;
 use crate::util::errors::CargoResult;
 use crate::util::interning::InternedString;
+use crate::util::network::http;
 use crate::util::{Filesystem, GlobalContext};
 use anyhow::Context as _;
 use cargo_util::paths;
@@ -361,6 +362,7 @@ impl<'gctx> RegistryData for RemoteRegistry<'gctx> {
         // This way if there's a problem the error gets printed before we even
         // hit the index, which may not actually read this configuration.
         self.gctx.http()?;
+        http::reject_git_registry_client_cert(self.gctx, self.source_id)?;
 
         self.prepare()?;
         self.head.set(None);
@@ -1,4 +1,4 @@
-use crate::core::{Dependency, Package, PackageId, SourceId};
+use crate::core::{Dependency, Package, PackageId, SourceId, YankReason};
 use crate::sources::source::MaybePackage;
 use crate::sources::source::QueryKind;
 use crate::sources::source::Source;
@@ -167,6 +167,10 @@ impl<'gctx> Source for ReplacedSource<'gctx> {
         self.inner.is_yanked(pkg)
     }
 
+    fn yank_reason(&mut self, pkg: PackageId) -> Poll<CargoResult<Option<YankReason>>> {
+        self.inner.yank_reason(pkg)
+    }
+
     fn block_until_ready(&mut self) -> CargoResult<()> {
         self.inner.block_until_ready().map_err(|e| {
             if self.is_builtin_replacement() {
@@ -6,7 +6,7 @@ use std::task::Poll;
 
 use crate::core::package::PackageSet;
 use crate::core::SourceId;
-use crate::core::{Dependency, Package, PackageId};
+use crate::core::{Dependency, Package, PackageId, YankReason};
 use crate::sources::IndexSummary;
 use crate::util::{CargoResult, GlobalContext};
 
@@ -161,6 +161,13 @@ pub trait Source {
     /// as yanked. This ignores the yanked whitelist.
     fn is_yanked(&mut self, _pkg: PackageId) -> Poll<CargoResult<bool>>;
 
+    /// Query if a package is yanked, and why. Returns `None` if it isn't
+    /// yanked, and an empty [`YankReason`] if the source doesn't know why.
+    fn yank_reason(&mut self, pkg: PackageId) -> Poll<CargoResult<Option<YankReason>>> {
+        self.is_yanked(pkg)
+            .map_ok(|is_yanked| is_yanked.then(YankReason::default))
+    }
+
     /// Block until all outstanding [`Poll::Pending`] requests are [`Poll::Ready`].
     ///
     /// After calling this function, the source should return `Poll::Ready` for
@@ -178,6 +185,11 @@ pub enum QueryKind {
     ///
     /// Each source gets to define what `exact` means for it.
     Exact,
+    /// Like [`QueryKind::Exact`], but also returns yanked packages, which is
+    /// what `cargo info --versions` lists.
+    ///
+    /// Sources without yanking treat this like [`QueryKind::Exact`].
+    ExactWithYanked,
     /// A query for packages close to the given dependency requirement.
     ///
     /// Each source gets to define what `close` means for it.
@@ -274,6 +286,10 @@ impl<'a, T: Source + ?Sized + 'a> Source for Box<T> {
         (**self).is_yanked(pkg)
     }
 
+    fn yank_reason(&mut self, pkg: PackageId) -> Poll<CargoResult<Option<YankReason>>> {
+        (**self).yank_reason(pkg)
+    }
+
     fn block_until_ready(&mut self) -> CargoResult<()> {
         (**self).block_until_ready()
     }
@@ -346,6 +362,10 @@ impl<'a, T: Source + ?Sized + 'a> Source for &'a mut T {
         (**self).is_yanked(pkg)
     }
 
+    fn yank_reason(&mut self, pkg: PackageId) -> Poll<CargoResult<Option<YankReason>>> {
+        (**self).yank_reason(pkg)
+    }
+
     fn block_until_ready(&mut self) -> CargoResult<()> {
         (**self).block_until_ready()
     }
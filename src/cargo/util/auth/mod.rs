@@ -12,6 +12,7 @@ use cargo_credential::{
 
 use core::fmt;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::error::Error;
 use time::{Duration, OffsetDateTime};
 use url::Url;
@@ -19,10 +20,12 @@ use url::Url;
 use crate::core::SourceId;
 use crate::util::context::Value;
 use crate::util::credential::adaptor::BasicProcessCredential;
+use crate::util::credential::encrypted_file::EncryptedFileCredential;
 use crate::util::credential::paseto::PasetoCredential;
+use crate::util::credential::trusted_publishing::TrustedPublishingCredential;
 
 use super::{
-    context::{CredentialCacheValue, OptValue, PathAndArgs},
+    context::{CredentialCacheKey, CredentialCacheValue, OptValue, PathAndArgs},
     credential::process::CredentialProcessCredential,
     credential::token::TokenCredential,
 };
@@ -38,6 +41,9 @@ pub struct RegistryConfig {
     pub credential_provider: Option<PathAndArgs>,
     pub secret_key: OptValue<Secret<String>>,
     pub secret_key_subject: Option<String>,
+    /// Keys that were replaced by `secret_key`, still used with registries
+    /// that only accept them.
+    pub previous_secret_keys: OptValue<Vec<Secret<String>>>,
     #[serde(rename = "protocol")]
     _protocol: Option<String>,
 }
@@ -54,6 +60,7 @@ pub struct RegistryConfigExtended {
     pub credential_provider: Option<PathAndArgs>,
     pub secret_key: OptValue<Secret<String>>,
     pub secret_key_subject: Option<String>,
+    pub previous_secret_keys: OptValue<Vec<Secret<String>>>,
     #[serde(rename = "default")]
     _default: Option<String>,
     #[serde(rename = "global-credential-providers")]
@@ -68,6 +75,7 @@ impl RegistryConfigExtended {
             credential_provider: self.credential_provider,
             secret_key: self.secret_key,
             secret_key_subject: self.secret_key_subject,
+            previous_secret_keys: self.previous_secret_keys,
             _protocol: None,
         }
     }
@@ -262,59 +270,15 @@ fn registry_credential_config_raw_uncached(
             .map(|c| c.to_registry_config()));
     }
 
-    // Find the SourceId's name by its index URL. If environment variables
-    // are available they will be preferred over configuration values.
-    //
-    // The fundamental problem is that we only know the index url of the registry
-    // for certain. For example, an unnamed registry source can come from the `--index`
-    // command line argument, or from a Cargo.lock file. For this reason, we always
-    // attempt to discover the name by looking it up by the index URL.
-    //
-    // This also allows the authorization token for a registry to be set
-    // without knowing the registry name by using the _INDEX and _TOKEN
-    // environment variables.
-
-    let name = {
-        // Discover names from environment variables.
-        let index = sid.canonical_url();
-        let mut names: Vec<_> = gctx
-            .env()
-            .filter_map(|(k, v)| {
-                Some((
-                    k.strip_prefix("CARGO_REGISTRIES_")?
-                        .strip_suffix("_INDEX")?,
-                    v,
-                ))
-            })
-            .filter_map(|(k, v)| Some((k, CanonicalUrl::new(&v.into_url().ok()?).ok()?)))
-            .filter(|(_, v)| v == index)
-            .map(|(k, _)| k.to_lowercase())
-            .collect();
-
-        // Discover names from the configuration only if none were found in the environment.
-        if names.len() == 0 {
-            if let Some(registries) = gctx.values()?.get("registries") {
-                let (registries, _) = registries.table("registries")?;
-                for (name, value) in registries {
-                    if let Some(v) = value.table(&format!("registries.{name}"))?.0.get("index") {
-                        let (v, _) = v.string(&format!("registries.{name}.index"))?;
-                        if index == &CanonicalUrl::new(&v.into_url()?)? {
-                            names.push(name.clone());
-                        }
-                    }
-                }
-            }
-        }
-        names.sort();
-        match names.len() {
-            0 => None,
-            1 => Some(std::mem::take(&mut names[0])),
-            _ => anyhow::bail!(
-                "multiple registries are configured with the same index url '{}': {}",
-                &sid.as_url(),
-                names.join(", ")
-            ),
-        }
+    let mut names = registry_names(gctx, sid)?;
+    let name = match names.len() {
+        0 => None,
+        1 => Some(std::mem::take(&mut names[0])),
+        _ => anyhow::bail!(
+            "multiple registries are configured with the same index url '{}': {}",
+            &sid.as_url(),
+            names.join(", ")
+        ),
     };
 
     // It's possible to have a registry configured in a Cargo config file,
@@ -339,6 +303,55 @@ fn registry_credential_config_raw_uncached(
     }
 }
 
+/// Finds the names of the `[registries]` entries configured with the index
+/// URL of `sid`, sorted.
+///
+/// If environment variables are available they will be preferred over
+/// configuration values.
+///
+/// The fundamental problem is that we only know the index url of the registry
+/// for certain. For example, an unnamed registry source can come from the `--index`
+/// command line argument, or from a Cargo.lock file. For this reason, we always
+/// attempt to discover the name by looking it up by the index URL.
+///
+/// This also allows the authorization token for a registry to be set
+/// without knowing the registry name by using the _INDEX and _TOKEN
+/// environment variables.
+pub(crate) fn registry_names(gctx: &GlobalContext, sid: &SourceId) -> CargoResult<Vec<String>> {
+    // Discover names from environment variables.
+    let index = sid.canonical_url();
+    let mut names: Vec<_> = gctx
+        .env()
+        .filter_map(|(k, v)| {
+            Some((
+                k.strip_prefix("CARGO_REGISTRIES_")?
+                    .strip_suffix("_INDEX")?,
+                v,
+            ))
+        })
+        .filter_map(|(k, v)| Some((k, CanonicalUrl::new(&v.into_url().ok()?).ok()?)))
+        .filter(|(_, v)| v == index)
+        .map(|(k, _)| k.to_lowercase())
+        .collect();
+
+    // Discover names from the configuration only if none were found in the environment.
+    if names.len() == 0 {
+        if let Some(registries) = gctx.values()?.get("registries") {
+            let (registries, _) = registries.table("registries")?;
+            for (name, value) in registries {
+                if let Some(v) = value.table(&format!("registries.{name}"))?.0.get("index") {
+                    let (v, _) = v.string(&format!("registries.{name}.index"))?;
+                    if index == &CanonicalUrl::new(&v.into_url()?)? {
+                        names.push(name.clone());
+                    }
+                }
+            }
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
 /// Use the `[credential-alias]` table to see if the provider name has been aliased.
 fn resolve_credential_alias(gctx: &GlobalContext, mut provider: PathAndArgs) -> Vec<String> {
     if provider.args.is_empty() {
@@ -473,30 +486,80 @@ my-registry = {{ index = "{}" }}
     }
 }
 
+/// Forget the tokens cached for a registry, so the next call asks the
+/// credential providers again.
+pub fn clear_cached_tokens(gctx: &GlobalContext, sid: &SourceId) {
+    let url = sid.canonical_url();
+    gctx.credential_cache()
+        .retain(|(cached_url, _), _| cached_url != url);
+}
+
 /// Store a token in the cache for future calls.
 pub fn cache_token_from_commandline(gctx: &GlobalContext, sid: &SourceId, token: Secret<&str>) {
     let url = sid.canonical_url();
     gctx.credential_cache().insert(
-        url.clone(),
+        (url.clone(), None),
         CredentialCacheValue {
             token_value: token.to_owned(),
             expiration: None,
-            operation_independent: true,
+            refresh_after: None,
         },
     );
 }
 
 /// List of credential providers built-in to Cargo.
-/// Keep in sync with the `match` in `credential_action`.
+/// Keep in sync with the `match` in `credential_provider_for`.
 static BUILT_IN_PROVIDERS: &[&'static str] = &[
     "cargo:token",
     "cargo:paseto",
     "cargo:token-from-stdout",
+    "cargo:encrypted-file",
+    "cargo:trusted-publishing",
     "cargo:wincred",
     "cargo:macos-keychain",
     "cargo:libsecret",
 ];
 
+/// Creates the credential provider that `process`, the first element of a
+/// `credential-provider` config value, refers to.
+fn credential_provider_for<'a>(
+    gctx: &'a GlobalContext,
+    process: &str,
+) -> CargoResult<Box<dyn Credential + 'a>> {
+    // If the available built-in providers are changed, update the `BUILT_IN_PROVIDERS` list.
+    let provider: Box<dyn Credential> = match process {
+        "cargo:token" => Box::new(TokenCredential::new(gctx)),
+        "cargo:paseto" if gctx.cli_unstable().asymmetric_token => {
+            Box::new(PasetoCredential::new(gctx))
+        }
+        "cargo:paseto" => bail!("cargo:paseto requires -Zasymmetric-token"),
+        "cargo:token-from-stdout" => Box::new(BasicProcessCredential {}),
+        "cargo:encrypted-file" if gctx.cli_unstable().encrypted_credentials => {
+            Box::new(EncryptedFileCredential::new(gctx))
+        }
+        "cargo:encrypted-file" => {
+            bail!("cargo:encrypted-file requires -Zencrypted-credentials")
+        }
+        "cargo:trusted-publishing" if gctx.cli_unstable().trusted_publishing => {
+            Box::new(TrustedPublishingCredential::new(gctx))
+        }
+        "cargo:trusted-publishing" => {
+            bail!("cargo:trusted-publishing requires -Ztrusted-publishing")
+        }
+        #[cfg(windows)]
+        "cargo:wincred" => Box::new(cargo_credential_wincred::WindowsCredential {}),
+        #[cfg(target_os = "macos")]
+        "cargo:macos-keychain" => Box::new(cargo_credential_macos_keychain::MacKeychain {}),
+        #[cfg(target_os = "linux")]
+        "cargo:libsecret" => Box::new(cargo_credential_libsecret::LibSecretCredential {}),
+        name if BUILT_IN_PROVIDERS.contains(&name) => {
+            Box::new(cargo_credential::UnsupportedCredential {})
+        }
+        process => Box::new(CredentialProcessCredential::new(process)),
+    };
+    Ok(provider)
+}
+
 fn credential_action(
     gctx: &GlobalContext,
     sid: &SourceId,
@@ -521,25 +584,7 @@ fn credential_action(
             .collect();
         let process = args[0];
         tracing::debug!("attempting credential provider: {args:?}");
-        // If the available built-in providers are changed, update the `BUILT_IN_PROVIDERS` list.
-        let provider: Box<dyn Credential> = match process {
-            "cargo:token" => Box::new(TokenCredential::new(gctx)),
-            "cargo:paseto" if gctx.cli_unstable().asymmetric_token => {
-                Box::new(PasetoCredential::new(gctx))
-            }
-            "cargo:paseto" => bail!("cargo:paseto requires -Zasymmetric-token"),
-            "cargo:token-from-stdout" => Box::new(BasicProcessCredential {}),
-            #[cfg(windows)]
-            "cargo:wincred" => Box::new(cargo_credential_wincred::WindowsCredential {}),
-            #[cfg(target_os = "macos")]
-            "cargo:macos-keychain" => Box::new(cargo_credential_macos_keychain::MacKeychain {}),
-            #[cfg(target_os = "linux")]
-            "cargo:libsecret" => Box::new(cargo_credential_libsecret::LibSecretCredential {}),
-            name if BUILT_IN_PROVIDERS.contains(&name) => {
-                Box::new(cargo_credential::UnsupportedCredential {})
-            }
-            process => Box::new(CredentialProcessCredential::new(process)),
-        };
+        let provider = credential_provider_for(gctx, process)?;
         gctx.shell().verbose(|c| {
             c.status(
                 "Credential",
@@ -571,6 +616,75 @@ fn credential_action(
     }
 }
 
+/// What a credential provider answered when asked for a token to read a
+/// registry, as reported by `cargo login --status`.
+#[derive(Debug)]
+pub enum CredentialStatus {
+    /// The provider has a token. The token itself is not kept.
+    Found {
+        cache: CacheControl,
+        operation_independent: bool,
+    },
+    /// The provider has no token for the registry.
+    NotFound,
+    /// The provider doesn't support the registry.
+    UrlNotSupported,
+    /// The provider wasn't asked, since an earlier provider had a token or failed.
+    NotUsed,
+    /// The provider failed.
+    Error(anyhow::Error),
+}
+
+/// Walks the credential providers of a registry the same way Cargo does when
+/// it needs a token, and reports what each of them answered.
+pub fn credential_status(
+    gctx: &GlobalContext,
+    sid: &SourceId,
+) -> CargoResult<Vec<(Vec<String>, CredentialStatus)>> {
+    let registry = RegistryInfo {
+        index_url: sid.url().as_str(),
+        name: sid.alt_registry_key(),
+        headers: vec![],
+    };
+    let action = Action::Get(Operation::Read);
+    let mut done = false;
+    let mut statuses = Vec::new();
+    for provider in credential_provider(gctx, sid, false, true)? {
+        if done {
+            statuses.push((provider, CredentialStatus::NotUsed));
+            continue;
+        }
+        let args: Vec<&str> = provider.iter().map(String::as_str).collect();
+        let status = match credential_provider_for(gctx, args[0])
+            .and_then(|p| Ok(p.perform(&registry, &action, &args[1..])?))
+        {
+            Ok(CredentialResponse::Get {
+                token: _,
+                cache,
+                operation_independent,
+            }) => CredentialStatus::Found {
+                cache,
+                operation_independent,
+            },
+            Ok(response) => CredentialStatus::Error(anyhow::format_err!(
+                "credential provider produced unexpected response for `get` request: {response:?}"
+            )),
+            Err(e) => match e.downcast_ref::<cargo_credential::Error>() {
+                Some(cargo_credential::Error::NotFound) => CredentialStatus::NotFound,
+                Some(cargo_credential::Error::UrlNotSupported) => CredentialStatus::UrlNotSupported,
+                _ => CredentialStatus::Error(e),
+            },
+        };
+        // Cargo stops at the first provider that has a token or fails.
+        done = matches!(
+            status,
+            CredentialStatus::Found { .. } | CredentialStatus::Error(_)
+        );
+        statuses.push((provider, status));
+    }
+    Ok(statuses)
+}
+
 /// Returns the token to use for the given registry.
 /// If a `login_url` is provided and a token is not available, the
 /// login_url will be included in the returned error.
@@ -603,21 +717,34 @@ fn auth_token_optional(
     require_cred_provider_config: bool,
 ) -> CargoResult<Option<Secret<String>>> {
     tracing::trace!("token requested for {}", sid.display_registry_name());
-    let mut cache = gctx.credential_cache();
     let url = sid.canonical_url();
-    if let Some(cached_token) = cache.get(url) {
-        if cached_token
-            .expiration
-            .map(|exp| OffsetDateTime::now_utc() + Duration::minutes(1) < exp)
-            .unwrap_or(true)
-        {
-            if cached_token.operation_independent || matches!(operation, Operation::Read) {
-                tracing::trace!("using token from in-memory cache");
-                return Ok(Some(cached_token.token_value.clone()));
+    // Tokens scoped to an operation are only reused for that same operation.
+    let operation_key = serde_json::to_string(&operation)?;
+    let now = OffsetDateTime::now_utc();
+    // A cached token that is still valid, but that the provider asked to refresh.
+    let mut stale = None;
+    {
+        let mut cache = gctx.credential_cache();
+        for key in cached_token_keys(&cache, url, &operation, &operation_key) {
+            let cached_token = &cache[&key];
+            if cached_token
+                .expiration
+                .map(|exp| now + Duration::minutes(1) < exp)
+                .unwrap_or(true)
+            {
+                if cached_token
+                    .refresh_after
+                    .map(|refresh_after| now < refresh_after)
+                    .unwrap_or(true)
+                {
+                    tracing::trace!("using token from in-memory cache");
+                    return Ok(Some(cached_token.token_value.clone()));
+                }
+                stale = Some((key, cached_token.token_value.clone()));
+            } else {
+                // Remove expired token from the cache
+                cache.remove(&key);
             }
-        } else {
-            // Remove expired token from the cache
-            cache.remove(url);
         }
     }
 
@@ -629,6 +756,28 @@ fn auth_token_optional(
         &[],
         require_cred_provider_config,
     );
+    if let Some((key, token)) = stale {
+        match &credential_response {
+            Ok(_) => {
+                gctx.credential_cache().remove(&key);
+            }
+            Err(e) => {
+                if let Some(cached_token) = gctx.credential_cache().get_mut(&key) {
+                    cached_token.refresh_after = None;
+                }
+                crate::display_warning_with_error(
+                    &format!(
+                        "failed to refresh the token for `{}`, \
+                         the cached token will be used until it expires",
+                        sid.display_registry_name()
+                    ),
+                    e,
+                    &mut gctx.shell(),
+                );
+                return Ok(Some(token));
+            }
+        }
+    }
     if let Some(e) = credential_response.as_ref().err() {
         if let Some(e) = e.downcast_ref::<cargo_credential::Error>() {
             if matches!(e, cargo_credential::Error::NotFound) {
@@ -648,23 +797,69 @@ fn auth_token_optional(
     };
     let token = Secret::from(token);
     tracing::trace!("found token");
-    let expiration = match cache_control {
-        CacheControl::Expires { expiration } => Some(expiration),
-        CacheControl::Session => None,
+    let (expiration, refresh_after) = match cache_control {
+        CacheControl::Expires { expiration } => (Some(expiration), None),
+        CacheControl::Refresh {
+            expiration,
+            refresh_after,
+        } => (Some(expiration), Some(refresh_after)),
+        CacheControl::Session => (None, None),
         CacheControl::Never | _ => return Ok(Some(token)),
     };
 
-    cache.insert(
-        url.clone(),
+    gctx.credential_cache().insert(
+        (
+            url.clone(),
+            (!operation_independent).then_some(operation_key),
+        ),
         CredentialCacheValue {
             token_value: token.clone(),
             expiration,
-            operation_independent,
+            refresh_after,
         },
     );
     Ok(Some(token))
 }
 
+/// The keys of the cached tokens that can be used for `operation`, with the
+/// operation-independent token first.
+///
+/// Reading from a registry can use any of its tokens, including ones scoped
+/// to another operation.
+fn cached_token_keys(
+    cache: &HashMap<CredentialCacheKey, CredentialCacheValue>,
+    url: &CanonicalUrl,
+    operation: &Operation<'_>,
+    operation_key: &str,
+) -> Vec<CredentialCacheKey> {
+    let mut keys: Vec<_> = cache
+        .keys()
+        .filter(|(cached_url, cached_operation)| {
+            cached_url == url
+                && match cached_operation {
+                    None => true,
+                    Some(cached_operation) => {
+                        cached_operation == operation_key || matches!(operation, Operation::Read)
+                    }
+                }
+        })
+        .cloned()
+        .collect();
+    keys.sort_by_key(|(_, cached_operation)| cached_operation.is_some());
+    keys
+}
+
+/// The credential provider `cargo login` hands the token to for the given
+/// registry, that is the first one Cargo tries.
+pub fn login_provider(gctx: &GlobalContext, sid: &SourceId) -> CargoResult<String> {
+    let providers = credential_provider(gctx, sid, false, false)?;
+    let provider = providers
+        .into_iter()
+        .next()
+        .and_then(|p| p.into_iter().next());
+    provider.ok_or_else(|| anyhow::format_err!("no credential providers are configured"))
+}
+
 /// Log out from the given registry.
 pub fn logout(gctx: &GlobalContext, sid: &SourceId) -> CargoResult<()> {
     let credential_response = credential_action(gctx, sid, Action::Logout, vec![], &[], false);
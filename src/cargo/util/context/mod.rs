@@ -53,7 +53,7 @@ use crate::util::cache_lock::{CacheLock, CacheLockMode, CacheLocker};
 use std::borrow::Cow;
 use std::cell::{RefCell, RefMut};
 use std::collections::hash_map::Entry::{Occupied, Vacant};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::env;
 use std::ffi::{OsStr, OsString};
 use std::fmt;
@@ -77,13 +77,14 @@ use crate::sources::CRATES_IO_REGISTRY;
 use crate::util::errors::CargoResult;
 use crate::util::network::http::configure_http_handle;
 use crate::util::network::http::http_handle;
+use crate::util::network::stats::NetworkStats;
 use crate::util::try_canonicalize;
 use crate::util::{internal, CanonicalUrl};
 use crate::util::{Filesystem, IntoUrl, IntoUrlWithBase, Rustc};
 use anyhow::{anyhow, bail, format_err, Context as _};
 use cargo_credential::Secret;
 use cargo_util::paths;
-use cargo_util_schemas::manifest::RegistryName;
+use cargo_util_schemas::manifest::{RegistryName, TomlLint};
 use curl::easy::Easy;
 use lazycell::LazyCell;
 use serde::de::IntoDeserializer as _;
@@ -151,12 +152,18 @@ enum WhyLoad {
     FileDiscovery,
 }
 
+/// Key of a cached authentication token: the registry it is for and, if the
+/// token is scoped to one operation, that operation serialized as JSON.
+pub type CredentialCacheKey = (CanonicalUrl, Option<String>);
+
 /// A previously generated authentication token and the data needed to determine if it can be reused.
 #[derive(Debug)]
 pub struct CredentialCacheValue {
     pub token_value: Secret<String>,
     pub expiration: Option<OffsetDateTime>,
-    pub operation_independent: bool,
+    /// When to ask the credential provider for a new token, even though this
+    /// one has not expired yet.
+    pub refresh_after: Option<OffsetDateTime>,
 }
 
 /// Configuration information for cargo. This is not specific to a build, it is information
@@ -212,9 +219,11 @@ pub struct GlobalContext {
     env: Env,
     /// Tracks which sources have been updated to avoid multiple updates.
     updated_sources: LazyCell<RefCell<HashSet<SourceId>>>,
+    /// Statistics of the network requests made so far.
+    network_stats: LazyCell<RefCell<NetworkStats>>,
     /// Cache of credentials from configuration or credential providers.
     /// Maps from url to credential value.
-    credential_cache: LazyCell<RefCell<HashMap<CanonicalUrl, CredentialCacheValue>>>,
+    credential_cache: LazyCell<RefCell<HashMap<CredentialCacheKey, CredentialCacheValue>>>,
     /// Cache of registry config from the `[registries]` table.
     registry_config: LazyCell<RefCell<HashMap<SourceId, Option<RegistryConfig>>>>,
     /// Locks on the package and index caches.
@@ -228,6 +237,7 @@ pub struct GlobalContext {
     doc_extern_map: LazyCell<RustdocExternMap>,
     progress_config: ProgressConfig,
     env_config: LazyCell<EnvConfig>,
+    lints_config: LazyCell<LintsConfig>,
     /// This should be false if:
     /// - this is an artifact of the rustc distribution process for "stable" or for "beta"
     /// - this is an `#[test]` that does not opt in with `enable_nightly_features`
@@ -311,6 +321,7 @@ impl GlobalContext {
             target_dir: None,
             env,
             updated_sources: LazyCell::new(),
+            network_stats: LazyCell::new(),
             credential_cache: LazyCell::new(),
             registry_config: LazyCell::new(),
             package_cache_lock: CacheLocker::new(),
@@ -322,6 +333,7 @@ impl GlobalContext {
             doc_extern_map: LazyCell::new(),
             progress_config: ProgressConfig::default(),
             env_config: LazyCell::new(),
+            lints_config: LazyCell::new(),
             nightly_features_allowed: matches!(&*features::channel(), "nightly" | "dev"),
             ws_roots: RefCell::new(HashMap::new()),
             global_cache_tracker: LazyCell::new(),
@@ -509,8 +521,17 @@ impl GlobalContext {
             .borrow_mut()
     }
 
+    /// Statistics of the network requests made by this process.
+    pub fn network_stats(&self) -> RefMut<'_, NetworkStats> {
+        self.network_stats
+            .borrow_with(|| RefCell::new(NetworkStats::default()))
+            .borrow_mut()
+    }
+
     /// Cached credentials from credential providers or configuration.
-    pub fn credential_cache(&self) -> RefMut<'_, HashMap<CanonicalUrl, CredentialCacheValue>> {
+    pub fn credential_cache(
+        &self,
+    ) -> RefMut<'_, HashMap<CredentialCacheKey, CredentialCacheValue>> {
         self.credential_cache
             .borrow_with(|| RefCell::new(HashMap::new()))
             .borrow_mut()
@@ -1474,24 +1495,23 @@ impl GlobalContext {
                     );
                 }
 
-                if toml_v
-                    .get("registry")
-                    .and_then(|v| v.as_table())
-                    .and_then(|t| t.get("secret-key"))
-                    .is_some()
-                {
-                    bail!(
-                        "registry.secret-key cannot be set through --config for security reasons"
-                    );
-                } else if let Some((k, _)) = toml_v
-                    .get("registries")
-                    .and_then(|v| v.as_table())
-                    .and_then(|t| t.iter().find(|(_, v)| v.get("secret-key").is_some()))
-                {
-                    bail!(
-                        "registries.{}.secret-key cannot be set through --config for security reasons",
-                        k
-                    );
+                for key in ["secret-key", "previous-secret-keys"] {
+                    if toml_v
+                        .get("registry")
+                        .and_then(|v| v.as_table())
+                        .and_then(|t| t.get(key))
+                        .is_some()
+                    {
+                        bail!("registry.{key} cannot be set through --config for security reasons");
+                    } else if let Some((k, _)) = toml_v
+                        .get("registries")
+                        .and_then(|v| v.as_table())
+                        .and_then(|t| t.iter().find(|(_, v)| v.get(key).is_some()))
+                    {
+                        bail!(
+                            "registries.{k}.{key} cannot be set through --config for security reasons",
+                        );
+                    }
                 }
 
                 CV::from_toml(Definition::Cli(None), toml_v)
@@ -1829,6 +1849,18 @@ impl GlobalContext {
             .try_borrow_with(|| self.get::<CargoBuildConfig>("build"))
     }
 
+    /// The `[lints]` table, which is empty without `-Zconfig-lints`.
+    pub fn lints_config(&self) -> CargoResult<&LintsConfig> {
+        self.lints_config.try_borrow_with(|| {
+            if !self.cli_unstable().config_lints {
+                return Ok(LintsConfig::new());
+            }
+            Ok(self
+                .get::<Option<LintsConfig>>("lints")?
+                .unwrap_or_default())
+        })
+    }
+
     pub fn progress_config(&self) -> &ProgressConfig {
         &self.progress_config
     }
@@ -2398,7 +2430,7 @@ pub fn save_credentials(
                     ("registry".into(), table)
                 }
             }
-            RegistryCredentialConfig::AsymmetricKey((secret_key, key_subject)) => {
+            RegistryCredentialConfig::AsymmetricKey((secret_key, key_subject, previous_keys)) => {
                 // login with key
 
                 let key = "secret-key".to_string();
@@ -2409,6 +2441,28 @@ pub fn save_credentials(
                     let value = ConfigValue::String(key_subject, path_def.clone());
                     map.insert(key, value);
                 }
+                // Previous keys are replaced rather than merged with the ones
+                // already saved.
+                let saved_table = match registry {
+                    Some(registry) => toml
+                        .get_mut("registries")
+                        .and_then(|registries| registries.get_mut(registry)),
+                    None => toml.get_mut("registry"),
+                };
+                if let Some(table) = saved_table.and_then(|t| t.as_table_mut()) {
+                    table.remove("previous-secret-keys");
+                }
+                if !previous_keys.is_empty() {
+                    let key = "previous-secret-keys".to_string();
+                    let value = ConfigValue::List(
+                        previous_keys
+                            .into_iter()
+                            .map(|key| (key.expose(), path_def.clone()))
+                            .collect(),
+                        path_def.clone(),
+                    );
+                    map.insert(key, value);
+                }
                 let table = CV::Table(map, path_def.clone());
 
                 if let Some(registry) = registry {
@@ -2439,6 +2493,7 @@ pub fn save_credentials(
                     rtable.remove("token");
                     rtable.remove("secret-key");
                     rtable.remove("secret-key-subject");
+                    rtable.remove("previous-secret-keys");
                 }
             }
         } else if let Some(registry) = toml.get_mut("registry") {
@@ -2448,6 +2503,7 @@ pub fn save_credentials(
             reg_table.remove("token");
             reg_table.remove("secret-key");
             reg_table.remove("secret-key-subject");
+            reg_table.remove("previous-secret-keys");
         }
     }
 
@@ -2485,6 +2541,8 @@ pub struct CargoHttpConfig {
     pub low_speed_limit: Option<u32>,
     pub timeout: Option<u64>,
     pub cainfo: Option<ConfigRelativePath>,
+    pub client_cert: Option<ConfigRelativePath>,
+    pub client_key: Option<ConfigRelativePath>,
     pub check_revoke: Option<bool>,
     pub user_agent: Option<String>,
     pub debug: Option<bool>,
@@ -2562,6 +2620,7 @@ pub struct CargoNetConfig {
     pub retry: Option<u32>,
     pub offline: Option<bool>,
     pub git_fetch_with_cli: Option<bool>,
+    pub git_allowed_signers: Option<ConfigRelativePath>,
     pub ssh: Option<CargoSshConfig>,
 }
 
@@ -2856,6 +2915,10 @@ impl EnvConfigValue {
 
 pub type EnvConfig = HashMap<String, EnvConfigValue>;
 
+/// The `[lints]` table, by tool and then lint name, like the `[lints]` table of
+/// a manifest, which it takes precedence over.
+pub type LintsConfig = BTreeMap<String, BTreeMap<String, Value<TomlLint>>>;
+
 fn parse_document(toml: &str, _file: &Path, _gctx: &GlobalContext) -> CargoResult<toml::Table> {
     // At the moment, no compatibility checks are needed.
     toml.parse().map_err(Into::into)
@@ -0,0 +1,338 @@
+//! Credential provider that stores tokens in a file in Cargo's home directory,
+//! encrypted with a key derived from a passphrase.
+//!
+//! The file starts with a header holding the parameters of the key
+//! derivation (Argon2i), followed by the tokens as a JSON object mapping index
+//! URLs to tokens, sealed with XChaCha20-Poly1305.
+
+use std::collections::BTreeMap;
+use std::io::{Read as _, Seek as _, SeekFrom, Write as _};
+
+use anyhow::{bail, Context as _};
+use cargo_credential::{
+    Action, CacheControl, Credential, CredentialResponse, Error, RegistryInfo, Secret,
+};
+use clap::{ArgMatches, Command};
+use lazycell::LazyCell;
+use orion::{aead, kdf};
+use url::Url;
+
+use crate::core::SourceId;
+use crate::util::command_prelude::opt;
+use crate::{CargoResult, GlobalContext};
+
+/// Name of the encrypted file in Cargo's home directory.
+const FILENAME: &str = "credentials.enc";
+
+/// Identifies the file format, including its version.
+const MAGIC: &[u8; 8] = b"cargoen1";
+
+/// Environment variable the passphrase is read from by default.
+const DEFAULT_PASSPHRASE_ENV: &str = "CARGO_CREDENTIAL_PASSPHRASE";
+
+/// Argon2i parameters used when writing the file.
+const KDF_ITERATIONS: u32 = 3;
+const KDF_MEMORY_KIB: u32 = 1 << 15;
+
+/// Argon2i parameters accepted when reading the file, so a tampered header
+/// can't make Cargo spin or allocate without bounds.
+const KDF_ITERATIONS_RANGE: std::ops::RangeInclusive<u32> = 3..=16;
+const KDF_MEMORY_KIB_RANGE: std::ops::RangeInclusive<u32> = 8..=(1 << 18);
+
+const SALT_LEN: usize = 16;
+const HEADER_LEN: usize = MAGIC.len() + 4 + 4 + SALT_LEN;
+
+pub(crate) struct EncryptedFileCredential<'a> {
+    gctx: &'a GlobalContext,
+    /// The passphrase, once it has been read.
+    passphrase: LazyCell<Secret<String>>,
+}
+
+impl<'a> EncryptedFileCredential<'a> {
+    pub fn new(gctx: &'a GlobalContext) -> Self {
+        Self {
+            gctx,
+            passphrase: LazyCell::new(),
+        }
+    }
+
+    /// Gets the passphrase from the file descriptor or the environment
+    /// variable given in the provider arguments, reading it only once.
+    fn passphrase(&self, matches: &ArgMatches) -> CargoResult<Secret<&str>> {
+        self.passphrase
+            .try_borrow_with(|| self.read_passphrase(matches))
+            .map(Secret::as_deref)
+    }
+
+    fn read_passphrase(&self, matches: &ArgMatches) -> CargoResult<Secret<String>> {
+        let passphrase = if let Some(fd) = matches.get_one::<String>("passphrase-fd") {
+            let fd = fd
+                .parse()
+                .with_context(|| format!("invalid passphrase file descriptor `{fd}`"))?;
+            read_fd(fd)?.expose()
+        } else {
+            let var = matches
+                .get_one::<String>("passphrase-env")
+                .map(String::as_str)
+                .unwrap_or(DEFAULT_PASSPHRASE_ENV);
+            match self.gctx.get_env(var) {
+                Ok(passphrase) => passphrase,
+                Err(_) => bail!(
+                    "the passphrase for `{}` is not set\n\
+                     Set the `{var}` environment variable, or pass `--passphrase-fd` \
+                     to the `cargo:encrypted-file` provider.",
+                    self.path().display()
+                ),
+            }
+        };
+        let passphrase = passphrase.trim_end_matches(['\r', '\n']);
+        if passphrase.is_empty() {
+            bail!("the passphrase for `{}` is empty", self.path().display());
+        }
+        Ok(Secret::from(passphrase.to_string()))
+    }
+
+    fn path(&self) -> std::path::PathBuf {
+        self.gctx.home().as_path_unlocked().join(FILENAME)
+    }
+
+    /// Loads the tokens from the file, or `None` if there is no file.
+    fn load(&self, matches: &ArgMatches) -> CargoResult<Option<BTreeMap<String, String>>> {
+        if !self.path().exists() {
+            return Ok(None);
+        }
+        let mut file =
+            self.gctx
+                .home()
+                .open_ro_shared(FILENAME, self.gctx, "encrypted credentials")?;
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents)?;
+        let passphrase = self.passphrase(matches)?;
+        decrypt(&contents, passphrase)
+            .with_context(|| format!("failed to decrypt `{}`", file.path().display()))
+            .map(Some)
+    }
+
+    /// Changes the token of a registry with `f`, rewriting the file.
+    ///
+    /// Returns whatever `f` returns, without writing if it is `None`.
+    fn update<T>(
+        &self,
+        matches: &ArgMatches,
+        f: impl FnOnce(&mut BTreeMap<String, String>) -> Option<T>,
+    ) -> CargoResult<Option<T>> {
+        let passphrase = self.passphrase(matches)?;
+        self.gctx.home().create_dir()?;
+        let mut file = self.gctx.home().open_rw_exclusive_create(
+            FILENAME,
+            self.gctx,
+            "encrypted credentials",
+        )?;
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents)?;
+        let mut tokens = if contents.is_empty() {
+            BTreeMap::new()
+        } else {
+            decrypt(&contents, passphrase.clone())
+                .with_context(|| format!("failed to decrypt `{}`", file.path().display()))?
+        };
+        let Some(result) = f(&mut tokens) else {
+            return Ok(None);
+        };
+        let contents = encrypt(&tokens, passphrase)?;
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(&contents)
+            .with_context(|| format!("failed to write to `{}`", file.path().display()))?;
+        file.file().set_len(contents.len() as u64)?;
+        set_permissions(file.file())
+            .with_context(|| format!("failed to set permissions of `{}`", file.path().display()))?;
+        Ok(Some(result))
+    }
+}
+
+impl<'a> Credential for EncryptedFileCredential<'a> {
+    fn perform(
+        &self,
+        registry: &RegistryInfo<'_>,
+        action: &Action<'_>,
+        args: &[&str],
+    ) -> Result<CredentialResponse, Error> {
+        let matches = Command::new("cargo:encrypted-file")
+            .no_binary_name(true)
+            .arg(
+                opt(
+                    "passphrase-env",
+                    "Environment variable to read the passphrase from",
+                )
+                .value_name("VAR"),
+            )
+            .arg(
+                opt(
+                    "passphrase-fd",
+                    "File descriptor to read the passphrase from",
+                )
+                .value_name("FD")
+                .conflicts_with("passphrase-env"),
+            )
+            .try_get_matches_from(args)
+            .map_err(Box::new)?;
+
+        let index_url = Url::parse(registry.index_url).context("parsing index url")?;
+        let sid = if let Some(name) = registry.name {
+            SourceId::for_alt_registry(&index_url, name)
+        } else {
+            SourceId::for_registry(&index_url)
+        }?;
+        let reg_name = sid.display_registry_name();
+
+        match action {
+            Action::Get(_) => {
+                let token = self
+                    .load(&matches)?
+                    .and_then(|mut tokens| tokens.remove(registry.index_url))
+                    .ok_or(Error::NotFound)?;
+                Ok(CredentialResponse::Get {
+                    token: Secret::from(token),
+                    cache: CacheControl::Session,
+                    operation_independent: true,
+                })
+            }
+            Action::Login(options) => {
+                let new_token = cargo_credential::read_token(options, registry)?
+                    .map(|line| line.replace("cargo login", "").trim().to_string());
+                crates_io::check_token(new_token.as_ref().expose()).map_err(Box::new)?;
+                self.update(&matches, |tokens| {
+                    Some(tokens.insert(registry.index_url.to_string(), new_token.expose()))
+                })?;
+                let _ = self.gctx.shell().status(
+                    "Login",
+                    format!(
+                        "token for `{reg_name}` saved in `{}`",
+                        self.path().display()
+                    ),
+                );
+                Ok(CredentialResponse::Login)
+            }
+            Action::Logout => {
+                if !self.path().exists() {
+                    return Err(Error::NotFound);
+                }
+                self.update(&matches, |tokens| tokens.remove(registry.index_url))?
+                    .ok_or(Error::NotFound)?;
+                let _ = self.gctx.shell().status(
+                    "Logout",
+                    format!(
+                        "token for `{reg_name}` has been removed from `{}`",
+                        self.path().display()
+                    ),
+                );
+                Ok(CredentialResponse::Logout)
+            }
+            _ => Err(Error::OperationNotSupported),
+        }
+    }
+}
+
+fn derive_key(
+    passphrase: Secret<&str>,
+    salt: &kdf::Salt,
+    iterations: u32,
+    memory: u32,
+) -> CargoResult<aead::SecretKey> {
+    let password = kdf::Password::from_slice(passphrase.expose().as_bytes())
+        .map_err(|_| anyhow::format_err!("invalid passphrase"))?;
+    let key = kdf::derive_key(&password, salt, iterations, memory, 32)
+        .map_err(|_| anyhow::format_err!("failed to derive a key from the passphrase"))?;
+    aead::SecretKey::from_slice(key.unprotected_as_bytes())
+        .map_err(|_| anyhow::format_err!("failed to derive a key from the passphrase"))
+}
+
+fn encrypt(tokens: &BTreeMap<String, String>, passphrase: Secret<&str>) -> CargoResult<Vec<u8>> {
+    let salt = kdf::Salt::generate(SALT_LEN)
+        .map_err(|_| anyhow::format_err!("failed to generate a salt"))?;
+    let key = derive_key(passphrase, &salt, KDF_ITERATIONS, KDF_MEMORY_KIB)?;
+    let plaintext = serde_json::to_vec(tokens)?;
+    let sealed = aead::seal(&key, &plaintext)
+        .map_err(|_| anyhow::format_err!("failed to encrypt credentials"))?;
+
+    let mut contents = Vec::with_capacity(HEADER_LEN + sealed.len());
+    contents.extend_from_slice(MAGIC);
+    contents.extend_from_slice(&KDF_ITERATIONS.to_le_bytes());
+    contents.extend_from_slice(&KDF_MEMORY_KIB.to_le_bytes());
+    contents.extend_from_slice(salt.as_ref());
+    contents.extend_from_slice(&sealed);
+    Ok(contents)
+}
+
+fn decrypt(contents: &[u8], passphrase: Secret<&str>) -> CargoResult<BTreeMap<String, String>> {
+    if contents.len() < HEADER_LEN || &contents[..MAGIC.len()] != MAGIC {
+        bail!("not a file of encrypted credentials written by this version of Cargo");
+    }
+    let (header, sealed) = contents.split_at(HEADER_LEN);
+    let (iterations, rest) = header[MAGIC.len()..].split_at(4);
+    let (memory, salt) = rest.split_at(4);
+    let iterations = u32::from_le_bytes(iterations.try_into().unwrap());
+    let memory = u32::from_le_bytes(memory.try_into().unwrap());
+    if !KDF_ITERATIONS_RANGE.contains(&iterations) || !KDF_MEMORY_KIB_RANGE.contains(&memory) {
+        bail!(
+            "unsupported key derivation parameters ({iterations} iterations, {memory} KiB of memory), \
+             the file is corrupted"
+        );
+    }
+    let salt = kdf::Salt::from_slice(salt).map_err(|_| anyhow::format_err!("invalid salt"))?;
+
+    let key = derive_key(passphrase, &salt, iterations, memory)?;
+    let plaintext = aead::open(&key, sealed)
+        .map_err(|_| anyhow::format_err!("wrong passphrase, or the file is corrupted"))?;
+    Ok(serde_json::from_slice(&plaintext)?)
+}
+
+/// Reads the passphrase from an inherited file descriptor, e.g. a pipe set up
+/// by the parent process.
+///
+/// The descriptor is read to the end, so what it held is kept for the rest of
+/// the process, for the providers created for later requests.
+#[cfg(unix)]
+fn read_fd(fd: std::os::fd::RawFd) -> CargoResult<Secret<String>> {
+    use std::collections::HashMap;
+    use std::os::fd::FromRawFd as _;
+    use std::sync::{Mutex, OnceLock};
+
+    static PASSPHRASES: OnceLock<Mutex<HashMap<std::os::fd::RawFd, Secret<String>>>> =
+        OnceLock::new();
+    let mut passphrases = PASSPHRASES.get_or_init(Default::default).lock().unwrap();
+    if let Some(passphrase) = passphrases.get(&fd) {
+        return Ok(passphrase.clone());
+    }
+
+    // SAFETY: the caller of cargo is responsible for `fd` being open. It is
+    // left open, as it belongs to the caller.
+    let file = unsafe { std::fs::File::from_raw_fd(fd) };
+    let mut file = std::mem::ManuallyDrop::new(file);
+    let mut passphrase = String::new();
+    file.read_to_string(&mut passphrase)
+        .with_context(|| format!("failed to read passphrase from file descriptor {fd}"))?;
+    let passphrase = Secret::from(passphrase);
+    passphrases.insert(fd, passphrase.clone());
+    Ok(passphrase)
+}
+
+#[cfg(not(unix))]
+fn read_fd(_fd: i32) -> CargoResult<Secret<String>> {
+    bail!("`--passphrase-fd` is only supported on Unix")
+}
+
+#[cfg(unix)]
+fn set_permissions(file: &std::fs::File) -> CargoResult<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut perms = file.metadata()?.permissions();
+    perms.set_mode(0o600);
+    file.set_permissions(perms)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_permissions(_file: &std::fs::File) -> CargoResult<()> {
+    Ok(())
+}
@@ -3,6 +3,8 @@
 #![allow(clippy::print_stderr)]
 
 pub mod adaptor;
+pub mod encrypted_file;
 pub mod paseto;
 pub mod process;
 pub mod token;
+pub mod trusted_publishing;
@@ -1,4 +1,9 @@
 //! Credential provider that implements PASETO asymmetric tokens stored in Cargo's config.
+//!
+//! Besides `secret-key`, a registry can have `previous-secret-keys` left over
+//! from `cargo login --rotate-key`. A token is signed with whichever of these
+//! keys the registry advertises through the `kip` parameter of its `Cargo`
+//! challenge, falling back to `secret-key`.
 
 use anyhow::Context as _;
 use cargo_credential::{
@@ -15,8 +20,12 @@ use url::Url;
 use crate::{
     core::SourceId,
     ops::RegistryCredentialConfig,
-    util::{auth::registry_credential_config_raw, command_prelude::opt, context},
-    GlobalContext,
+    util::{
+        auth::registry_credential_config_raw,
+        command_prelude::{flag, opt},
+        context,
+    },
+    CargoResult, GlobalContext,
 };
 
 /// The main body of an asymmetric token as describe in RFC 3231.
@@ -75,6 +84,10 @@ impl<'a> Credential for PasetoCredential<'a> {
         let matches = Command::new("cargo:paseto")
             .no_binary_name(true)
             .arg(opt("key-subject", "Set the key subject for this registry").value_name("SUBJECT"))
+            .arg(flag(
+                "rotate-key",
+                "Replace the secret key, keeping the old one as a previous key",
+            ))
             .try_get_matches_from(args)
             .map_err(Box::new)?;
         let key_subject = matches.get_one("key-subject").map(String::as_str);
@@ -89,19 +102,26 @@ impl<'a> Credential for PasetoCredential<'a> {
                 };
 
                 let secret_key_subject = reg_cfg.secret_key_subject;
-                let secret: Secret<AsymmetricSecretKey<pasetors::version3::V3>> = secret_key
-                    .val
-                    .as_ref()
-                    .map(|key| key.as_str().try_into())
-                    .transpose()
-                    .context("failed to load private key")?;
-                let public: AsymmetricPublicKey<pasetors::version3::V3> = secret
-                    .as_ref()
-                    .map(|key| key.try_into())
-                    .transpose()
-                    .context("failed to load public key from private key")?
-                    .expose();
-                let kip: pasetors::paserk::Id = (&public).into();
+                let (mut secret, mut kip) = load_key(secret_key.val.as_deref())?;
+                let advertised = advertised_key_ids(&registry.headers);
+                if !advertised.is_empty() && !advertised.contains(&paserk_id(&kip)) {
+                    let previous_keys = reg_cfg
+                        .previous_secret_keys
+                        .map(|keys| keys.val)
+                        .unwrap_or_default();
+                    for previous_key in previous_keys {
+                        let (previous_secret, previous_kip) = load_key(previous_key.as_deref())
+                            .context("failed to load previous private key")?;
+                        if advertised.contains(&paserk_id(&previous_kip)) {
+                            tracing::debug!(
+                                "signing with previous key `{}`",
+                                paserk_id(&previous_kip)
+                            );
+                            (secret, kip) = (previous_secret, previous_kip);
+                            break;
+                        }
+                    }
+                }
 
                 let iat = OffsetDateTime::now_utc();
 
@@ -112,6 +132,8 @@ impl<'a> Credential for PasetoCredential<'a> {
                         Operation::Publish { .. } => Some("publish"),
                         Operation::Yank { .. } => Some("yank"),
                         Operation::Unyank { .. } => Some("unyank"),
+                        Operation::Promote { .. } => Some("promote"),
+                        Operation::EditMetadata { .. } => Some("edit-metadata"),
                         Operation::Owners { .. } => Some("owners"),
                         _ => None,
                     },
@@ -119,13 +141,17 @@ impl<'a> Credential for PasetoCredential<'a> {
                         Operation::Publish { name, .. }
                         | Operation::Yank { name, .. }
                         | Operation::Unyank { name, .. }
+                        | Operation::Promote { name, .. }
+                        | Operation::EditMetadata { name, .. }
                         | Operation::Owners { name, .. } => Some(name),
                         _ => None,
                     },
                     vers: match operation {
                         Operation::Publish { vers, .. }
                         | Operation::Yank { vers, .. }
-                        | Operation::Unyank { vers, .. } => Some(vers),
+                        | Operation::Unyank { vers, .. }
+                        | Operation::Promote { vers, .. }
+                        | Operation::EditMetadata { vers, .. } => Some(vers),
                         _ => None,
                     },
                     cksum: match operation {
@@ -171,7 +197,31 @@ impl<'a> Credential for PasetoCredential<'a> {
                 })
             }
             Action::Login(options) => {
-                let old_key_subject = reg_cfg.and_then(|cfg| cfg.secret_key_subject);
+                let (old_key, old_key_subject, old_previous_keys) = match reg_cfg {
+                    Some(cfg) => (
+                        cfg.secret_key,
+                        cfg.secret_key_subject,
+                        cfg.previous_secret_keys,
+                    ),
+                    None => (None, None, None),
+                };
+                // When rotating, the replaced key, like the ones replaced
+                // before it, stays valid until the registry stops asking for it.
+                let previous_keys = if matches.get_flag("rotate-key") {
+                    let Some(old_key) = old_key else {
+                        return Err(format!(
+                            "there is no secret-key for `{}` to rotate",
+                            sid.display_registry_name()
+                        )
+                        .into());
+                    };
+                    let mut previous_keys =
+                        old_previous_keys.map(|keys| keys.val).unwrap_or_default();
+                    previous_keys.push(old_key.val);
+                    previous_keys
+                } else {
+                    Vec::new()
+                };
                 let new_token;
                 let secret_key: Secret<String>;
                 if let Some(key) = &options.token {
@@ -195,6 +245,7 @@ impl<'a> Credential for PasetoCredential<'a> {
                         Some(key_subject) => Some(key_subject.to_string()),
                         None => old_key_subject,
                     },
+                    previous_keys,
                 ));
                 context::save_credentials(self.gctx, Some(new_token), &sid)?;
                 Ok(CredentialResponse::Login)
@@ -217,6 +268,61 @@ impl<'a> Credential for PasetoCredential<'a> {
     }
 }
 
+/// Loads a secret key in Paserk format, along with the id of its public key.
+fn load_key(
+    secret_key: Secret<&str>,
+) -> CargoResult<(
+    Secret<AsymmetricSecretKey<pasetors::version3::V3>>,
+    pasetors::paserk::Id,
+)> {
+    let secret: Secret<AsymmetricSecretKey<pasetors::version3::V3>> = secret_key
+        .map(|key| key.try_into())
+        .transpose()
+        .context("failed to load private key")?;
+    let public: AsymmetricPublicKey<pasetors::version3::V3> = secret
+        .as_ref()
+        .map(|key| key.try_into())
+        .transpose()
+        .context("failed to load public key from private key")?
+        .expose();
+    let kip = (&public).into();
+    Ok((secret, kip))
+}
+
+fn paserk_id(kip: &pasetors::paserk::Id) -> String {
+    let mut id = String::new();
+    FormatAsPaserk::fmt(kip, &mut id).unwrap();
+    id
+}
+
+/// Collects the key ids from the `kip` parameters of the `Cargo` challenges
+/// in the `WWW-Authenticate` headers the registry responded with.
+fn advertised_key_ids(headers: &[String]) -> Vec<String> {
+    let mut ids = Vec::new();
+    for header in headers {
+        let Some((name, value)) = header.split_once(':') else {
+            continue;
+        };
+        if !name.trim().eq_ignore_ascii_case("www-authenticate") {
+            continue;
+        }
+        for challenge in http_auth::ChallengeParser::new(value.trim()) {
+            match challenge {
+                Ok(challenge) if challenge.scheme.eq_ignore_ascii_case("Cargo") => {
+                    for (param, value) in challenge.params {
+                        if param.eq_ignore_ascii_case("kip") {
+                            ids.extend(value.to_unescaped().split_whitespace().map(str::to_string));
+                        }
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => tracing::debug!("failed to parse challenge: {e}"),
+            }
+        }
+    }
+    ids
+}
+
 /// Checks that a secret key is valid, and returns the associated public key in Paserk format.
 pub(crate) fn paserk_public_from_paserk_secret(secret_key: Secret<&str>) -> Option<String> {
     let secret: Secret<AsymmetricSecretKey<pasetors::version3::V3>> =
@@ -0,0 +1,162 @@
+//! Credential provider that exchanges a workload identity token, such as the
+//! OIDC token issued to a CI job, for a short-lived registry token.
+
+use anyhow::bail;
+use anyhow::Context as _;
+use cargo_credential::{
+    Action, CacheControl, Credential, CredentialResponse, Error, RegistryInfo, Secret,
+};
+use clap::{ArgGroup, ArgMatches, Command};
+use curl::easy::List;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+use crate::util::command_prelude::opt;
+use crate::util::network::http::http_handle;
+use crate::{CargoResult, GlobalContext};
+
+/// The body of a token exchange request.
+#[derive(serde::Serialize)]
+struct ExchangeRequest<'a> {
+    /// The workload identity token.
+    jwt: &'a str,
+}
+
+/// The body of a successful token exchange response.
+#[derive(serde::Deserialize)]
+struct ExchangeResponse {
+    token: String,
+    /// When the registry token expires, in RFC 3339 format.
+    expires_at: Option<String>,
+}
+
+pub(crate) struct TrustedPublishingCredential<'a> {
+    gctx: &'a GlobalContext,
+}
+
+impl<'a> TrustedPublishingCredential<'a> {
+    pub fn new(gctx: &'a GlobalContext) -> Self {
+        Self { gctx }
+    }
+
+    /// Reads the workload identity token from where the provider arguments
+    /// say it is, returning `None` if it isn't there, e.g. outside of CI.
+    fn identity_token(&self, matches: &ArgMatches) -> CargoResult<Option<Secret<String>>> {
+        let token = if let Some(path) = matches.get_one::<String>("token-file") {
+            match std::fs::read_to_string(path) {
+                Ok(token) => token,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    tracing::debug!("identity token file `{path}` does not exist");
+                    return Ok(None);
+                }
+                Err(e) => {
+                    return Err(e)
+                        .with_context(|| format!("failed to read identity token from `{path}`"))
+                }
+            }
+        } else {
+            let var = matches.get_one::<String>("token-env").unwrap();
+            match self.gctx.get_env(var) {
+                Ok(token) => token,
+                Err(_) => {
+                    tracing::debug!("identity token environment variable `{var}` is not set");
+                    return Ok(None);
+                }
+            }
+        };
+        let token = token.trim();
+        Ok((!token.is_empty()).then(|| Secret::from(token.to_string())))
+    }
+
+    /// Exchanges the identity token for a registry token at `endpoint`.
+    fn exchange(&self, endpoint: &str, identity: Secret<&str>) -> CargoResult<ExchangeResponse> {
+        let body = serde_json::to_vec(&ExchangeRequest {
+            jwt: identity.expose(),
+        })?;
+        let mut handle = http_handle(self.gctx)?;
+        handle.url(endpoint)?;
+        handle.post(true)?;
+        handle.post_fields_copy(&body)?;
+        let mut headers = List::new();
+        headers.append("Accept: application/json")?;
+        headers.append("Content-Type: application/json")?;
+        handle.http_headers(headers)?;
+
+        let mut response = Vec::new();
+        {
+            let mut transfer = handle.transfer();
+            transfer.write_function(|data| {
+                response.extend_from_slice(data);
+                Ok(data.len())
+            })?;
+            transfer
+                .perform()
+                .with_context(|| format!("failed to send identity token to `{endpoint}`"))?;
+        }
+        let code = handle.response_code()?;
+        if code != 200 {
+            bail!(
+                "failed to exchange identity token at `{endpoint}` (status {code}): {}",
+                String::from_utf8_lossy(&response).trim()
+            );
+        }
+        serde_json::from_slice(&response)
+            .with_context(|| format!("invalid token exchange response from `{endpoint}`"))
+    }
+}
+
+impl<'a> Credential for TrustedPublishingCredential<'a> {
+    fn perform(
+        &self,
+        _registry: &RegistryInfo<'_>,
+        action: &Action<'_>,
+        args: &[&str],
+    ) -> Result<CredentialResponse, Error> {
+        let matches = Command::new("cargo:trusted-publishing")
+            .no_binary_name(true)
+            .arg(
+                opt("endpoint", "URL to exchange the identity token at")
+                    .value_name("URL")
+                    .required(true),
+            )
+            .arg(opt("token-file", "File to read the identity token from").value_name("PATH"))
+            .arg(
+                opt(
+                    "token-env",
+                    "Environment variable to read the identity token from",
+                )
+                .value_name("VAR"),
+            )
+            .group(
+                ArgGroup::new("identity")
+                    .args(["token-file", "token-env"])
+                    .required(true),
+            )
+            .try_get_matches_from(args)
+            .map_err(Box::new)?;
+
+        match action {
+            Action::Get(_) => {
+                let Some(identity) = self.identity_token(&matches)? else {
+                    return Err(Error::NotFound);
+                };
+                let endpoint = matches.get_one::<String>("endpoint").unwrap();
+                let response = self.exchange(endpoint, identity.as_deref())?;
+
+                let cache = match response.expires_at {
+                    Some(expires_at) => CacheControl::Expires {
+                        expiration: OffsetDateTime::parse(&expires_at, &Rfc3339).with_context(
+                            || format!("invalid token expiration `{expires_at}` from `{endpoint}`"),
+                        )?,
+                    },
+                    None => CacheControl::Session,
+                };
+                Ok(CredentialResponse::Get {
+                    token: Secret::from(response.token),
+                    cache,
+                    operation_independent: false,
+                })
+            }
+            _ => Err(Error::OperationNotSupported),
+        }
+    }
+}
@@ -1,15 +1,40 @@
-use crate::core::{Edition, Feature, Features, Manifest, Package};
+use crate::core::compiler::unused_dependencies::UnusedDependency;
+use crate::core::compiler::MessageFormat;
+use crate::core::dependency::DepKind;
+use crate::core::registry::Registry;
+use crate::core::{
+    Dependency, Edition, Feature, FeatureValue, Features, Manifest, MaybePackage, Package,
+    PackageId, Resolve, Workspace,
+};
+use crate::sources::source::QueryKind;
+use crate::sources::IndexSummary;
+use crate::util::context::{Definition, LintsConfig, Value};
+use crate::util::interning::InternedString;
+use crate::util::machine_message::{self, Message as _};
 use crate::{CargoResult, GlobalContext};
-use annotate_snippets::{Level, Snippet};
-use cargo_util_schemas::manifest::{TomlLintLevel, TomlToolLints};
+use annotate_snippets::{Level, Renderer, Snippet};
+use cargo_util_schemas::manifest::{RustVersion, TomlLint, TomlLintLevel, TomlToolLints};
+use itertools::Itertools;
 use pathdiff::diff_paths;
+use std::collections::{BTreeMap, HashSet};
 use std::fmt::Display;
 use std::ops::Range;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::task::Poll;
 use toml_edit::ImDocument;
 
 const LINT_GROUPS: &[LintGroup] = &[TEST_DUMMY_UNSTABLE];
-pub const LINTS: &[Lint] = &[IM_A_TEAPOT, UNKNOWN_LINTS];
+pub const LINTS: &[Lint] = &[
+    DIVERGENT_DEPENDENCY_VERSIONS,
+    FEATURE_ENABLES_DEV_DEPENDENCY,
+    IM_A_TEAPOT,
+    INCOMPATIBLE_DEPENDENCY_RUST_VERSION,
+    INEFFECTIVE_WEAK_DEPENDENCY_FEATURE,
+    SHADOWED_OPTIONAL_DEPENDENCY,
+    UNKNOWN_LINTS,
+    UNUSED_DEPENDENCIES,
+    UNUSED_FEATURES,
+];
 
 pub fn analyze_cargo_lints_table(
     pkg: &Package,
@@ -18,12 +43,13 @@ pub fn analyze_cargo_lints_table(
     ws_contents: &str,
     ws_document: &ImDocument<String>,
     ws_path: &Path,
+    message_format: MessageFormat,
     gctx: &GlobalContext,
 ) -> CargoResult<()> {
     let mut error_count = 0;
     let manifest = pkg.manifest();
     let manifest_path = rel_cwd_manifest_path(path, gctx);
-    let ws_path = rel_cwd_manifest_path(ws_path, gctx);
+    let rel_ws_path = rel_cwd_manifest_path(ws_path, gctx);
     let mut unknown_lints = Vec::new();
     for lint_name in pkg_lints.keys().map(|name| name) {
         let Some((name, default_level, edition_lint_opts, feature_gate)) =
@@ -38,6 +64,7 @@ pub fn analyze_cargo_lints_table(
             *default_level,
             *edition_lint_opts,
             pkg_lints,
+            None,
             manifest.edition(),
         );
 
@@ -55,7 +82,7 @@ pub fn analyze_cargo_lints_table(
                 &manifest_path,
                 ws_contents,
                 ws_document,
-                &ws_path,
+                &rel_ws_path,
                 &mut error_count,
                 gctx,
             )?;
@@ -64,13 +91,13 @@ pub fn analyze_cargo_lints_table(
 
     output_unknown_lints(
         unknown_lints,
-        manifest,
-        &manifest_path,
+        pkg,
         pkg_lints,
         ws_contents,
         ws_document,
-        &ws_path,
+        ws_path,
         &mut error_count,
+        message_format,
         gctx,
     )?;
 
@@ -222,7 +249,7 @@ pub fn get_span(
             let array = item.as_array().unwrap();
             let next = iter.next().unwrap();
             return array.iter().find_map(|item| {
-                if next == &item.to_string() {
+                if next == &item.to_string() || item.as_str() == Some(next) {
                     item.span()
                 } else {
                     None
@@ -242,6 +269,261 @@ pub fn rel_cwd_manifest_path(path: &Path, gctx: &GlobalContext) -> String {
         .to_string()
 }
 
+/// A diagnostic of a cargo lint, printed by [`LintDiagnostic::emit`] as text
+/// or, with `--message-format json`, as a [`machine_message::CargoLint`].
+struct LintDiagnostic<'a> {
+    lint: &'a Lint,
+    level: LintLevel,
+    pkg: &'a Package,
+    title: String,
+    span: LintSpan<'a>,
+    children: Vec<LintChild<'a>>,
+}
+
+/// A span of a manifest that a diagnostic points to.
+struct LintSpan<'a> {
+    path: &'a Path,
+    contents: &'a str,
+    range: Range<usize>,
+    /// The level of the annotation, which sets how the span is underlined.
+    level: Level,
+}
+
+impl LintSpan<'_> {
+    fn snippet<'s>(&'s self, origin: &'s str) -> Snippet<'s> {
+        Snippet::source(self.contents)
+            .origin(origin)
+            .annotation(self.level.span(self.range.clone()))
+            .fold(true)
+    }
+}
+
+/// A note or a help of a diagnostic.
+struct LintChild<'a> {
+    level: Level,
+    title: String,
+    span: Option<LintSpan<'a>>,
+    /// A replacement for a span of the manifest of the package, and how
+    /// applicable it is, which is only part of the JSON diagnostic.
+    suggestion: Option<(Range<usize>, String, &'static str)>,
+}
+
+impl<'a> LintDiagnostic<'a> {
+    /// A diagnostic of `lint` for `range` of the manifest of `pkg`.
+    fn new(
+        lint: &'a Lint,
+        level: LintLevel,
+        pkg: &'a Package,
+        title: String,
+        range: Range<usize>,
+    ) -> Self {
+        let span = LintSpan {
+            path: pkg.manifest_path(),
+            contents: pkg.manifest().contents(),
+            range,
+            level: level.to_diagnostic_level(),
+        };
+        Self::with_span(lint, level, pkg, title, span)
+    }
+
+    /// A diagnostic of `lint` for a span of any manifest, like the one of
+    /// the workspace when it is inherited from there.
+    fn with_span(
+        lint: &'a Lint,
+        level: LintLevel,
+        pkg: &'a Package,
+        title: String,
+        span: LintSpan<'a>,
+    ) -> Self {
+        LintDiagnostic {
+            lint,
+            level,
+            pkg,
+            title,
+            span,
+            children: Vec::new(),
+        }
+    }
+
+    fn child(mut self, level: Level, title: String, span: Option<LintSpan<'a>>) -> Self {
+        self.children.push(LintChild {
+            level,
+            title,
+            span,
+            suggestion: None,
+        });
+        self
+    }
+
+    fn note(self, title: impl Into<String>) -> Self {
+        self.child(Level::Note, title.into(), None)
+    }
+
+    /// Adds the note saying where the level of the lint was set, unless
+    /// `emitted_source` says an earlier diagnostic already had it.
+    fn source_note(self, reason: &LintLevelReason, emitted_source: &mut bool) -> Self {
+        if std::mem::replace(emitted_source, true) {
+            return self;
+        }
+        let note = format!(
+            "`cargo::{}` is set to `{}` {reason}",
+            self.lint.name, self.level
+        );
+        self.note(note)
+    }
+
+    fn help(self, title: impl Into<String>) -> Self {
+        self.child(Level::Help, title.into(), None)
+    }
+
+    /// Adds a help suggesting to replace `range` of the manifest of the
+    /// package with `replacement`.
+    fn help_with_suggestion(
+        mut self,
+        title: impl Into<String>,
+        range: Range<usize>,
+        replacement: String,
+        applicability: &'static str,
+    ) -> Self {
+        self.children.push(LintChild {
+            level: Level::Help,
+            title: title.into(),
+            span: None,
+            suggestion: Some((range, replacement, applicability)),
+        });
+        self
+    }
+
+    fn emit(
+        self,
+        error_count: &mut usize,
+        message_format: MessageFormat,
+        gctx: &GlobalContext,
+    ) -> CargoResult<()> {
+        if self.level == LintLevel::Forbid || self.level == LintLevel::Deny {
+            *error_count += 1;
+        }
+        let level = self.level.to_diagnostic_level();
+        let origin = |span: &LintSpan<'_>| rel_cwd_manifest_path(span.path, gctx);
+        let origins = std::iter::once(origin(&self.span))
+            .chain(
+                self.children
+                    .iter()
+                    .map(|child| child.span.as_ref().map(origin).unwrap_or_default()),
+            )
+            .collect::<Vec<_>>();
+        let mut message = level
+            .title(&self.title)
+            .snippet(self.span.snippet(&origins[0]));
+        for (child, origin) in self.children.iter().zip(&origins[1..]) {
+            let mut footer = child.level.title(&child.title);
+            if let Some(span) = &child.span {
+                footer = footer.snippet(span.snippet(origin));
+            }
+            message = message.footer(footer);
+        }
+
+        let MessageFormat::Json {
+            render_diagnostics: false,
+            ansi,
+            ..
+        } = message_format
+        else {
+            gctx.shell().print_message(message)?;
+            return Ok(());
+        };
+        let renderer = if ansi {
+            Renderer::styled()
+        } else {
+            Renderer::plain()
+        };
+        let rendered = format!("{}\n", renderer.render(message));
+        let diagnostic_span = |span: &LintSpan<'_>, is_primary| {
+            machine_message::DiagnosticSpan::new(
+                span.path,
+                span.contents,
+                span.range.clone(),
+                is_primary,
+            )
+        };
+        let children = self
+            .children
+            .iter()
+            .map(|child| {
+                let mut spans = child
+                    .span
+                    .iter()
+                    .map(|span| diagnostic_span(span, true))
+                    .collect::<Vec<_>>();
+                if let Some((range, replacement, applicability)) = &child.suggestion {
+                    let mut span = machine_message::DiagnosticSpan::new(
+                        self.pkg.manifest_path(),
+                        self.pkg.manifest().contents(),
+                        range.clone(),
+                        true,
+                    );
+                    span.suggested_replacement = Some(replacement.clone());
+                    span.suggestion_applicability = Some(applicability);
+                    spans.push(span);
+                }
+                machine_message::Diagnostic {
+                    message_type: None,
+                    message: child.title.clone(),
+                    code: None,
+                    level: diagnostic_level(child.level),
+                    spans,
+                    children: Vec::new(),
+                    rendered: None,
+                }
+            })
+            .collect();
+        let msg = machine_message::CargoLint {
+            package_id: self.pkg.package_id().to_spec(),
+            manifest_path: self.pkg.manifest_path(),
+            message: machine_message::Diagnostic {
+                message_type: Some("diagnostic"),
+                message: self.title.clone(),
+                code: Some(machine_message::DiagnosticCode {
+                    code: format!("cargo::{}", self.lint.name),
+                    explanation: None,
+                }),
+                level: diagnostic_level(level),
+                spans: vec![diagnostic_span(&self.span, true)],
+                children,
+                rendered: Some(rendered),
+            },
+        }
+        .to_json_string();
+        writeln!(gctx.shell().out(), "{msg}")?;
+        Ok(())
+    }
+}
+
+/// The range of the whole line of `contents` that `span` is on, with its line
+/// ending, if nothing else is on that line.
+fn line_of(contents: &str, span: Range<usize>) -> Option<Range<usize>> {
+    let start = contents[..span.start].rfind('\n').map_or(0, |i| i + 1);
+    let end = contents[span.end..]
+        .find('\n')
+        .map_or(contents.len(), |i| span.end + i + 1);
+    let is_blank = |s: &str| s.trim().is_empty();
+    (is_blank(&contents[start..span.start])
+        && is_blank(&contents[span.end..end])
+        && !contents[span.clone()].contains('\n'))
+    .then_some(start..end)
+}
+
+/// The name rustc gives `level` in JSON diagnostics.
+fn diagnostic_level(level: Level) -> &'static str {
+    match level {
+        Level::Error => "error",
+        Level::Warning => "warning",
+        Level::Info => "info",
+        Level::Note => "note",
+        Level::Help => "help",
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct LintGroup {
     pub name: &'static str,
@@ -278,9 +560,11 @@ impl Lint {
     pub fn level(
         &self,
         pkg_lints: &TomlToolLints,
+        config_lints: &LintsConfig,
         edition: Edition,
         unstable_features: &Features,
     ) -> (LintLevel, LintLevelReason) {
+        let config_lints = config_lints.get("cargo");
         // We should return `Allow` if a lint is behind a feature, but it is
         // not enabled, that way the lint does not run.
         if self
@@ -300,6 +584,7 @@ impl Lint {
                         g.default_level,
                         g.edition_lint_opts,
                         pkg_lints,
+                        config_lints,
                         edition,
                     ),
                 )
@@ -311,6 +596,7 @@ impl Lint {
                     self.default_level,
                     self.edition_lint_opts,
                     pkg_lints,
+                    config_lints,
                     edition,
                 ),
             )))
@@ -361,11 +647,13 @@ impl From<TomlLintLevel> for LintLevel {
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum LintLevelReason {
     Default,
     Edition(Edition),
     Package,
+    /// Set in the `[lints]` config, where the definition says.
+    Config(Definition),
 }
 
 impl Display for LintLevelReason {
@@ -374,6 +662,10 @@ impl Display for LintLevelReason {
             LintLevelReason::Default => write!(f, "by default"),
             LintLevelReason::Edition(edition) => write!(f, "in edition {}", edition),
             LintLevelReason::Package => write!(f, "in `[lints]`"),
+            LintLevelReason::Config(Definition::Path(path) | Definition::Cli(Some(path))) => {
+                write!(f, "in `[lints]` of `{}`", path.display())
+            }
+            LintLevelReason::Config(definition) => write!(f, "by {definition}"),
         }
     }
 }
@@ -384,15 +676,19 @@ impl LintLevelReason {
             LintLevelReason::Default => false,
             LintLevelReason::Edition(_) => false,
             LintLevelReason::Package => true,
+            LintLevelReason::Config(_) => true,
         }
     }
 }
 
+/// The level of lint `name`, with its reason and priority. A level set in the
+/// `[lints]` config takes precedence over one set in the manifest.
 fn level_priority(
     name: &str,
     default_level: LintLevel,
     edition_lint_opts: Option<(Edition, LintLevel)>,
     pkg_lints: &TomlToolLints,
+    config_lints: Option<&BTreeMap<String, Value<TomlLint>>>,
     edition: Edition,
 ) -> (LintLevel, LintLevelReason, i8) {
     let (unspecified_level, reason) = if let Some(level) = edition_lint_opts
@@ -409,7 +705,13 @@ fn level_priority(
         return (unspecified_level, reason, 0);
     }
 
-    if let Some(defined_level) = pkg_lints.get(name) {
+    if let Some(defined_level) = config_lints.and_then(|lints| lints.get(name)) {
+        (
+            defined_level.val.level().into(),
+            LintLevelReason::Config(defined_level.definition.clone()),
+            defined_level.val.priority(),
+        )
+    } else if let Some(defined_level) = pkg_lints.get(name) {
         (
             defined_level.level().into(),
             LintLevelReason::Package,
@@ -420,6 +722,503 @@ fn level_priority(
     }
 }
 
+const DIVERGENT_DEPENDENCY_VERSIONS: Lint = Lint {
+    name: "divergent_dependency_versions",
+    desc: "dependency is required with different versions across the workspace",
+    groups: &[],
+    default_level: LintLevel::Allow,
+    edition_lint_opts: None,
+    feature_gate: None,
+    docs: Some(
+        r#"
+### What it does
+Checks for dependencies that workspace members require with different, but
+semver compatible, version requirements, instead of inheriting one from
+`[workspace.dependencies]`.
+
+### Why it is bad
+The requirements drift apart as members are updated one at a time, so it is
+hard to tell which version the workspace actually needs, and members can end
+up tested against versions older than the ones they get in practice.
+
+`cargo fix` moves the highest of the requirements to `[workspace.dependencies]`
+and makes the members inherit it.
+
+### Example
+```toml
+# a/Cargo.toml
+[dependencies]
+serde = "1.0.100"
+
+# b/Cargo.toml
+[dependencies]
+serde = "1.0.200"
+```
+
+Should be written as:
+
+```toml
+# Cargo.toml
+[workspace.dependencies]
+serde = "1.0.200"
+
+# a/Cargo.toml and b/Cargo.toml
+[dependencies]
+serde.workspace = true
+```
+"#,
+    ),
+};
+
+/// Where a workspace member requires a registry dependency with its own version
+/// requirement, rather than inheriting it from the workspace.
+#[derive(Debug)]
+pub struct DependencyRequirement {
+    pub manifest_path: PathBuf,
+    /// Keys of the dependency table, like `["target", "cfg(unix)", "dependencies"]`.
+    pub table: Vec<String>,
+    pub req: String,
+    pub default_features: bool,
+}
+
+/// A dependency that workspace members require with different, but semver
+/// compatible, caret version requirements.
+#[derive(Debug)]
+pub struct DivergentDependency {
+    pub name: String,
+    pub registry: Option<String>,
+    /// The requirement in `[workspace.dependencies]`, if there is one.
+    pub workspace_req: Option<String>,
+    /// The requirement with the highest minimum version. As all requirements
+    /// are caret requirements with the same upper bound, any version it
+    /// matches is matched by the requirements of all members.
+    pub unified_req: String,
+    pub requirements: Vec<DependencyRequirement>,
+}
+
+/// Finds the dependencies of workspace members for [`DIVERGENT_DEPENDENCY_VERSIONS`].
+pub fn find_divergent_dependencies(ws: &Workspace<'_>) -> Vec<DivergentDependency> {
+    let ws_document = match ws.root_maybe() {
+        MaybePackage::Package(pkg) => pkg.manifest().document(),
+        MaybePackage::Virtual(vm) => vm.document(),
+    };
+    let workspace_deps = ws_document
+        .get("workspace")
+        .and_then(|w| w.get("dependencies"))
+        .and_then(|d| d.as_table_like());
+
+    let mut groups: BTreeMap<_, Vec<DependencyRequirement>> = BTreeMap::new();
+    for member in ws.members() {
+        let document = member.manifest().document();
+        for table in dependency_tables(document) {
+            let Some(deps) = table
+                .iter()
+                .try_fold(document.as_item(), |item, key| item.get(key))
+                .and_then(|t| t.as_table_like())
+            else {
+                continue;
+            };
+            for (name, item) in deps.iter() {
+                let Some((req, registry, default_features)) = own_registry_requirement(item) else {
+                    continue;
+                };
+                let Some(class) = compatibility_class(&req) else {
+                    continue;
+                };
+                groups
+                    .entry((name.to_owned(), registry, class))
+                    .or_default()
+                    .push(DependencyRequirement {
+                        manifest_path: member.manifest_path().to_owned(),
+                        table: table.clone(),
+                        req,
+                        default_features,
+                    });
+            }
+        }
+    }
+
+    let mut divergent = Vec::new();
+    for ((name, registry, class), requirements) in groups {
+        let workspace_req = match workspace_deps.and_then(|d| d.get(&name)) {
+            Some(item) => match own_registry_requirement(item) {
+                Some((req, ws_registry, _))
+                    if ws_registry == registry && compatibility_class(&req) == Some(class) =>
+                {
+                    Some(req)
+                }
+                // The members can't inherit the workspace's requirement
+                // without changing which package they depend on.
+                _ => continue,
+            },
+            None => None,
+        };
+        let mut reqs = requirements
+            .iter()
+            .map(|r| r.req.trim())
+            .chain(workspace_req.as_deref().map(str::trim))
+            .collect::<Vec<_>>();
+        reqs.dedup();
+        if reqs.iter().all(|req| *req == reqs[0]) {
+            continue;
+        }
+        let unified_req = reqs
+            .iter()
+            .rev()
+            .max_by_key(|req| minimum_version(req))
+            .unwrap()
+            .to_string();
+        divergent.push(DivergentDependency {
+            name,
+            registry,
+            workspace_req,
+            unified_req,
+            requirements,
+        });
+    }
+    divergent
+}
+
+/// The keys of the tables in a manifest that can declare dependencies.
+fn dependency_tables(document: &ImDocument<String>) -> Vec<Vec<String>> {
+    const KINDS: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+    let mut tables = KINDS
+        .iter()
+        .map(|kind| vec![kind.to_string()])
+        .collect::<Vec<_>>();
+    if let Some(targets) = document.get("target").and_then(|t| t.as_table_like()) {
+        for (target, _) in targets.iter() {
+            tables.extend(
+                KINDS
+                    .iter()
+                    .map(|kind| vec!["target".to_string(), target.to_string(), kind.to_string()]),
+            );
+        }
+    }
+    tables
+}
+
+/// Returns the version requirement, registry, and whether default features
+/// are enabled, for a dependency on a registry package under its own name.
+fn own_registry_requirement(item: &toml_edit::Item) -> Option<(String, Option<String>, bool)> {
+    if let Some(req) = item.as_str() {
+        return Some((req.to_owned(), None, true));
+    }
+    let table = item.as_table_like()?;
+    if [
+        "workspace",
+        "path",
+        "git",
+        "package",
+        "registry-index",
+        "base",
+    ]
+    .iter()
+    .any(|key| table.contains_key(key))
+    {
+        return None;
+    }
+    let req = table.get("version")?.as_str()?.to_owned();
+    let registry = table
+        .get("registry")
+        .and_then(|r| r.as_str())
+        .map(str::to_owned);
+    let default_features = ["default-features", "default_features"]
+        .iter()
+        .find_map(|key| table.get(key).and_then(|v| v.as_bool()))
+        .unwrap_or(true);
+    Some((req, registry, default_features))
+}
+
+/// The single caret comparator of a requirement like `1.2` or `^1.2.3`.
+///
+/// Other requirements, like `=1.2.3`, `~1.2` or `>=1.2, <1.5`, are pinned on
+/// purpose, and can't be unified without loosening or breaking them.
+fn caret_comparator(req: &str) -> Option<semver::Comparator> {
+    let req = semver::VersionReq::parse(req).ok()?;
+    match req.comparators.as_slice() {
+        [comparator] if comparator.op == semver::Op::Caret && comparator.pre.is_empty() => {
+            Some(comparator.clone())
+        }
+        _ => None,
+    }
+}
+
+/// The minimum version of a caret requirement, as `(major, minor, patch)`.
+fn minimum_version(req: &str) -> Option<(u64, u64, u64)> {
+    let comparator = caret_comparator(req)?;
+    Some((
+        comparator.major,
+        comparator.minor.unwrap_or(0),
+        comparator.patch.unwrap_or(0),
+    ))
+}
+
+/// The semver compatible series a caret requirement matches, identified by
+/// the version it stops at, like `2.0.0` for `1.2` or `0.3.0` for `0.2.1`.
+fn compatibility_class(req: &str) -> Option<(u64, u64, u64)> {
+    let comparator = caret_comparator(req)?;
+    if comparator.major > 0 {
+        return Some((comparator.major + 1, 0, 0));
+    }
+    Some(match (comparator.minor, comparator.patch) {
+        (None, _) => (1, 0, 0),
+        (Some(0), None) => (0, 1, 0),
+        (Some(0), Some(patch)) => (0, 0, patch + 1),
+        (Some(minor), _) => (0, minor + 1, 0),
+    })
+}
+
+/// The level of [`DIVERGENT_DEPENDENCY_VERSIONS`] for `pkg`, so that
+/// `cargo fix` only changes the members that enable it.
+pub fn divergent_dependency_versions_level(
+    pkg: &Package,
+    gctx: &GlobalContext,
+) -> CargoResult<LintLevel> {
+    Ok(package_lint_level(&DIVERGENT_DEPENDENCY_VERSIONS, pkg, gctx)?.0)
+}
+
+/// The level of `lint` set in the `[lints.cargo]` table of `pkg`, or in the
+/// `[lints]` config.
+fn package_lint_level(
+    lint: &Lint,
+    pkg: &Package,
+    gctx: &GlobalContext,
+) -> CargoResult<(LintLevel, LintLevelReason)> {
+    let manifest = pkg.manifest();
+    let cargo_lints = manifest
+        .normalized_toml()
+        .lints
+        .as_ref()
+        .and_then(|lints| lints.lints.get("cargo"))
+        .cloned()
+        .unwrap_or_default();
+    Ok(lint.level(
+        &cargo_lints,
+        gctx.lints_config()?,
+        manifest.edition(),
+        manifest.unstable_features(),
+    ))
+}
+
+/// Emits [`DIVERGENT_DEPENDENCY_VERSIONS`] for `pkg`, out of the `divergent`
+/// dependencies found with [`find_divergent_dependencies`] for its workspace.
+pub fn check_divergent_dependency_versions(
+    divergent: &[DivergentDependency],
+    pkg: &Package,
+    pkg_lints: &TomlToolLints,
+    error_count: &mut usize,
+    message_format: MessageFormat,
+    gctx: &GlobalContext,
+) -> CargoResult<()> {
+    let manifest = pkg.manifest();
+    let (lint_level, reason) = DIVERGENT_DEPENDENCY_VERSIONS.level(
+        pkg_lints,
+        gctx.lints_config()?,
+        manifest.edition(),
+        manifest.unstable_features(),
+    );
+
+    if lint_level == LintLevel::Allow {
+        return Ok(());
+    }
+
+    let mut emitted_source = false;
+    for dep in divergent {
+        for requirement in &dep.requirements {
+            if requirement.manifest_path != pkg.manifest_path() {
+                continue;
+            }
+            let title = format!("{}: `{}`", DIVERGENT_DEPENDENCY_VERSIONS.desc, dep.name);
+            let mut dep_path = requirement
+                .table
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>();
+            dep_path.push(&dep.name);
+            let span = get_span(manifest.document(), &dep_path, true).unwrap();
+
+            let mut notes = dep
+                .requirements
+                .iter()
+                .filter(|other| other.req != requirement.req)
+                .map(|other| {
+                    format!(
+                        "`{}` requires `{}`",
+                        rel_cwd_manifest_path(&other.manifest_path, gctx),
+                        other.req
+                    )
+                })
+                .unique()
+                .collect::<Vec<_>>();
+            if let Some(workspace_req) = &dep.workspace_req {
+                notes.push(format!(
+                    "`[workspace.dependencies]` requires `{workspace_req}`"
+                ));
+            }
+            let help = if dep.workspace_req.is_some() {
+                format!(
+                    "use `{}.workspace = true`, or run `cargo fix` to do it",
+                    dep.name
+                )
+            } else {
+                format!(
+                    "move `{}` to `[workspace.dependencies]` and use `{}.workspace = true`, \
+                     or run `cargo fix` to do it",
+                    dep.name, dep.name
+                )
+            };
+
+            let mut diagnostic =
+                LintDiagnostic::new(&DIVERGENT_DEPENDENCY_VERSIONS, lint_level, pkg, title, span);
+            for note in notes {
+                diagnostic = diagnostic.note(note);
+            }
+            diagnostic
+                .source_note(&reason, &mut emitted_source)
+                .help(help)
+                .emit(error_count, message_format, gctx)?;
+        }
+    }
+    Ok(())
+}
+
+const FEATURE_ENABLES_DEV_DEPENDENCY: Lint = Lint {
+    name: "feature_enables_dev_dependency",
+    desc: "feature enables a feature of a dev-dependency",
+    groups: &[],
+    default_level: LintLevel::Allow,
+    edition_lint_opts: None,
+    feature_gate: None,
+    docs: Some(
+        r#"
+### What it does
+Checks for features that enable a feature of a dependency, where the
+dependency is only declared in `[dev-dependencies]`.
+
+### Why it is bad
+Dev-dependencies are not built for the dependents of a package, so for them
+the dependency doesn't exist and enabling the feature does nothing. This is
+fine for features only meant for the package's own tests, but is a mistake
+otherwise.
+
+### Example
+```toml
+[dev-dependencies]
+serde = "1.0.200"
+
+[features]
+serde = ["serde/derive"]
+```
+
+Should be written as:
+
+```toml
+[dependencies]
+serde = { version = "1.0.200", optional = true }
+
+[features]
+serde = ["dep:serde", "serde/derive"]
+```
+"#,
+    ),
+};
+
+/// The features declared in the `[features]` table of `pkg`, with their
+/// values, leaving out the implicit features of optional dependencies.
+fn explicit_features(pkg: &Package) -> Vec<(InternedString, &[FeatureValue])> {
+    let document = pkg.manifest().document();
+    pkg.summary()
+        .features()
+        .iter()
+        .filter(|(name, _)| get_span(document, &["features", name], false).is_some())
+        .map(|(name, values)| (*name, values.as_slice()))
+        .collect()
+}
+
+/// Prints a diagnostic of `lint` for a span of the manifest of `pkg`.
+///
+/// The note saying where the level of the lint was set is only added to the
+/// first diagnostic, tracked with `emitted_source`.
+fn emit_manifest_lint(
+    lint: &Lint,
+    (lint_level, reason): &(LintLevel, LintLevelReason),
+    pkg: &Package,
+    span: Range<usize>,
+    title: &str,
+    help: Option<&str>,
+    emitted_source: &mut bool,
+    error_count: &mut usize,
+    message_format: MessageFormat,
+    gctx: &GlobalContext,
+) -> CargoResult<()> {
+    let mut diagnostic = LintDiagnostic::new(lint, *lint_level, pkg, title.to_owned(), span)
+        .source_note(reason, emitted_source);
+    if let Some(help) = help {
+        diagnostic = diagnostic.help(help);
+    }
+    diagnostic.emit(error_count, message_format, gctx)
+}
+
+pub fn check_feature_enables_dev_dependency(
+    pkg: &Package,
+    pkg_lints: &TomlToolLints,
+    error_count: &mut usize,
+    message_format: MessageFormat,
+    gctx: &GlobalContext,
+) -> CargoResult<()> {
+    let manifest = pkg.manifest();
+    let level = FEATURE_ENABLES_DEV_DEPENDENCY.level(
+        pkg_lints,
+        gctx.lints_config()?,
+        manifest.edition(),
+        manifest.unstable_features(),
+    );
+    if level.0 == LintLevel::Allow {
+        return Ok(());
+    }
+
+    let mut emitted_source = false;
+    for (feature, values) in explicit_features(pkg) {
+        for value in values {
+            let FeatureValue::DepFeature { dep_name, .. } = value else {
+                continue;
+            };
+            let mut deps = pkg
+                .dependencies()
+                .iter()
+                .filter(|dep| dep.name_in_toml() == *dep_name)
+                .peekable();
+            if deps.peek().is_none() || deps.any(|dep| dep.kind() != DepKind::Development) {
+                continue;
+            }
+            let value = value.to_string();
+            let Some(span) = get_span(manifest.document(), &["features", &feature, &value], true)
+            else {
+                continue;
+            };
+            let title = format!("{}: `{value}`", FEATURE_ENABLES_DEV_DEPENDENCY.desc);
+            let help = format!(
+                "`{dep_name}` is only a dev-dependency, so this has no effect for dependents of `{}`",
+                pkg.name()
+            );
+            emit_manifest_lint(
+                &FEATURE_ENABLES_DEV_DEPENDENCY,
+                &level,
+                pkg,
+                span,
+                &title,
+                Some(&help),
+                &mut emitted_source,
+                error_count,
+                message_format,
+                gctx,
+            )?;
+        }
+    }
+    Ok(())
+}
+
 /// This lint is only to be used for testing purposes
 const IM_A_TEAPOT: Lint = Lint {
     name: "im_a_teapot",
@@ -433,14 +1232,18 @@ const IM_A_TEAPOT: Lint = Lint {
 
 pub fn check_im_a_teapot(
     pkg: &Package,
-    path: &Path,
     pkg_lints: &TomlToolLints,
     error_count: &mut usize,
+    message_format: MessageFormat,
     gctx: &GlobalContext,
 ) -> CargoResult<()> {
     let manifest = pkg.manifest();
-    let (lint_level, reason) =
-        IM_A_TEAPOT.level(pkg_lints, manifest.edition(), manifest.unstable_features());
+    let (lint_level, reason) = IM_A_TEAPOT.level(
+        pkg_lints,
+        gctx.lints_config()?,
+        manifest.edition(),
+        manifest.unstable_features(),
+    );
 
     if lint_level == LintLevel::Allow {
         return Ok(());
@@ -451,29 +1254,403 @@ pub fn check_im_a_teapot(
         .package()
         .is_some_and(|p| p.im_a_teapot.is_some())
     {
-        if lint_level == LintLevel::Forbid || lint_level == LintLevel::Deny {
-            *error_count += 1;
+        let key_span = get_span(manifest.document(), &["package", "im-a-teapot"], false).unwrap();
+        let value_span = get_span(manifest.document(), &["package", "im-a-teapot"], true).unwrap();
+        LintDiagnostic::new(
+            &IM_A_TEAPOT,
+            lint_level,
+            pkg,
+            IM_A_TEAPOT.desc.to_owned(),
+            key_span.start..value_span.end,
+        )
+        .source_note(&reason, &mut false)
+        .emit(error_count, message_format, gctx)?;
+    }
+    Ok(())
+}
+
+const INCOMPATIBLE_DEPENDENCY_RUST_VERSION: Lint = Lint {
+    name: "incompatible_dependency_rust_version",
+    desc: "dependency requires a newer Rust than the package's `rust-version`",
+    groups: &[],
+    default_level: LintLevel::Warn,
+    edition_lint_opts: None,
+    feature_gate: None,
+    docs: Some(
+        r#"
+### What it does
+Checks for dependencies locked to a version whose `rust-version` is newer
+than the `rust-version` of the package.
+
+### Why it is bad
+The package claims to build with a Rust version it doesn't build with, as
+its dependencies need a newer one. The dependency can often be locked to an
+older version that is compatible instead, which the help of the lint
+suggests when one exists in the index.
+
+### Example
+```toml
+[package]
+name = "foo"
+rust-version = "1.70"
+
+[dependencies]
+bar = "1.0.0"
+```
+
+Where `Cargo.lock` has `bar` 1.2.0, which has a `rust-version` of 1.80,
+`bar` should be locked to a version compatible with Rust 1.70, like with
+`cargo update bar --precise 1.1.0`, or the `rust-version` of `foo` raised.
+"#,
+    ),
+};
+
+/// Whether [`INCOMPATIBLE_DEPENDENCY_RUST_VERSION`] applies to `pkg`, so that
+/// the workspace is only resolved for it when it does.
+pub fn needs_incompatible_dependency_rust_version(
+    pkg: &Package,
+    gctx: &GlobalContext,
+) -> CargoResult<bool> {
+    Ok(pkg.rust_version().is_some()
+        && package_lint_level(&INCOMPATIBLE_DEPENDENCY_RUST_VERSION, pkg, gctx)?.0
+            != LintLevel::Allow)
+}
+
+pub fn check_incompatible_dependency_rust_version(
+    pkg: &Package,
+    resolve: &Resolve,
+    registry: &mut dyn Registry,
+    error_count: &mut usize,
+    message_format: MessageFormat,
+    gctx: &GlobalContext,
+) -> CargoResult<()> {
+    let level = package_lint_level(&INCOMPATIBLE_DEPENDENCY_RUST_VERSION, pkg, gctx)?;
+    if level.0 == LintLevel::Allow {
+        return Ok(());
+    }
+    let Some(rust_version) = pkg.rust_version() else {
+        return Ok(());
+    };
+
+    let manifest = pkg.manifest();
+    let mut emitted_source = false;
+    for dep in pkg.dependencies() {
+        // The dependencies in the resolve may be locked, so the requirement
+        // is taken from the manifest and only the locked package from there.
+        let Some(dep_id) = resolve
+            .deps(pkg.package_id())
+            .find(|(_, deps)| {
+                deps.iter().any(|d| {
+                    d.name_in_toml() == dep.name_in_toml()
+                        && d.kind() == dep.kind()
+                        && d.platform() == dep.platform()
+                })
+            })
+            .map(|(dep_id, _)| dep_id)
+        else {
+            continue;
+        };
+        let Some(dep_rust_version) = resolve.summary(dep_id).rust_version() else {
+            continue;
+        };
+        if dep_rust_version.is_compatible_with(rust_version.as_partial()) {
+            continue;
         }
-        let level = lint_level.to_diagnostic_level();
-        let manifest_path = rel_cwd_manifest_path(path, gctx);
-        let emitted_reason = format!(
-            "`cargo::{}` is set to `{lint_level}` {reason}",
-            IM_A_TEAPOT.name
+
+        let mut dep_path = Vec::new();
+        let platform = dep.platform().map(|p| p.to_string());
+        if let Some(platform) = &platform {
+            dep_path.extend(["target", platform.as_str()]);
+        }
+        dep_path.extend([dep.kind().kind_table(), dep.name_in_toml().as_str()]);
+        let (Some(key_span), Some(value_span)) = (
+            get_span(manifest.document(), &dep_path, false),
+            get_span(manifest.document(), &dep_path, true),
+        ) else {
+            continue;
+        };
+
+        let title = format!(
+            "{}: `{}`",
+            INCOMPATIBLE_DEPENDENCY_RUST_VERSION.desc,
+            dep.name_in_toml()
+        );
+        let mut help = format!(
+            "`{}` v{} requires Rust {dep_rust_version}, while `rust-version` is {rust_version}",
+            dep_id.name(),
+            dep_id.version(),
         );
+        match highest_compatible_version(registry, dep_id, rust_version)? {
+            Some(version) if dep.version_req().matches(&version) => help.push_str(&format!(
+                "; lock the highest compatible version with \
+                 `cargo update {}@{} --precise {version}`",
+                dep_id.name(),
+                dep_id.version(),
+            )),
+            Some(version) => help.push_str(&format!(
+                "; the highest compatible version is v{version}, which needs the \
+                 version requirement of `{}` to change",
+                dep.name_in_toml()
+            )),
+            None => {}
+        }
+        emit_manifest_lint(
+            &INCOMPATIBLE_DEPENDENCY_RUST_VERSION,
+            &level,
+            pkg,
+            key_span.start..value_span.end,
+            &title,
+            Some(&help),
+            &mut emitted_source,
+            error_count,
+            message_format,
+            gctx,
+        )?;
+    }
+    Ok(())
+}
 
-        let key_span = get_span(manifest.document(), &["package", "im-a-teapot"], false).unwrap();
-        let value_span = get_span(manifest.document(), &["package", "im-a-teapot"], true).unwrap();
-        let message = level
-            .title(IM_A_TEAPOT.desc)
-            .snippet(
-                Snippet::source(manifest.contents())
-                    .origin(&manifest_path)
-                    .annotation(level.span(key_span.start..value_span.end))
-                    .fold(true),
+/// The highest version of the package `dep_id` in its registry that is
+/// compatible with `rust_version`, leaving out yanked versions.
+fn highest_compatible_version(
+    registry: &mut dyn Registry,
+    dep_id: PackageId,
+    rust_version: &RustVersion,
+) -> CargoResult<Option<semver::Version>> {
+    if !dep_id.source_id().is_registry() {
+        return Ok(None);
+    }
+    let query = Dependency::parse(dep_id.name(), None, dep_id.source_id())?;
+    let possibilities = loop {
+        match registry.query_vec(&query, QueryKind::Exact) {
+            Poll::Ready(res) => break res?,
+            Poll::Pending => registry.block_until_ready()?,
+        }
+    };
+    Ok(possibilities
+        .iter()
+        .filter(|s| matches!(s, IndexSummary::Candidate(_)))
+        .map(|s| s.as_summary())
+        .filter(|s| {
+            s.rust_version()
+                .map_or(true, |rv| rv.is_compatible_with(rust_version.as_partial()))
+        })
+        .map(|s| s.version())
+        .filter(|v| v.pre.is_empty())
+        .max()
+        .cloned())
+}
+
+const INEFFECTIVE_WEAK_DEPENDENCY_FEATURE: Lint = Lint {
+    name: "ineffective_weak_dependency_feature",
+    desc: "weak dependency feature of a dependency the feature always enables",
+    groups: &[],
+    default_level: LintLevel::Warn,
+    edition_lint_opts: None,
+    feature_gate: None,
+    docs: Some(
+        r#"
+### What it does
+Checks for `dep?/feature` values in features that also enable `dep` itself,
+with `dep:dep`, `dep/feature`, or the implicit feature of `dep`.
+
+### Why it is bad
+The `?` only enables the feature of the dependency if something else enables
+the dependency, but here the feature always enables it, so the `?` has no
+effect. This usually means that either the `?` or the other value is a
+mistake.
+
+### Example
+```toml
+[features]
+json = ["dep:serde_json", "serde_json?/std"]
+```
+
+Should be written as:
+
+```toml
+[features]
+json = ["dep:serde_json", "serde_json/std"]
+```
+"#,
+    ),
+};
+
+pub fn check_ineffective_weak_dependency_feature(
+    pkg: &Package,
+    pkg_lints: &TomlToolLints,
+    error_count: &mut usize,
+    message_format: MessageFormat,
+    gctx: &GlobalContext,
+) -> CargoResult<()> {
+    let manifest = pkg.manifest();
+    let level = INEFFECTIVE_WEAK_DEPENDENCY_FEATURE.level(
+        pkg_lints,
+        gctx.lints_config()?,
+        manifest.edition(),
+        manifest.unstable_features(),
+    );
+    if level.0 == LintLevel::Allow {
+        return Ok(());
+    }
+
+    let features = pkg.summary().features();
+    let mut emitted_source = false;
+    for (feature, values) in explicit_features(pkg) {
+        // The dependencies the feature enables unconditionally.
+        let enabled = values
+            .iter()
+            .filter_map(|value| match value {
+                FeatureValue::Dep { dep_name } => Some(*dep_name),
+                FeatureValue::DepFeature {
+                    dep_name,
+                    weak: false,
+                    ..
+                } => Some(*dep_name),
+                // The implicit feature of an optional dependency.
+                FeatureValue::Feature(name) => features
+                    .get(name)
+                    .filter(|values| values.as_slice() == [FeatureValue::Dep { dep_name: *name }])
+                    .map(|_| *name),
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+        for value in values {
+            let FeatureValue::DepFeature {
+                dep_name,
+                dep_feature,
+                weak: true,
+            } = value
+            else {
+                continue;
+            };
+            if !enabled.contains(dep_name) {
+                continue;
+            }
+            let value = value.to_string();
+            let Some(span) = get_span(manifest.document(), &["features", &feature, &value], true)
+            else {
+                continue;
+            };
+            let title = format!("{}: `{value}`", INEFFECTIVE_WEAK_DEPENDENCY_FEATURE.desc);
+            let help = format!(
+                "feature `{feature}` always enables `{dep_name}`, so use `{dep_name}/{dep_feature}`"
+            );
+            LintDiagnostic::new(
+                &INEFFECTIVE_WEAK_DEPENDENCY_FEATURE,
+                level.0,
+                pkg,
+                title,
+                span.clone(),
             )
-            .footer(Level::Note.title(&emitted_reason));
+            .source_note(&level.1, &mut emitted_source)
+            .help_with_suggestion(
+                help,
+                span,
+                format!("\"{dep_name}/{dep_feature}\""),
+                "MachineApplicable",
+            )
+            .emit(error_count, message_format, gctx)?;
+        }
+    }
+    Ok(())
+}
 
-        gctx.shell().print_message(message)?;
+const SHADOWED_OPTIONAL_DEPENDENCY: Lint = Lint {
+    name: "shadowed_optional_dependency",
+    desc: "feature has the name of an optional dependency it doesn't enable",
+    groups: &[],
+    default_level: LintLevel::Warn,
+    edition_lint_opts: None,
+    feature_gate: None,
+    docs: Some(
+        r#"
+### What it does
+Checks for features with the same name as an optional dependency, which
+don't enable that dependency.
+
+### Why it is bad
+The feature takes the place of the implicit feature of the optional
+dependency, so users enabling the feature to get the dependency don't get
+it, and `dep/feature` values elsewhere enable the feature as well as the
+dependency.
+
+### Example
+```toml
+[dependencies]
+serde = { version = "1.0.200", optional = true }
+
+[features]
+serde = []
+derive = ["dep:serde", "serde/derive"]
+```
+
+Should be written as:
+
+```toml
+[dependencies]
+serde = { version = "1.0.200", optional = true }
+
+[features]
+serde = ["dep:serde"]
+derive = ["serde", "serde/derive"]
+```
+"#,
+    ),
+};
+
+pub fn check_shadowed_optional_dependency(
+    pkg: &Package,
+    pkg_lints: &TomlToolLints,
+    error_count: &mut usize,
+    message_format: MessageFormat,
+    gctx: &GlobalContext,
+) -> CargoResult<()> {
+    let manifest = pkg.manifest();
+    let level = SHADOWED_OPTIONAL_DEPENDENCY.level(
+        pkg_lints,
+        gctx.lints_config()?,
+        manifest.edition(),
+        manifest.unstable_features(),
+    );
+    if level.0 == LintLevel::Allow {
+        return Ok(());
+    }
+
+    let mut emitted_source = false;
+    for (feature, values) in explicit_features(pkg) {
+        let is_optional_dep = pkg
+            .dependencies()
+            .iter()
+            .any(|dep| dep.name_in_toml() == feature && dep.is_optional());
+        let enables_dep = values.iter().any(|value| match value {
+            FeatureValue::Dep { dep_name } => *dep_name == feature,
+            FeatureValue::DepFeature {
+                dep_name,
+                weak: false,
+                ..
+            } => *dep_name == feature,
+            _ => false,
+        });
+        if !is_optional_dep || enables_dep {
+            continue;
+        }
+        let span = get_span(manifest.document(), &["features", &feature], false).unwrap();
+        let title = format!("{}: `{feature}`", SHADOWED_OPTIONAL_DEPENDENCY.desc);
+        let help =
+            format!("add `\"dep:{feature}\"` to the feature, or give the feature another name");
+        emit_manifest_lint(
+            &SHADOWED_OPTIONAL_DEPENDENCY,
+            &level,
+            pkg,
+            span,
+            &title,
+            Some(&help),
+            &mut emitted_source,
+            error_count,
+            message_format,
+            gctx,
+        )?;
     }
     Ok(())
 }
@@ -507,27 +1684,28 @@ this-lint-does-not-exist = "warn"
 
 fn output_unknown_lints(
     unknown_lints: Vec<&String>,
-    manifest: &Manifest,
-    manifest_path: &str,
+    pkg: &Package,
     pkg_lints: &TomlToolLints,
     ws_contents: &str,
     ws_document: &ImDocument<String>,
-    ws_path: &str,
+    ws_path: &Path,
     error_count: &mut usize,
+    message_format: MessageFormat,
     gctx: &GlobalContext,
 ) -> CargoResult<()> {
-    let (lint_level, reason) =
-        UNKNOWN_LINTS.level(pkg_lints, manifest.edition(), manifest.unstable_features());
+    let manifest = pkg.manifest();
+    let (lint_level, reason) = UNKNOWN_LINTS.level(
+        pkg_lints,
+        gctx.lints_config()?,
+        manifest.edition(),
+        manifest.unstable_features(),
+    );
     if lint_level == LintLevel::Allow {
         return Ok(());
     }
 
-    let level = lint_level.to_diagnostic_level();
-    let mut emitted_source = None;
+    let mut emitted_source = false;
     for lint_name in unknown_lints {
-        if lint_level == LintLevel::Forbid || lint_level == LintLevel::Deny {
-            *error_count += 1;
-        }
         let title = format!("{}: `{lint_name}`", UNKNOWN_LINTS.desc);
         let second_title = format!("`cargo::{}` was inherited", lint_name);
         let underscore_lint_name = lint_name.replace("-", "_");
@@ -541,14 +1719,20 @@ fn output_unknown_lints(
         let help =
             matching.map(|(name, kind)| format!("there is a {kind} with a similar name: `{name}`"));
 
-        let mut message = if let Some(span) =
+        let mut diagnostic = if let Some(span) =
             get_span(manifest.document(), &["lints", "cargo", lint_name], false)
         {
-            level.title(&title).snippet(
-                Snippet::source(manifest.contents())
-                    .origin(&manifest_path)
-                    .annotation(Level::Error.span(span))
-                    .fold(true),
+            LintDiagnostic::with_span(
+                &UNKNOWN_LINTS,
+                lint_level,
+                pkg,
+                title,
+                LintSpan {
+                    path: pkg.manifest_path(),
+                    contents: manifest.contents(),
+                    range: span,
+                    level: Level::Error,
+                },
             )
         } else {
             let lint_span = get_span(
@@ -560,48 +1744,337 @@ fn output_unknown_lints(
                 "could not find `cargo::{lint_name}` in `[lints]`, or `[workspace.lints]` "
             ));
 
-            let inherited_note = if let (Some(inherit_span_key), Some(inherit_span_value)) = (
+            let inherit_span = if let (Some(inherit_span_key), Some(inherit_span_value)) = (
                 get_span(manifest.document(), &["lints", "workspace"], false),
                 get_span(manifest.document(), &["lints", "workspace"], true),
             ) {
-                Level::Note.title(&second_title).snippet(
-                    Snippet::source(manifest.contents())
-                        .origin(&manifest_path)
-                        .annotation(
-                            Level::Note.span(inherit_span_key.start..inherit_span_value.end),
-                        )
-                        .fold(true),
-                )
+                Some(LintSpan {
+                    path: pkg.manifest_path(),
+                    contents: manifest.contents(),
+                    range: inherit_span_key.start..inherit_span_value.end,
+                    level: Level::Note,
+                })
             } else {
-                Level::Note.title(&second_title)
+                None
             };
 
-            level
-                .title(&title)
-                .snippet(
-                    Snippet::source(ws_contents)
-                        .origin(&ws_path)
-                        .annotation(Level::Error.span(lint_span))
-                        .fold(true),
+            LintDiagnostic::with_span(
+                &UNKNOWN_LINTS,
+                lint_level,
+                pkg,
+                title,
+                LintSpan {
+                    path: ws_path,
+                    contents: ws_contents,
+                    range: lint_span,
+                    level: Level::Error,
+                },
+            )
+            .child(Level::Note, second_title, inherit_span)
+        }
+        .source_note(&reason, &mut emitted_source);
+
+        if let Some(help) = help {
+            diagnostic = diagnostic.help(help);
+        }
+
+        diagnostic.emit(error_count, message_format, gctx)?;
+    }
+
+    Ok(())
+}
+
+const UNUSED_DEPENDENCIES: Lint = Lint {
+    name: "unused_dependencies",
+    desc: "unused dependency",
+    groups: &[],
+    default_level: LintLevel::Allow,
+    edition_lint_opts: None,
+    feature_gate: None,
+    docs: Some(
+        r#"
+### What it does
+Checks for dependencies that none of the targets of a package use, as
+reported by rustc's `unused_crate_dependencies` lint for the targets that
+were built.
+
+A dependency is only reported when every target built with it leaves it
+unused, so build a package with `--all-targets` to avoid reporting a
+dependency that only a test or an example uses.
+
+### Why it is bad
+Unused dependencies make builds slower and add to the code that has to be
+audited and updated, for no benefit.
+
+`cargo fix` removes the dependencies like `cargo remove` does, except for
+those it can't tell are unused from the targets it built: dev-dependencies,
+which doctests may use, platform-specific and optional dependencies, and the
+dependencies of a package that wasn't built with all of its features.
+
+### Example
+```toml
+[dependencies]
+regex = "1.10.0"
+```
+
+Where nothing uses `regex`, should be removed.
+"#,
+    ),
+};
+
+/// The level of [`UNUSED_DEPENDENCIES`] for `pkg`, so that rustc is only asked
+/// to report unused dependencies for the packages that enable it.
+pub fn unused_dependencies_level(pkg: &Package, gctx: &GlobalContext) -> CargoResult<LintLevel> {
+    Ok(package_lint_level(&UNUSED_DEPENDENCIES, pkg, gctx)?.0)
+}
+
+/// The keys of the tables in the manifest of `pkg` that declare `dep`.
+pub fn unused_dependency_tables(pkg: &Package, dep: &UnusedDependency) -> Vec<Vec<String>> {
+    let document = pkg.manifest().document();
+    dependency_tables(document)
+        .into_iter()
+        .filter(|table| {
+            let kind = table.last().unwrap().as_str();
+            if dep.build {
+                kind == "build-dependencies"
+            } else {
+                kind != "build-dependencies"
+            }
+        })
+        .filter(|table| {
+            let mut item = document.as_item();
+            for key in table {
+                match item.get(key) {
+                    Some(next) => item = next,
+                    None => return false,
+                }
+            }
+            item.get(dep.name.as_str()).is_some()
+        })
+        .collect()
+}
+
+/// Whether `cargo fix` may remove `dep` from `table`.
+///
+/// The targets that were built may not be all that use a dependency: rustc
+/// doesn't report on doctests, nor on code behind a `cfg` or a feature that
+/// wasn't built, so only remove those that every build of the package has.
+pub fn is_fixable_unused_dependency(
+    pkg: &Package,
+    dep: &UnusedDependency,
+    table: &[String],
+) -> bool {
+    let [kind] = table else {
+        return false;
+    };
+    let optional = pkg
+        .manifest()
+        .document()
+        .get(kind)
+        .and_then(|deps| deps.get(dep.name.as_str()))
+        .and_then(|dep| dep.get("optional"))
+        .and_then(|optional| optional.as_bool())
+        .unwrap_or(false);
+    dep.all_features && kind != "dev-dependencies" && !optional
+}
+
+pub fn check_unused_dependencies(
+    ws: &Workspace<'_>,
+    pkg: &Package,
+    unused: &[UnusedDependency],
+    error_count: &mut usize,
+    message_format: MessageFormat,
+    gctx: &GlobalContext,
+) -> CargoResult<()> {
+    let manifest = pkg.manifest();
+    let (lint_level, reason) = package_lint_level(&UNUSED_DEPENDENCIES, pkg, gctx)?;
+
+    if lint_level == LintLevel::Allow {
+        return Ok(());
+    }
+
+    let mut emitted_source = false;
+    for dep in unused {
+        for table in unused_dependency_tables(pkg, dep) {
+            let title = format!("{}: `{}`", UNUSED_DEPENDENCIES.desc, dep.name);
+            let mut dep_path = table.iter().map(String::as_str).collect::<Vec<_>>();
+            dep_path.push(&dep.name);
+            let key_span = get_span(manifest.document(), &dep_path, false).unwrap();
+            let value_span = get_span(manifest.document(), &dep_path, true).unwrap();
+
+            let mut remove = String::from("cargo remove");
+            if ws.members().nth(1).is_some() {
+                remove.push_str(&format!(" --package {}", pkg.name()));
+            }
+            if let [target, platform, _] = table.as_slice() {
+                if target == "target" {
+                    remove.push_str(&format!(" --target '{platform}'"));
+                }
+            }
+            match table.last().unwrap().as_str() {
+                "dev-dependencies" => remove.push_str(" --dev"),
+                "build-dependencies" => remove.push_str(" --build"),
+                _ => {}
+            }
+            let help = if is_fixable_unused_dependency(pkg, dep, &table) {
+                format!(
+                    "remove it with `{remove} {}`, or run `cargo fix` to do it",
+                    dep.name
                 )
-                .footer(inherited_note)
-        };
+            } else {
+                format!("remove it with `{remove} {}`", dep.name)
+            };
 
-        if emitted_source.is_none() {
-            emitted_source = Some(format!(
-                "`cargo::{}` is set to `{lint_level}` {reason}",
-                UNKNOWN_LINTS.name
-            ));
-            message = message.footer(Level::Note.title(emitted_source.as_ref().unwrap()));
+            let span = key_span.start..value_span.end;
+            let diagnostic =
+                LintDiagnostic::new(&UNUSED_DEPENDENCIES, lint_level, pkg, title, span.clone())
+                    .source_note(&reason, &mut emitted_source);
+            // Only suggest removing dependencies on a single line, leaving
+            // the dependency tables alone.
+            let diagnostic = match line_of(manifest.contents(), span) {
+                Some(line) => {
+                    diagnostic.help_with_suggestion(help, line, String::new(), "MaybeIncorrect")
+                }
+                None => diagnostic.help(help),
+            };
+            diagnostic.emit(error_count, message_format, gctx)?;
+        }
+    }
+    Ok(())
+}
+
+const UNUSED_FEATURES: Lint = Lint {
+    name: "unused_features",
+    desc: "feature is never enabled in the workspace",
+    groups: &[],
+    default_level: LintLevel::Allow,
+    edition_lint_opts: None,
+    feature_gate: None,
+    docs: Some(
+        r#"
+### What it does
+Checks for features that nothing in the workspace enables: neither the
+`default` feature, nor the workspace members depending on the package, nor
+the features enabled by them.
+
+### Why it is bad
+Code behind a feature that is never enabled is never built or tested, and
+tends to rot. This doesn't apply to features of packages meant to be used
+from outside of the workspace, or that are only enabled on the command line,
+like with `--features`.
+
+### Example
+```toml
+[features]
+default = ["std"]
+std = []
+legacy = []
+```
+
+Where no workspace member enables `legacy`, it should be removed.
+"#,
+    ),
+};
+
+/// The features of `pkg` that are enabled by default, by workspace members
+/// depending on it, or by other features enabled that way.
+fn features_enabled_in_workspace(ws: &Workspace<'_>, pkg: &Package) -> HashSet<InternedString> {
+    let mut roots = vec![InternedString::new("default")];
+    for member in ws.members() {
+        let deps_on_pkg = member
+            .dependencies()
+            .iter()
+            .filter(|dep| {
+                dep.package_name() == pkg.name() && dep.source_id() == pkg.package_id().source_id()
+            })
+            .collect::<Vec<_>>();
+        for dep in &deps_on_pkg {
+            roots.extend(dep.features().iter().copied());
         }
+        // Values of the features of members count even if nothing enables
+        // those features, rather than following features across packages.
+        for values in member.summary().features().values() {
+            for value in values {
+                if let FeatureValue::DepFeature {
+                    dep_name,
+                    dep_feature,
+                    ..
+                } = value
+                {
+                    if deps_on_pkg
+                        .iter()
+                        .any(|dep| dep.name_in_toml() == *dep_name)
+                    {
+                        roots.push(*dep_feature);
+                    }
+                }
+            }
+        }
+    }
 
-        if let Some(help) = help.as_ref() {
-            message = message.footer(Level::Help.title(help));
+    let features = pkg.summary().features();
+    let mut enabled = HashSet::new();
+    while let Some(feature) = roots.pop() {
+        if !enabled.insert(feature) {
+            continue;
         }
+        for value in features.get(&feature).into_iter().flatten() {
+            match value {
+                FeatureValue::Feature(name) => roots.push(*name),
+                // `dep/feature` also enables a feature with the name of the
+                // dependency.
+                FeatureValue::DepFeature {
+                    dep_name,
+                    weak: false,
+                    ..
+                } => roots.push(*dep_name),
+                _ => {}
+            }
+        }
+    }
+    enabled
+}
 
-        gctx.shell().print_message(message)?;
+pub fn check_unused_features(
+    ws: &Workspace<'_>,
+    pkg: &Package,
+    pkg_lints: &TomlToolLints,
+    error_count: &mut usize,
+    message_format: MessageFormat,
+    gctx: &GlobalContext,
+) -> CargoResult<()> {
+    let manifest = pkg.manifest();
+    let level = UNUSED_FEATURES.level(
+        pkg_lints,
+        gctx.lints_config()?,
+        manifest.edition(),
+        manifest.unstable_features(),
+    );
+    if level.0 == LintLevel::Allow {
+        return Ok(());
     }
 
+    let enabled = features_enabled_in_workspace(ws, pkg);
+    let mut emitted_source = false;
+    for (feature, _) in explicit_features(pkg) {
+        if enabled.contains(&feature) {
+            continue;
+        }
+        let span = get_span(manifest.document(), &["features", &feature], false).unwrap();
+        let title = format!("{}: `{feature}`", UNUSED_FEATURES.desc);
+        emit_manifest_lint(
+            &UNUSED_FEATURES,
+            &level,
+            pkg,
+            span,
+            &title,
+            Some("remove the feature, or enable it from `default` or a workspace member"),
+            &mut emitted_source,
+            error_count,
+            message_format,
+            gctx,
+        )?;
+    }
     Ok(())
 }
 
@@ -1,3 +1,4 @@
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 
 use cargo_util_schemas::core::PackageIdSpec;
@@ -33,6 +34,109 @@ impl<'a> Message for FromCompiler<'a> {
     }
 }
 
+/// A diagnostic of a cargo lint, for `--message-format json`.
+#[derive(Serialize)]
+pub struct CargoLint<'a> {
+    pub package_id: PackageIdSpec,
+    pub manifest_path: &'a Path,
+    pub message: Diagnostic,
+}
+
+impl<'a> Message for CargoLint<'a> {
+    fn reason(&self) -> &str {
+        "cargo-lint"
+    }
+}
+
+/// A diagnostic in the JSON format of rustc, so that tools reading
+/// [`FromCompiler`] messages can read it the same way.
+#[derive(Serialize)]
+pub struct Diagnostic {
+    /// Only set on the top-level diagnostic, as `"diagnostic"`.
+    #[serde(rename = "$message_type", skip_serializing_if = "Option::is_none")]
+    pub message_type: Option<&'static str>,
+    pub message: String,
+    pub code: Option<DiagnosticCode>,
+    pub level: &'static str,
+    pub spans: Vec<DiagnosticSpan>,
+    pub children: Vec<Diagnostic>,
+    pub rendered: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct DiagnosticCode {
+    pub code: String,
+    pub explanation: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct DiagnosticSpan {
+    pub file_name: PathBuf,
+    pub byte_start: usize,
+    pub byte_end: usize,
+    pub line_start: usize,
+    pub line_end: usize,
+    pub column_start: usize,
+    pub column_end: usize,
+    pub is_primary: bool,
+    pub text: Vec<DiagnosticSpanLine>,
+    pub label: Option<String>,
+    pub suggested_replacement: Option<String>,
+    pub suggestion_applicability: Option<&'static str>,
+    pub expansion: Option<()>,
+}
+
+impl DiagnosticSpan {
+    /// The span of the bytes `range` of `contents`, the contents of
+    /// `file_name`, with 1-based lines and columns like rustc has.
+    pub fn new(file_name: &Path, contents: &str, range: Range<usize>, is_primary: bool) -> Self {
+        let position = |byte: usize| {
+            let line_start = contents[..byte].rfind('\n').map_or(0, |i| i + 1);
+            let line = contents[..byte].matches('\n').count() + 1;
+            let column = contents[line_start..byte].chars().count() + 1;
+            (line_start, line, column)
+        };
+        let (first_line_start, line_start, column_start) = position(range.start);
+        let (_, line_end, column_end) = position(range.end);
+        let text = contents[first_line_start..]
+            .lines()
+            .take(line_end - line_start + 1)
+            .enumerate()
+            .map(|(i, text)| DiagnosticSpanLine {
+                text: text.to_owned(),
+                highlight_start: if i == 0 { column_start } else { 1 },
+                highlight_end: if i == line_end - line_start {
+                    column_end
+                } else {
+                    text.chars().count() + 1
+                },
+            })
+            .collect();
+        DiagnosticSpan {
+            file_name: file_name.to_owned(),
+            byte_start: range.start,
+            byte_end: range.end,
+            line_start,
+            line_end,
+            column_start,
+            column_end,
+            is_primary,
+            text,
+            label: None,
+            suggested_replacement: None,
+            suggestion_applicability: None,
+            expansion: None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DiagnosticSpanLine {
+    pub text: String,
+    pub highlight_start: usize,
+    pub highlight_end: usize,
+}
+
 #[derive(Serialize)]
 pub struct Artifact<'a> {
     pub package_id: PackageIdSpec,
@@ -11,6 +11,7 @@ pub mod http;
 pub mod proxy;
 pub mod retry;
 pub mod sleep;
+pub mod stats;
 
 /// LOCALHOST constants for both IPv4 and IPv6.
 pub const LOCALHOST: [SocketAddr; 2] = [
@@ -59,6 +59,9 @@ pub struct Retry<'a> {
     ///
     /// 0 means it should never retry.
     max_retries: u64,
+    /// The URL of the request, used to attribute retries in the
+    /// [`NetworkStats`](super::stats::NetworkStats).
+    url: Option<String>,
 }
 
 /// The result of attempting some operation via [`Retry::try`].
@@ -95,9 +98,17 @@ impl<'a> Retry<'a> {
             gctx,
             retries: 0,
             max_retries: gctx.net_config()?.retry.unwrap_or(3) as u64,
+            url: None,
         })
     }
 
+    /// Records retries of this operation as retries of a request to `url`
+    /// in the [`GlobalContext::network_stats`].
+    pub fn with_url(mut self, url: impl Into<String>) -> Retry<'a> {
+        self.url = Some(url.into());
+        self
+    }
+
     /// Calls the given callback, and returns a [`RetryResult`] which
     /// indicates whether or not this needs to be called again at some point
     /// in the future to retry the operation if it failed.
@@ -115,6 +126,9 @@ impl<'a> Retry<'a> {
                 if let Err(e) = self.gctx.shell().warn(msg) {
                     return RetryResult::Err(e);
                 }
+                if let Some(url) = &self.url {
+                    self.gctx.network_stats().record_retry(url);
+                }
                 self.retries += 1;
                 RetryResult::Retry(self.next_sleep_ms())
             }
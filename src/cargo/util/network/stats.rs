@@ -0,0 +1,192 @@
+//! Bookkeeping of network activity, for diagnosing slow downloads.
+//!
+//! Every HTTP transfer performed by the sparse registry protocol
+//! ([`HttpRegistry`]) and by crate downloads ([`Downloads`]) is recorded here,
+//! keyed by the host it was sent to. Spurious failures retried through
+//! [`Retry`] are counted as well, so that it is possible to tell whether the
+//! index, crate downloads, or retries dominate the time spent on the network.
+//!
+//! The collected data is only reported to the user on request, for example
+//! with `cargo fetch --stats`.
+//!
+//! [`HttpRegistry`]: crate::sources::registry::http_remote::HttpRegistry
+//! [`Downloads`]: crate::core::package::Downloads
+//! [`Retry`]: super::retry::Retry
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use serde::Serialize;
+use url::Url;
+
+/// Host name used when a URL could not be parsed or has no host.
+const UNKNOWN_HOST: &str = "<unknown>";
+
+/// The kind of resource a request was made for.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RequestKind {
+    /// A file of a sparse registry index (including `config.json`).
+    Index,
+    /// A `.crate` tarball.
+    Crate,
+}
+
+/// Statistics of all network requests made by this cargo process.
+#[derive(Debug, Default)]
+pub struct NetworkStats {
+    hosts: BTreeMap<String, HostStats>,
+}
+
+/// Statistics of the requests sent to a single host.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct HostStats {
+    /// Number of requests for index files, including retried attempts.
+    pub index_requests: u64,
+    /// Number of requests for `.crate` files, including retried attempts.
+    pub crate_requests: u64,
+    /// Number of bytes of response bodies received.
+    pub bytes: u64,
+    /// Number of index requests answered with `304 Not Modified`, thanks to
+    /// an `ETag` or `Last-Modified` of a locally cached copy.
+    pub cache_hits: u64,
+    /// Number of attempts which failed spuriously and were retried.
+    pub retries: u64,
+    /// Total time spent in the requests, as reported by curl.
+    ///
+    /// Requests run in parallel, so this may exceed the wall-clock time.
+    #[serde(rename = "duration_secs", serialize_with = "serialize_secs")]
+    pub duration: Duration,
+}
+
+impl HostStats {
+    /// Total number of requests of any kind.
+    pub fn requests(&self) -> u64 {
+        self.index_requests + self.crate_requests
+    }
+
+    fn merge(&mut self, other: &HostStats) {
+        self.index_requests += other.index_requests;
+        self.crate_requests += other.crate_requests;
+        self.bytes += other.bytes;
+        self.cache_hits += other.cache_hits;
+        self.retries += other.retries;
+        self.duration += other.duration;
+    }
+}
+
+impl NetworkStats {
+    /// Records a single finished HTTP transfer to `url`.
+    ///
+    /// `not_modified` indicates the server answered with `304 Not Modified`.
+    pub fn record_request(
+        &mut self,
+        url: &str,
+        kind: RequestKind,
+        bytes: u64,
+        duration: Duration,
+        not_modified: bool,
+    ) {
+        let host = self.host_mut(url);
+        match kind {
+            RequestKind::Index => host.index_requests += 1,
+            RequestKind::Crate => host.crate_requests += 1,
+        }
+        host.bytes += bytes;
+        host.duration += duration;
+        if not_modified {
+            host.cache_hits += 1;
+        }
+    }
+
+    /// Records that a request to `url` failed spuriously and will be retried.
+    pub fn record_retry(&mut self, url: &str) {
+        self.host_mut(url).retries += 1;
+    }
+
+    /// Returns `true` if no network activity has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.hosts.is_empty()
+    }
+
+    /// Iterates over the statistics of each host, sorted by host name.
+    pub fn hosts(&self) -> impl Iterator<Item = (&str, &HostStats)> {
+        self.hosts
+            .iter()
+            .map(|(host, stats)| (host.as_str(), stats))
+    }
+
+    /// Sum of the statistics of all hosts.
+    pub fn total(&self) -> HostStats {
+        let mut total = HostStats::default();
+        for stats in self.hosts.values() {
+            total.merge(stats);
+        }
+        total
+    }
+
+    fn host_mut(&mut self, url: &str) -> &mut HostStats {
+        let host = Url::parse(url)
+            .ok()
+            .and_then(|url| {
+                let host = url.host_str()?.to_string();
+                Some(match url.port() {
+                    Some(port) => format!("{host}:{port}"),
+                    None => host,
+                })
+            })
+            .unwrap_or_else(|| UNKNOWN_HOST.to_string());
+        self.hosts.entry(host).or_default()
+    }
+}
+
+fn serialize_secs<S: serde::Serializer>(d: &Duration, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_f64(d.as_secs_f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_by_host() {
+        let mut stats = NetworkStats::default();
+        let ms = Duration::from_millis;
+        stats.record_request(
+            "https://index.crates.io/config.json",
+            RequestKind::Index,
+            100,
+            ms(10),
+            false,
+        );
+        stats.record_request(
+            "https://index.crates.io/3/s/syn",
+            RequestKind::Index,
+            0,
+            ms(5),
+            true,
+        );
+        stats.record_request(
+            "http://127.0.0.1:8080/dl/foo/1.0.0/download",
+            RequestKind::Crate,
+            2000,
+            ms(20),
+            false,
+        );
+        stats.record_retry("http://127.0.0.1:8080/dl/foo/1.0.0/download");
+        stats.record_retry("not a url");
+
+        let hosts: Vec<_> = stats.hosts().map(|(h, _)| h).collect();
+        assert_eq!(hosts, ["127.0.0.1:8080", "<unknown>", "index.crates.io"]);
+
+        let (_, index) = stats.hosts().last().unwrap();
+        assert_eq!(index.index_requests, 2);
+        assert_eq!(index.cache_hits, 1);
+        assert_eq!(index.bytes, 100);
+
+        let total = stats.total();
+        assert_eq!(total.requests(), 3);
+        assert_eq!(total.retries, 2);
+        assert_eq!(total.bytes, 2100);
+        assert_eq!(total.duration, ms(35));
+    }
+}
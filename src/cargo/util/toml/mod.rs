@@ -29,10 +29,11 @@ use crate::core::{Dependency, Manifest, Package, PackageId, Summary, Target};
 use crate::core::{Edition, EitherManifest, Feature, Features, VirtualManifest, Workspace};
 use crate::core::{GitReference, PackageIdSpec, SourceId, WorkspaceConfig, WorkspaceRootConfig};
 use crate::sources::{CRATES_IO_INDEX, CRATES_IO_REGISTRY};
+use crate::util::context::{ConfigRelativePath, LintsConfig};
 use crate::util::errors::{CargoResult, ManifestError};
 use crate::util::interning::InternedString;
 use crate::util::lints::{get_span, rel_cwd_manifest_path};
-use crate::util::{self, context::ConfigRelativePath, GlobalContext, IntoUrl, OptVersionReq};
+use crate::util::{self, GlobalContext, IntoUrl, OptVersionReq};
 
 mod embedded;
 mod targets;
@@ -1412,13 +1413,17 @@ pub fn to_real_manifest(
         gctx,
         warnings,
     )?;
-    let default = manifest::TomlLints::default();
-    let rustflags = lints_to_rustflags(
-        normalized_toml
-            .normalized_lints()
-            .expect("previously normalized")
-            .unwrap_or(&default),
-    )?;
+    let mut lints = normalized_toml
+        .normalized_lints()
+        .expect("previously normalized")
+        .cloned()
+        .unwrap_or_default();
+    // Packages from registries and git have their lints capped, so only
+    // local packages get the lints of the config.
+    if source_id.is_path() {
+        merge_config_lints(&mut lints, gctx.lints_config()?);
+    }
+    let rustflags = lints_to_rustflags(&lints)?;
 
     let metadata = ManifestMetadata {
         description: normalized_package
@@ -1994,13 +1999,14 @@ fn detailed_dep_to_dependency<P: ResolveToPath + Clone>(
 
     if orig.git.is_none() {
         let git_only_keys = [
-            (&orig.branch, "branch"),
-            (&orig.tag, "tag"),
-            (&orig.rev, "rev"),
+            (orig.branch.is_some(), "branch"),
+            (orig.tag.is_some(), "tag"),
+            (orig.rev.is_some(), "rev"),
+            (orig.verify_signature.is_some(), "verify-signature"),
         ];
 
-        for &(key, key_name) in &git_only_keys {
-            if key.is_some() {
+        for &(is_set, key_name) in &git_only_keys {
+            if is_set {
                 bail!(
                     "key `{}` is ignored for dependency ({}).",
                     key_name,
@@ -2073,6 +2079,22 @@ fn detailed_dep_to_dependency<P: ResolveToPath + Clone>(
         dep.set_public(p);
     }
 
+    if let Some(verify_signature) = orig.verify_signature {
+        if manifest_ctx
+            .gctx
+            .cli_unstable()
+            .git
+            .map_or(false, |features| features.verify_signature)
+        {
+            dep.set_verify_signature(verify_signature);
+        } else {
+            manifest_ctx.warnings.push(format!(
+                "ignoring `verify-signature` on dependency {name_in_toml}, \
+                 pass `-Zgit=verify-signature` to enable support for it"
+            ));
+        }
+    }
+
     if let (Some(artifact), is_lib, target) = (
         orig.artifact.as_ref(),
         orig.lib.unwrap_or(false),
@@ -2522,6 +2544,17 @@ switch to nightly channel you can pass
     warnings.push(message);
 }
 
+/// Adds the lints of the `[lints]` config to `lints`, replacing the lints
+/// set in both.
+fn merge_config_lints(lints: &mut manifest::TomlLints, config: &LintsConfig) {
+    for (tool, config_lints) in config {
+        let tool_lints = lints.entry(tool.clone()).or_default();
+        for (name, lint) in config_lints {
+            tool_lints.insert(name.clone(), lint.val.clone());
+        }
+    }
+}
+
 fn lints_to_rustflags(lints: &manifest::TomlLints) -> CargoResult<Vec<String>> {
     let mut rustflags = lints
         .iter()
@@ -2965,6 +2998,7 @@ fn prepare_toml_for_publish(
                 d.branch.take();
                 d.tag.take();
                 d.rev.take();
+                d.verify_signature.take();
                 // registry specifications are elaborated to the index URL
                 if let Some(registry) = d.registry.take() {
                     d.registry_index = Some(gctx.get_registry_index(&registry)?.to_string());
@@ -63,18 +63,20 @@ _cargo()
 	local opt__fix="$opt_common $opt_pkg_spec $opt_feat $opt_mani $opt_parallel $opt_targets $opt_lock --release --target --message-format --broken-code --edition --edition-idioms --allow-no-vcs --allow-dirty --allow-staged --profile --target-dir --ignore-rust-version"
 	local opt__generate_lockfile="$opt_common $opt_mani $opt_lock"
 	local opt__help="$opt_help"
-	local opt__info="$opt_common $opt_lock --registry --index"
+	local opt__info="$opt_common $opt_lock --registry --index --versions"
 	local opt__init="$opt_common $opt_lock --bin --lib --name --vcs --edition --registry"
 	local opt__install="$opt_common $opt_feat $opt_parallel $opt_lock $opt_force --bin --bins --branch --debug --example --examples --git --list --path --rev --root --tag --version --registry --target --profile --no-track --ignore-rust-version"
 	local opt__locate_project="$opt_common $opt_mani $opt_lock --message-format --workspace"
-	local opt__login="$opt_common $opt_lock --registry"
+	local opt__login="$opt_common $opt_lock --registry --status --rotate-key"
 	local opt__metadata="$opt_common $opt_feat $opt_mani $opt_lock --format-version=1 --no-deps --filter-platform"
 	local opt__new="$opt_common $opt_lock --vcs --bin --lib --name --edition --registry"
-	local opt__owner="$opt_common $opt_lock -a --add -r --remove -l --list --index --token --registry"
+	local opt__owner="$opt_common $opt_lock -a --add --role -r --remove -l --list --index --token --registry"
 	local opt__package="$opt_common $opt_mani $opt_feat $opt_lock $opt_parallel --allow-dirty -l --list --no-verify --no-metadata --index --registry --target --target-dir"
 	local opt__pkgid="$opt_common $opt_mani $opt_lock $opt_pkg"
 	local opt__publish="$opt_common $opt_mani $opt_feat $opt_lock $opt_parallel --allow-dirty --dry-run --token --no-verify --index --registry --target --target-dir"
 	local opt__read_manifest="$opt_help $opt_quiet $opt_verbose $opt_mani $opt_color $opt_lock --no-deps"
+	local opt__registry="$opt_help $opt_verbose $opt_color edit-metadata"
+	local opt__registry__edit_metadata="$opt_common $opt_lock --version --description --documentation --homepage --keyword --category --index --token --registry"
 	local opt__remove="$opt_common $opt_pkg $opt_lock $opt_mani --dry-run --dev --build --target"
 	local opt__rm="$opt__remove"
 	local opt__report="$opt_help $opt_verbose $opt_color future-incompat future-incompatibilities"
@@ -83,7 +85,7 @@ _cargo()
 	local opt__r="$opt__run"
 	local opt__rustc="$opt_common $opt_pkg $opt_feat $opt_mani $opt_lock $opt_parallel $opt_targets -L --crate-type --extern --message-format --profile --target --release --target-dir --ignore-rust-version"
 	local opt__rustdoc="$opt_common $opt_pkg $opt_feat $opt_mani $opt_lock $opt_parallel $opt_targets --message-format --target --release --open --target-dir --profile --ignore-rust-version"
-	local opt__search="$opt_common $opt_lock --limit --index --registry"
+	local opt__search="$opt_common $opt_lock --limit --sort --category --keyword --json --index --registry"
 	local opt__test="$opt_common $opt_pkg_spec $opt_feat $opt_mani $opt_lock $opt_jobs $opt_targets --message-format --doc --target --no-run --release --no-fail-fast --target-dir --profile --ignore-rust-version"
 	local opt__t="$opt__test"
 	local opt__tree="$opt_common $opt_pkg_spec $opt_feat $opt_mani $opt_lock --target -i --invert --prefix --no-dedupe --duplicates -d --charset -f --format -e --edges"
@@ -92,7 +94,7 @@ _cargo()
 	local opt__vendor="$opt_common $opt_mani $opt_lock $opt_sync --no-delete --respect-source-config --versioned-dirs"
 	local opt__verify_project="$opt_common $opt_mani $opt_lock"
 	local opt__version="$opt_common $opt_lock"
-	local opt__yank="$opt_common $opt_lock --version --undo --index --token --registry"
+	local opt__yank="$opt_common $opt_lock --version --undo --reason --advisory --index --token --registry"
 	local opt__libtest="--help --include-ignored --ignored --test --bench --list --logfile --nocapture --test-threads --skip -q --quiet --exact --color --format"
 
 	if [[ $cword -gt $dd_i ]]; then
@@ -150,7 +152,7 @@ _cargo()
 				COMPREPLY=( $( compgen -W "$__cargo_commands_cache" -- "$cur" ) )
 				;;
 			*)
-				if [[ "$cmd" == "report" && "$prev" == future-incompat* ]]; then
+				if [[ "$cmd" == "report" && "$prev" == future-incompat* ]] || [[ "$cmd" == "registry" && "$prev" == edit-metadata ]]; then
 					local opt_var=opt__${cmd//-/_}__${prev//-/_}
 				else
 					local opt_var=opt__${cmd//-/_}
@@ -17,6 +17,8 @@ mod specify_version_within_ws_and_conflict_with_lockfile;
 mod specify_version_within_ws_and_match_with_lockfile;
 mod transitive_dependency_within_ws;
 mod verbose;
+mod versions;
+mod versions_requires_nightly;
 mod with_frozen_outside_ws;
 mod with_frozen_within_ws;
 mod with_locked_outside_ws;
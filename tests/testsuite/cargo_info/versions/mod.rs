@@ -0,0 +1,41 @@
+use cargo_test_support::prelude::*;
+use cargo_test_support::{current_dir, file, Project};
+
+use super::init_registry_without_token;
+
+#[cargo_test]
+fn case() {
+    init_registry_without_token();
+    for (ver, pubtime, rust_version) in [
+        ("0.1.0", Some("2023-01-05T10:00:00Z"), "1.60"),
+        ("0.1.1", Some("2023-02-05T22:00:00-05:00"), "1.65"),
+        ("0.1.2", Some("2023-03-05T10:00:00Z"), "1.65"),
+        ("0.2.0", Some("2024-01-05T10:00:00Z"), "1.70"),
+        ("0.2.1", Some("January 2024"), "1.70"),
+        ("0.3.0", None, "1.80"),
+    ] {
+        let mut package = cargo_test_support::registry::Package::new("my-package", ver);
+        package.rust_version(rust_version).yanked(ver == "0.1.2");
+        if let Some(pubtime) = pubtime {
+            package.pubtime(pubtime);
+        }
+        package.publish();
+    }
+
+    let project = Project::from_template(current_dir!().join("in"));
+    let project_root = project.root();
+    let cwd = &project_root;
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("info")
+        .arg("my-package")
+        .arg("-Zunstable-options")
+        .arg("--versions")
+        .arg("--registry=dummy-registry")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .current_dir(cwd)
+        .assert()
+        .success()
+        .stdout_eq(file!["stdout.term.svg"])
+        .stderr_eq(file!["stderr.term.svg"]);
+}
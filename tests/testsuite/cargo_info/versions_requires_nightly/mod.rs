@@ -0,0 +1,25 @@
+use cargo_test_support::prelude::*;
+use cargo_test_support::{current_dir, file, Project};
+
+use super::init_registry_without_token;
+
+#[cargo_test]
+fn case() {
+    init_registry_without_token();
+    cargo_test_support::registry::Package::new("my-package", "0.1.0").publish();
+
+    let project = Project::from_template(current_dir!().join("in"));
+    let project_root = project.root();
+    let cwd = &project_root;
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("info")
+        .arg("my-package")
+        .arg("--versions")
+        .arg("--registry=dummy-registry")
+        .current_dir(cwd)
+        .assert()
+        .failure()
+        .stdout_eq("")
+        .stderr_eq(file!["stderr.term.svg"]);
+}
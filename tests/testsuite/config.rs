@@ -1990,13 +1990,50 @@ Caused by:
         Some(GitFeatures {
             shallow_index: false,
             shallow_deps: true,
+            sparse_checkout: false,
+            verify_signature: false,
+            ls_remote: false,
+        }),
+    ));
+
+    let gctx = GlobalContextBuilder::new()
+        .env("CARGO_UNSTABLE_GIT", "shallow-deps,sparse-checkout")
+        .build();
+    assert!(do_check(
+        gctx,
+        Some(GitFeatures {
+            shallow_index: false,
+            shallow_deps: true,
+            sparse_checkout: true,
+            verify_signature: false,
+            ls_remote: false,
+        }),
+    ));
+
+    let gctx = GlobalContextBuilder::new()
+        .env("CARGO_UNSTABLE_GIT", "verify-signature")
+        .build();
+    assert!(do_check(
+        gctx,
+        Some(GitFeatures {
+            verify_signature: true,
+            ..GitFeatures::default()
         }),
     ));
 
     let gctx = GlobalContextBuilder::new()
         .env("CARGO_UNSTABLE_GIT", "true")
         .build();
-    assert!(do_check(gctx, Some(GitFeatures::all())));
+    assert!(do_check(
+        gctx,
+        Some(GitFeatures {
+            shallow_index: true,
+            shallow_deps: true,
+            sparse_checkout: false,
+            verify_signature: false,
+            ls_remote: true,
+        }),
+    ));
 
     let gctx = GlobalContextBuilder::new()
         .env("CARGO_UNSTABLE_GIT_SHALLOW_INDEX", "true")
@@ -2034,6 +2071,9 @@ git = 'shallow-index'
         Some(GitFeatures {
             shallow_index: true,
             shallow_deps: false,
+            sparse_checkout: false,
+            verify_signature: false,
+            ls_remote: false,
         }),
     ));
 
@@ -457,6 +457,13 @@ fn no_disallowed_values() {
         gctx.unwrap_err(),
         "registries.crates-io.secret-key cannot be set through --config for security reasons",
     );
+    let gctx = GlobalContextBuilder::new()
+        .config_arg("registries.crates-io.previous-secret-keys=[\"hello\"]")
+        .build_err();
+    assert_error(
+        gctx.unwrap_err(),
+        "registries.crates-io.previous-secret-keys cannot be set through --config for security reasons",
+    );
 }
 
 #[cargo_test]
@@ -185,7 +185,7 @@ fn owner() {
 [UPDATING] `alternative` index
 {"v":1,"registry":{"index-url":"[..]","name":"alternative","headers":[..]},"kind":"get","operation":"read"}
 {"v":1,"registry":{"index-url":"[..]","name":"alternative"},"kind":"get","operation":"owners","name":"foo"}
-[OWNER] completed!
+[OWNER] username added as owners of crate foo
 
 "#]])
         .run();
@@ -586,6 +586,94 @@ You may press ctrl-c [..]
         .run();
 }
 
+#[cargo_test]
+fn refresh_hint() {
+    let server = registry::RegistryBuilder::new()
+        .no_configure_token()
+        .no_configure_registry()
+        .token(cargo_test_support::registry::Token::Plaintext(
+            "sekrit".to_string(),
+        ))
+        .alternative()
+        .http_api()
+        .http_index()
+        .build();
+
+    // Asks for the token to be refreshed right away, then fails to refresh it.
+    let cred_proj = project()
+        .at("refresh_provider")
+        .file("Cargo.toml", &basic_manifest("refresh_provider", "1.0.0"))
+        .file(
+            "src/main.rs",
+            r####"
+                fn main() {
+                    println!("{}", r#"{"v":[1,2]}"#);
+                    let mut buffer = String::new();
+                    std::io::stdin().read_line(&mut buffer).unwrap();
+                    eprint!("{}", buffer);
+                    let marker = std::env::current_exe().unwrap().with_file_name("refreshed");
+                    if marker.exists() {
+                        println!("{}", r#"{"Err":{"kind":"other","message":"registry unavailable"}}"#);
+                    } else {
+                        std::fs::write(&marker, "").unwrap();
+                        println!("{}", r#"{"Ok":{"kind":"get","token":"sekrit","cache":"refresh","expiration":4102444800,"refresh-after":0,"operation_independent":true}}"#);
+                    }
+                } "####,
+        )
+        .build();
+    cred_proj.cargo("build").run();
+    let provider = toml_bin(&cred_proj, "refresh_provider");
+
+    let p = project()
+        .file(
+            ".cargo/config.toml",
+            &format!(
+                r#"
+                    [registries.alternative]
+                    index = "{}"
+                    credential-provider = ["{provider}"]
+                "#,
+                server.index_url(),
+            ),
+        )
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+                description = "foo"
+                license = "MIT"
+                homepage = "https://example.com/"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("publish --registry alternative --no-verify")
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+{"v":2,"registry":{"index-url":"[..]","name":"alternative"},"kind":"get","operation":"read"}
+[PACKAGING] foo v0.1.0 ([ROOT]/foo)
+[PACKAGED] 3 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+[UPLOADING] foo v0.1.0 ([ROOT]/foo)
+{"v":2,"registry":{"index-url":"[..]","name":"alternative"},"kind":"get","operation":"publish","name":"foo","vers":"0.1.0","cksum":"[..]"}
+[WARNING] failed to refresh the token for `alternative`, the cached token will be used until it expires
+
+credential provider `[ROOT]/refresh_provider/target/debug/refresh_provider` failed action `get`
+
+Caused by:
+  registry unavailable
+[UPLOADED] foo v0.1.0 to registry `alternative`
+[NOTE] waiting for `foo v0.1.0` to be available at registry `alternative`.
+You may press ctrl-c to skip waiting; the crate should be available shortly.
+[PUBLISHED] foo v0.1.0 at registry `alternative`
+
+"#]])
+        .run();
+}
+
 #[cargo_test]
 fn basic_provider() {
     let cred_proj = project()
@@ -683,7 +771,7 @@ fn unsupported_version() {
 [ERROR] credential provider `[..]` failed action `login`
 
 Caused by:
-  credential provider supports protocol versions [998, 999], while Cargo supports [1]
+  credential provider supports protocol versions [998, 999], while Cargo supports [1, 2]
 
 "#]])
         .run();
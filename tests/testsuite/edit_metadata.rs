@@ -0,0 +1,184 @@
+//! Tests for the `cargo registry edit-metadata` command.
+
+use std::fs;
+
+use cargo_test_support::compare::assert_e2e;
+use cargo_test_support::prelude::*;
+use cargo_test_support::registry::{self, api_path, RegistryBuilder};
+use cargo_test_support::{cargo_process, project, str};
+
+fn edited_metadata(name: &str, version: &str) -> String {
+    let path = api_path().join(format!("api/v1/crates/{name}/{version}/metadata"));
+    let metadata: serde_json::Value = serde_json::from_slice(&fs::read(path).unwrap()).unwrap();
+    serde_json::to_string_pretty(&metadata).unwrap()
+}
+
+#[cargo_test]
+fn requires_unstable_options() {
+    let registry = registry::init();
+
+    cargo_process("registry edit-metadata foo@0.1.0 --description foo")
+        .replace_crates_io(registry.index_url())
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `cargo registry edit-metadata` command is unstable, and only available on the nightly channel of Cargo, but this is the `stable` channel
+See https://doc.rust-lang.org/book/appendix-07-nightly-rust.html for more information about Rust release channels.
+See https://github.com/rust-lang/cargo/issues/14705 for more information about the `cargo registry edit-metadata` command.
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn explicit_version() {
+    let registry = RegistryBuilder::new().http_api().build();
+
+    cargo_process("registry edit-metadata -Zunstable-options foo@0.1.0")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .arg("--description=The foo crate")
+        .arg("--keyword=foo")
+        .arg("--keyword=bar")
+        .replace_crates_io(registry.index_url())
+        .with_stderr_data(str![[r#"
+[UPDATING] crates.io index
+[EDITING] metadata of foo@0.1.0
+
+"#]])
+        .run();
+    assert_e2e().eq(
+        edited_metadata("foo", "0.1.0"),
+        str![[r#"
+{
+  "description": "The foo crate",
+  "keywords": [
+    "foo",
+    "bar"
+  ]
+}
+"#]]
+        .is_json(),
+    );
+
+    // Later edits leave the metadata that isn't given unchanged.
+    cargo_process("registry edit-metadata -Zunstable-options foo --version 0.1.0")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .arg("--homepage=https://foo.example.com")
+        .arg("--category=algorithms")
+        .replace_crates_io(registry.index_url())
+        .with_stderr_data(str![[r#"
+[UPDATING] crates.io index
+[EDITING] metadata of foo@0.1.0
+
+"#]])
+        .run();
+    assert_e2e().eq(
+        edited_metadata("foo", "0.1.0"),
+        str![[r#"
+{
+  "categories": [
+    "algorithms"
+  ],
+  "description": "The foo crate",
+  "homepage": "https://foo.example.com",
+  "keywords": [
+    "foo",
+    "bar"
+  ]
+}
+"#]]
+        .is_json(),
+    );
+}
+
+#[cargo_test]
+fn current_package() {
+    let registry = RegistryBuilder::new().http_api().build();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+                license = "MIT"
+                description = "foo"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo(
+        "registry edit-metadata -Zunstable-options --documentation https://docs.example.com/foo",
+    )
+    .masquerade_as_nightly_cargo(&["unstable-options"])
+    .replace_crates_io(registry.index_url())
+    .with_stderr_data(str![[r#"
+[UPDATING] crates.io index
+[EDITING] metadata of foo@0.0.1
+
+"#]])
+    .run();
+    assert_e2e().eq(
+        edited_metadata("foo", "0.0.1"),
+        str![[r#"
+{
+  "documentation": "https://docs.example.com/foo"
+}
+"#]]
+        .is_json(),
+    );
+}
+
+#[cargo_test]
+fn missing_version() {
+    let registry = RegistryBuilder::new().http_api().build();
+
+    cargo_process("registry edit-metadata -Zunstable-options foo --description foo")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .replace_crates_io(registry.index_url())
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] a version must be specified to edit the metadata of `foo`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn nothing_to_edit() {
+    let registry = registry::init();
+
+    cargo_process("registry edit-metadata -Zunstable-options foo@0.1.0")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .replace_crates_io(registry.index_url())
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] no metadata to edit, at least one of `--description`, `--documentation`, `--homepage`, `--keyword` or `--category` must be specified
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn with_asymmetric() {
+    let registry = RegistryBuilder::new()
+        .http_api()
+        .token(registry::Token::rfc_key())
+        .build();
+
+    // The http_api server will check that the authorization is correct.
+    // If the authorization was not sent then we would get an unauthorized error.
+    cargo_process(
+        "registry edit-metadata -Zunstable-options foo@0.1.0 --description foo -Zasymmetric-token",
+    )
+    .masquerade_as_nightly_cargo(&["unstable-options", "asymmetric-token"])
+    .replace_crates_io(registry.index_url())
+    .with_stderr_data(str![[r#"
+[UPDATING] crates.io index
+[EDITING] metadata of foo@0.1.0
+
+"#]])
+    .run();
+}
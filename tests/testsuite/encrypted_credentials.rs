@@ -0,0 +1,243 @@
+//! Tests for the `cargo:encrypted-file` credential provider.
+
+use cargo_test_support::paths;
+use cargo_test_support::prelude::*;
+use cargo_test_support::registry::{self, TestRegistry};
+use cargo_test_support::{project, str, Project};
+
+fn setup() -> (Project, TestRegistry) {
+    let server = registry::RegistryBuilder::new()
+        .no_configure_token()
+        .no_configure_registry()
+        .token(registry::Token::Plaintext("sekrit".to_string()))
+        .alternative()
+        .http_api()
+        .http_index()
+        .build();
+
+    let p = project()
+        .file(
+            ".cargo/config.toml",
+            &format!(
+                r#"
+                    [registries.alternative]
+                    index = "{}"
+                    credential-provider = ["cargo:encrypted-file"]
+                "#,
+                server.index_url(),
+            ),
+        )
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+                description = "foo"
+                license = "MIT"
+                homepage = "https://example.com/"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+    (p, server)
+}
+
+#[cargo_test]
+fn login_publish_logout() {
+    let (p, _t) = setup();
+
+    p.cargo("login --registry alternative -Zencrypted-credentials sekrit")
+        .masquerade_as_nightly_cargo(&["encrypted-credentials"])
+        .env("CARGO_CREDENTIAL_PASSPHRASE", "hunter2")
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[LOGIN] token for `alternative` saved in `[ROOT]/home/.cargo/credentials.enc`
+
+"#]])
+        .run();
+
+    let contents = std::fs::read(paths::cargo_home().join("credentials.enc")).unwrap();
+    assert!(!String::from_utf8_lossy(&contents).contains("sekrit"));
+
+    p.cargo("publish --no-verify --registry alternative -Zencrypted-credentials")
+        .masquerade_as_nightly_cargo(&["encrypted-credentials"])
+        .env("CARGO_CREDENTIAL_PASSPHRASE", "hunter2")
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[PACKAGING] foo v0.1.0 ([ROOT]/foo)
+[PACKAGED] 3 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+[UPLOADING] foo v0.1.0 ([ROOT]/foo)
+[UPLOADED] foo v0.1.0 to registry `alternative`
+[NOTE] waiting for `foo v0.1.0` to be available at registry `alternative`.
+You may press ctrl-c to skip waiting; the crate should be available shortly.
+[PUBLISHED] foo v0.1.0 at registry `alternative`
+
+"#]])
+        .run();
+
+    p.cargo("logout --registry alternative -Zencrypted-credentials")
+        .masquerade_as_nightly_cargo(&["encrypted-credentials"])
+        .env("CARGO_CREDENTIAL_PASSPHRASE", "hunter2")
+        .with_stderr_data(str![[r#"
+[LOGOUT] token for `alternative` has been removed from `[ROOT]/home/.cargo/credentials.enc`
+
+"#]])
+        .run();
+
+    p.cargo("publish --no-verify --registry alternative -Zencrypted-credentials")
+        .masquerade_as_nightly_cargo(&["encrypted-credentials"])
+        .env("CARGO_CREDENTIAL_PASSPHRASE", "hunter2")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[ERROR] no token found for `alternative`, please run `cargo login --registry alternative`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn passphrase_env() {
+    let (p, _t) = setup();
+    let config = p.read_file(".cargo/config.toml").replace(
+        r#"["cargo:encrypted-file"]"#,
+        r#"["cargo:encrypted-file", "--passphrase-env", "MY_PASSPHRASE"]"#,
+    );
+    p.change_file(".cargo/config.toml", &config);
+
+    p.cargo("login --registry alternative -Zencrypted-credentials sekrit")
+        .masquerade_as_nightly_cargo(&["encrypted-credentials"])
+        .env("MY_PASSPHRASE", "hunter2")
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[LOGIN] token for `alternative` saved in `[ROOT]/home/.cargo/credentials.enc`
+
+"#]])
+        .run();
+
+    p.cargo("publish --no-verify --registry alternative -Zencrypted-credentials")
+        .masquerade_as_nightly_cargo(&["encrypted-credentials"])
+        .env("CARGO_CREDENTIAL_PASSPHRASE", "hunter2")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[ERROR] credential provider `cargo:encrypted-file --passphrase-env MY_PASSPHRASE` failed action `get`
+
+Caused by:
+  the passphrase for `[ROOT]/home/.cargo/credentials.enc` is not set
+  Set the `MY_PASSPHRASE` environment variable, or pass `--passphrase-fd` to the `cargo:encrypted-file` provider.
+
+"#]])
+        .run();
+}
+
+// Windows doesn't support `--passphrase-fd`.
+#[cfg(unix)]
+#[cargo_test]
+fn passphrase_fd() {
+    let (p, _t) = setup();
+
+    p.cargo("login --registry alternative -Zencrypted-credentials sekrit")
+        .masquerade_as_nightly_cargo(&["encrypted-credentials"])
+        .env("CARGO_CREDENTIAL_PASSPHRASE", "hunter2")
+        .run();
+
+    let config = p.read_file(".cargo/config.toml").replace(
+        r#"["cargo:encrypted-file"]"#,
+        r#"["cargo:encrypted-file", "--passphrase-fd", "0"]"#,
+    );
+    p.change_file(".cargo/config.toml", &config);
+
+    p.cargo("publish --no-verify --registry alternative -Zencrypted-credentials")
+        .masquerade_as_nightly_cargo(&["encrypted-credentials"])
+        .with_stdin("hunter2\n")
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[PACKAGING] foo v0.1.0 ([ROOT]/foo)
+[PACKAGED] 3 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+[UPLOADING] foo v0.1.0 ([ROOT]/foo)
+[UPLOADED] foo v0.1.0 to registry `alternative`
+[NOTE] waiting for `foo v0.1.0` to be available at registry `alternative`.
+You may press ctrl-c to skip waiting; the crate should be available shortly.
+[PUBLISHED] foo v0.1.0 at registry `alternative`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn wrong_passphrase() {
+    let (p, _t) = setup();
+
+    p.cargo("login --registry alternative -Zencrypted-credentials sekrit")
+        .masquerade_as_nightly_cargo(&["encrypted-credentials"])
+        .env("CARGO_CREDENTIAL_PASSPHRASE", "hunter2")
+        .run();
+
+    p.cargo("publish --no-verify --registry alternative -Zencrypted-credentials")
+        .masquerade_as_nightly_cargo(&["encrypted-credentials"])
+        .env("CARGO_CREDENTIAL_PASSPHRASE", "hunter3")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[ERROR] credential provider `cargo:encrypted-file` failed action `get`
+
+Caused by:
+  failed to decrypt `[ROOT]/home/.cargo/credentials.enc`
+
+Caused by:
+  wrong passphrase, or the file is corrupted
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn unsupported_kdf_parameters() {
+    let (p, _t) = setup();
+
+    p.cargo("login --registry alternative -Zencrypted-credentials sekrit")
+        .masquerade_as_nightly_cargo(&["encrypted-credentials"])
+        .env("CARGO_CREDENTIAL_PASSPHRASE", "hunter2")
+        .run();
+
+    // Claim 4 TiB of memory for the key derivation.
+    let path = paths::cargo_home().join("credentials.enc");
+    let mut contents = std::fs::read(&path).unwrap();
+    contents[12..16].copy_from_slice(&u32::MAX.to_le_bytes());
+    std::fs::write(&path, contents).unwrap();
+
+    p.cargo("publish --no-verify --registry alternative -Zencrypted-credentials")
+        .masquerade_as_nightly_cargo(&["encrypted-credentials"])
+        .env("CARGO_CREDENTIAL_PASSPHRASE", "hunter2")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[ERROR] credential provider `cargo:encrypted-file` failed action `get`
+
+Caused by:
+  failed to decrypt `[ROOT]/home/.cargo/credentials.enc`
+
+Caused by:
+  unsupported key derivation parameters (3 iterations, 4294967295 KiB of memory), the file is corrupted
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn requires_nightly() {
+    let (p, _t) = setup();
+
+    p.cargo("login --registry alternative sekrit")
+        .env("CARGO_CREDENTIAL_PASSPHRASE", "hunter2")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[ERROR] cargo:encrypted-file requires -Zencrypted-credentials
+
+"#]])
+        .run();
+}
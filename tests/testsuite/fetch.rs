@@ -1,7 +1,9 @@
 //! Tests for the `cargo fetch` command.
 
+use std::sync::Mutex;
+
 use cargo_test_support::prelude::*;
-use cargo_test_support::registry::Package;
+use cargo_test_support::registry::{Package, RegistryBuilder};
 use cargo_test_support::rustc_host;
 use cargo_test_support::{basic_manifest, cross_compile, project, str};
 
@@ -145,3 +147,125 @@ fn fetch_warning() {
 "#]])
         .run();
 }
+
+#[cargo_test]
+fn fetch_stats_json() {
+    let fail_count = Mutex::new(0);
+    let _registry = RegistryBuilder::new()
+        .http_index()
+        .add_responder("/index/3/b/bar", move |req, server| {
+            let mut fail_count = fail_count.lock().unwrap();
+            if *fail_count < 1 {
+                *fail_count += 1;
+                server.internal_server_error(req)
+            } else {
+                server.index(req)
+            }
+        })
+        .build();
+    Package::new("bar", "0.0.1").publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                bar = "0.0.1"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("fetch -Zunstable-options --stats=json")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .env("__CARGO_TEST_FIXED_RETRY_SLEEP_MS", "1")
+        .with_stdout_data(
+            str![[r#"
+{
+  "hosts": [
+    {
+      "bytes": "{...}",
+      "cache_hits": 0,
+      "crate_requests": 1,
+      "duration_secs": "{...}",
+      "host": "127.0.0.1:[..]",
+      "index_requests": 3,
+      "retries": 1
+    }
+  ],
+  "total": {
+    "bytes": "{...}",
+    "cache_hits": 0,
+    "crate_requests": 1,
+    "duration_secs": "{...}",
+    "index_requests": 3,
+    "retries": 1
+  }
+}
+"#]]
+            .is_json(),
+        )
+        .run();
+
+    // Everything is cached now, and the index is not re-checked.
+    p.cargo("fetch -Zunstable-options --stats")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_data("")
+        .with_stderr_data(str![[r#"
+[NOTE] no network requests were made, everything was cached locally
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn fetch_stats_human() {
+    let _registry = RegistryBuilder::new().http_index().build();
+    Package::new("bar", "0.0.1").publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                bar = "0.0.1"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("fetch -Zunstable-options --stats")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_data(str![[r#"
+host [..] index  cached  crates  retries [..] bytes [..] time
+127.0.0.1:[..]      2       0       1        0 [..]s
+total [..]      2       0       1        0 [..]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn fetch_stats_requires_unstable_options() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("fetch --stats")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--stats` flag is unstable, pass `-Z unstable-options` to enable it
+See https://github.com/rust-lang/cargo/issues/14700 for more information about the `--stats` flag.
+
+"#]])
+        .run();
+}
@@ -4119,3 +4119,109 @@ src/lib.rs
 "#]])
         .run();
 }
+
+#[cargo_test]
+fn update_skips_fetch_when_up_to_date() {
+    update_skips_fetch_when_up_to_date_with(None, &[]);
+}
+
+#[cargo_test(requires_git)]
+fn update_skips_fetch_when_up_to_date_with_cli() {
+    update_skips_fetch_when_up_to_date_with(Some("[net]\ngit-fetch-with-cli = true\n"), &[]);
+}
+
+#[cargo_test]
+fn update_skips_fetch_when_up_to_date_with_gitoxide() {
+    update_skips_fetch_when_up_to_date_with(None, &["-Zgitoxide=fetch"]);
+}
+
+fn update_skips_fetch_when_up_to_date_with(config: Option<&str>, args: &[&str]) {
+    let (bar, repo) = git::new_repo("bar", |project| {
+        project
+            .file("Cargo.toml", &basic_manifest("bar", "0.5.0"))
+            .file("src/lib.rs", "")
+    });
+    git::tag(&repo, "v0.5.0");
+
+    let mut p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "foo"
+                    version = "0.5.0"
+                    edition = "2015"
+
+                    [dependencies]
+                    bar = {{ git = '{url}', branch = "master" }}
+                    bar2 = {{ git = '{url}', tag = "v0.5.0", package = "bar" }}
+                "#,
+                url = bar.url()
+            ),
+        )
+        .file("src/lib.rs", "");
+    if let Some(config) = config {
+        p = p.file(".cargo/config.toml", config);
+    }
+    let p = p.build();
+    let cargo = |cmd: &str| {
+        let mut execs = p.cargo(cmd);
+        execs
+            .arg("-v")
+            .arg("-Zgit=ls-remote")
+            .args(args)
+            .masquerade_as_nightly_cargo(&["git", "gitoxide"]);
+        execs
+    };
+
+    cargo("generate-lockfile").run();
+
+    // Neither the branch nor the tag have moved.
+    cargo("update")
+        .with_stderr_data(
+            str![[r#"
+[UPDATING] git repository `[ROOTURL]/bar`
+[UPDATING] git repository `[ROOTURL]/bar`
+[FRESH] git repository `[ROOTURL]/bar` is up to date
+[FRESH] git repository `[ROOTURL]/bar` is up to date
+[LOCKING] 0 packages to latest compatible versions
+...
+"#]]
+            .unordered(),
+        )
+        .run();
+
+    // The branch moves, the tag doesn't.
+    bar.change_file("src/lib.rs", "pub fn bar() {}");
+    git::add(&repo);
+    git::commit(&repo);
+
+    cargo("update")
+        .with_stderr_data(
+            str![[r#"
+[UPDATING] git repository `[ROOTURL]/bar`
+[UPDATING] git repository `[ROOTURL]/bar`
+[FRESH] git repository `[ROOTURL]/bar` is up to date
+[LOCKING] 1 package to latest compatible version
+[UPDATING] bar v0.5.0 ([ROOTURL]/bar?branch=master#[..]) -> #[..]
+...
+"#]]
+            .unordered(),
+        )
+        .run();
+
+    cargo("update")
+        .with_stderr_data(
+            str![[r#"
+[UPDATING] git repository `[ROOTURL]/bar`
+[UPDATING] git repository `[ROOTURL]/bar`
+[FRESH] git repository `[ROOTURL]/bar` is up to date
+[FRESH] git repository `[ROOTURL]/bar` is up to date
+[LOCKING] 0 packages to latest compatible versions
+...
+"#]]
+            .unordered(),
+        )
+        .run();
+}
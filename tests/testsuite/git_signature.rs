@@ -0,0 +1,247 @@
+//! Tests for verifying signatures of git dependencies (`-Zgit=verify-signature`).
+
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use cargo_test_support::prelude::*;
+use cargo_test_support::{basic_manifest, git, paths, process, project, str, Project};
+
+/// Generates an SSH key called `name`, returning the path to its private key
+/// and an allowed signers line for it.
+fn signing_key(name: &str) -> (PathBuf, String) {
+    let path = paths::root().join(name);
+    process("ssh-keygen")
+        .args(&["-q", "-t", "ed25519", "-N", "", "-f"])
+        .arg(&path)
+        .exec_with_output()
+        .unwrap();
+    let pub_key = fs::read_to_string(path.with_extension("pub")).unwrap();
+    (path, format!("{name}@example.com {}", pub_key.trim()))
+}
+
+/// Runs `git` in `repo`, signing with the SSH `key`.
+fn git_signing(repo: &Path, key: &Path, args: &[&str]) {
+    process("git")
+        .arg("-c")
+        .arg("gpg.format=ssh")
+        .arg("-c")
+        .arg(format!("user.signingkey={}", key.display()))
+        .args(&[
+            "-c",
+            "user.name=signer",
+            "-c",
+            "user.email=signer@example.com",
+        ])
+        .args(args)
+        .cwd(repo)
+        .exec_with_output()
+        .unwrap();
+}
+
+/// Writes the allowed signers file and points `net.git-allowed-signers` to it.
+fn allow_signers(p: &Project, signers: &[&str]) {
+    fs::write(p.root().join("allowed_signers"), signers.join("\n")).unwrap();
+    p.change_file(
+        ".cargo/config.toml",
+        r#"
+            [net]
+            git-allowed-signers = "allowed_signers"
+        "#,
+    );
+}
+
+fn foo(dep: &str) -> Project {
+    project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "foo"
+                    version = "0.1.0"
+                    edition = "2015"
+
+                    [dependencies]
+                    dep = {{ {dep}, verify-signature = true }}
+                "#
+            ),
+        )
+        .file("src/lib.rs", "")
+        .build()
+}
+
+fn dep_repo() -> Project {
+    git::new("dep", |project| {
+        project
+            .file("Cargo.toml", &basic_manifest("dep", "0.1.0"))
+            .file("src/lib.rs", "")
+    })
+}
+
+#[cargo_test(requires_git)]
+fn signed_commit() {
+    let (key, signer) = signing_key("key");
+    let dep = dep_repo();
+    git_signing(
+        &dep.root(),
+        &key,
+        &["commit", "--allow-empty", "-S", "-m", "signed"],
+    );
+
+    let p = foo(&format!("git = '{}'", dep.url()));
+    allow_signers(&p, &[&signer]);
+
+    p.cargo("check -Zgit=verify-signature")
+        .masquerade_as_nightly_cargo(&["git"])
+        .with_stderr_data(str![[r#"
+[UPDATING] git repository `[ROOTURL]/dep`
+[LOCKING] 1 package to latest compatible version
+[CHECKING] dep v0.1.0 ([ROOTURL]/dep#[..])
+[CHECKING] foo v0.1.0 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test(requires_git)]
+fn unsigned_commit() {
+    let (_key, signer) = signing_key("key");
+    let dep = dep_repo();
+
+    let p = foo(&format!("git = '{}'", dep.url()));
+    allow_signers(&p, &[&signer]);
+
+    p.cargo("check -Zgit=verify-signature")
+        .masquerade_as_nightly_cargo(&["git"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] git repository `[ROOTURL]/dep`
+[ERROR] failed to get `dep` as a dependency of package `foo v0.1.0 ([ROOT]/foo)`
+
+Caused by:
+  revision `[..]` of git repository `[ROOTURL]/dep` is not signed by an allowed signer
+
+Caused by:
+  process didn't exit successfully: `git -c gpg.ssh.allowedSignersFile=[ROOT]/foo/allowed_signers verify-commit [..]` ([EXIT_STATUS]: 1)
+...
+"#]])
+        .run();
+}
+
+#[cargo_test(requires_git)]
+fn signed_by_other_key() {
+    let (key, _signer) = signing_key("key");
+    let (_other_key, other_signer) = signing_key("other");
+    let dep = dep_repo();
+    git_signing(
+        &dep.root(),
+        &key,
+        &["commit", "--allow-empty", "-S", "-m", "signed"],
+    );
+
+    let p = foo(&format!("git = '{}'", dep.url()));
+    allow_signers(&p, &[&other_signer]);
+
+    p.cargo("check -Zgit=verify-signature")
+        .masquerade_as_nightly_cargo(&["git"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] git repository `[ROOTURL]/dep`
+[ERROR] failed to get `dep` as a dependency of package `foo v0.1.0 ([ROOT]/foo)`
+
+Caused by:
+  revision `[..]` of git repository `[ROOTURL]/dep` is not signed by an allowed signer
+...
+"#]])
+        .run();
+}
+
+#[cargo_test(requires_git)]
+fn signed_tag() {
+    let (key, signer) = signing_key("key");
+    let dep = dep_repo();
+    git_signing(&dep.root(), &key, &["tag", "-s", "v1", "-m", "signed tag"]);
+
+    let p = foo(&format!("git = '{}', tag = 'v1'", dep.url()));
+    allow_signers(&p, &[&signer]);
+
+    p.cargo("check -Zgit=verify-signature")
+        .masquerade_as_nightly_cargo(&["git"])
+        .with_stderr_data(str![[r#"
+[UPDATING] git repository `[ROOTURL]/dep`
+[LOCKING] 1 package to latest compatible version
+[CHECKING] dep v0.1.0 ([ROOTURL]/dep?tag=v1#[..])
+[CHECKING] foo v0.1.0 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    // The tag isn't accepted for other kinds of references to the same commit.
+    p.change_file(
+        "Cargo.toml",
+        &format!(
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                dep = {{ git = '{}', branch = 'master', verify-signature = true }}
+            "#,
+            dep.url()
+        ),
+    );
+    p.cargo("check -Zgit=verify-signature")
+        .masquerade_as_nightly_cargo(&["git"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] git repository `[ROOTURL]/dep`
+[ERROR] failed to get `dep` as a dependency of package `foo v0.1.0 ([ROOT]/foo)`
+
+Caused by:
+  revision `[..]` of git repository `[ROOTURL]/dep` is not signed by an allowed signer
+...
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn ignored_without_unstable_flag() {
+    let dep = dep_repo();
+    let p = foo(&format!("git = '{}'", dep.url()));
+
+    p.cargo("check")
+        .with_stderr_data(str![[r#"
+[WARNING] ignoring `verify-signature` on dependency dep, pass `-Zgit=verify-signature` to enable support for it
+[UPDATING] git repository `[ROOTURL]/dep`
+[LOCKING] 1 package to latest compatible version
+[CHECKING] dep v0.1.0 ([ROOTURL]/dep#[..])
+[CHECKING] foo v0.1.0 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn requires_git_dependency() {
+    let p = foo("path = 'dep'");
+    p.change_file("dep/Cargo.toml", &basic_manifest("dep", "0.1.0"));
+    p.change_file("dep/src/lib.rs", "");
+
+    p.cargo("check -Zgit=verify-signature")
+        .masquerade_as_nightly_cargo(&["git"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] failed to parse manifest at `[ROOT]/foo/Cargo.toml`
+
+Caused by:
+  key `verify-signature` is ignored for dependency (dep).
+
+"#]])
+        .run();
+}
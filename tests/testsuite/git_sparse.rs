@@ -0,0 +1,279 @@
+//! Tests for sparse checkouts of git dependencies (`-Zgit=sparse-checkout`).
+
+use std::path::PathBuf;
+
+use cargo_test_support::prelude::*;
+use cargo_test_support::{git, paths, project, str, Project};
+
+/// A repository with a virtual workspace, where `app` depends on `util`
+/// through `[workspace.dependencies]`, and `big` is unrelated.
+fn monorepo() -> Project {
+    git::new("monorepo", |project| {
+        project
+            .file(
+                "Cargo.toml",
+                r#"
+                    [workspace]
+                    members = ["crates/*"]
+
+                    [workspace.package]
+                    version = "0.1.0"
+                    edition = "2015"
+
+                    [workspace.dependencies]
+                    util = { path = "crates/util" }
+                "#,
+            )
+            .file("README.md", "a big monorepo")
+            .file(
+                "crates/app/Cargo.toml",
+                r#"
+                    [package]
+                    name = "app"
+                    version.workspace = true
+                    edition.workspace = true
+
+                    [dependencies]
+                    util.workspace = true
+                "#,
+            )
+            .file(
+                "crates/app/src/lib.rs",
+                "extern crate util; pub fn app() -> i32 { util::util() }",
+            )
+            .file(
+                "crates/util/Cargo.toml",
+                r#"
+                    [package]
+                    name = "util"
+                    version.workspace = true
+                    edition.workspace = true
+                "#,
+            )
+            .file("crates/util/src/lib.rs", "pub fn util() -> i32 { 1 }")
+            .file(
+                "crates/big/Cargo.toml",
+                r#"
+                    [package]
+                    name = "big"
+                    version.workspace = true
+                    edition.workspace = true
+                "#,
+            )
+            .file("crates/big/src/lib.rs", "pub fn big() -> i32 { 2 }")
+    })
+}
+
+/// Finds the single sparse checkout in `CARGO_HOME`.
+fn sparse_checkout() -> PathBuf {
+    let checkouts = paths::home().join(".cargo/git/checkouts");
+    let mut found = Vec::new();
+    for db in checkouts.read_dir().unwrap() {
+        for checkout in db.unwrap().path().read_dir().unwrap() {
+            let checkout = checkout.unwrap().path();
+            if checkout.to_str().unwrap().ends_with("-sparse") {
+                found.push(checkout);
+            }
+        }
+    }
+    assert_eq!(
+        found.len(),
+        1,
+        "expected a single sparse checkout: {found:?}"
+    );
+    found.pop().unwrap()
+}
+
+#[cargo_test]
+fn checks_out_requested_package_only() {
+    let repo = monorepo();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "foo"
+                    version = "0.1.0"
+                    edition = "2015"
+
+                    [dependencies]
+                    app = {{ git = "{}" }}
+                "#,
+                repo.url()
+            ),
+        )
+        .file(
+            "src/lib.rs",
+            "extern crate app; pub fn foo() -> i32 { app::app() }",
+        )
+        .build();
+
+    p.cargo("check -Zgit=sparse-checkout")
+        .masquerade_as_nightly_cargo(&["git"])
+        .with_stderr_data(str![[r#"
+[UPDATING] git repository `[ROOTURL]/monorepo`
+[LOCKING] 2 packages to latest compatible versions
+[CHECKING] util v0.1.0 ([ROOTURL]/monorepo#[..])
+[CHECKING] app v0.1.0 ([ROOTURL]/monorepo#[..])
+[CHECKING] foo v0.1.0 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    let checkout = sparse_checkout();
+    assert!(checkout.join("Cargo.toml").is_file());
+    assert!(checkout.join("crates/app/src/lib.rs").is_file());
+    assert!(checkout.join("crates/util/src/lib.rs").is_file());
+    assert!(!checkout.join("crates/big").exists());
+    assert!(!checkout.join("README.md").exists());
+
+    // The existing checkout is reused.
+    p.cargo("check -Zgit=sparse-checkout")
+        .masquerade_as_nightly_cargo(&["git"])
+        .with_stderr_data(str![[r#"
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    // Without the feature, a full checkout is made next to the sparse one.
+    p.cargo("check")
+        .with_stderr_data(str![[r#"
+[CHECKING] util v0.1.0 ([ROOTURL]/monorepo#[..])
+[CHECKING] app v0.1.0 ([ROOTURL]/monorepo#[..])
+[CHECKING] foo v0.1.0 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+    let full = checkout.with_file_name(
+        checkout
+            .file_name()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .trim_end_matches("-sparse"),
+    );
+    assert!(full.join("crates/big/src/lib.rs").is_file());
+    assert!(full.join("README.md").is_file());
+}
+
+#[cargo_test]
+fn extends_checkout_for_later_queries() {
+    let repo = monorepo();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "foo"
+                    version = "0.1.0"
+                    edition = "2015"
+
+                    [dependencies]
+                    app = {{ git = "{url}" }}
+                    bar = {{ path = "bar" }}
+                "#,
+                url = repo.url()
+            ),
+        )
+        .file("src/lib.rs", "")
+        .file(
+            "bar/Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "bar"
+                    version = "0.1.0"
+                    edition = "2015"
+
+                    [dependencies]
+                    big = {{ git = "{url}" }}
+                "#,
+                url = repo.url()
+            ),
+        )
+        .file(
+            "bar/src/lib.rs",
+            "extern crate big; pub fn bar() -> i32 { big::big() }",
+        )
+        .build();
+
+    p.cargo("check -Zgit=sparse-checkout")
+        .masquerade_as_nightly_cargo(&["git"])
+        .with_stderr_data(
+            str![[r#"
+[UPDATING] git repository `[ROOTURL]/monorepo`
+[LOCKING] 4 packages to latest compatible versions
+[CHECKING] util v0.1.0 ([ROOTURL]/monorepo#[..])
+[CHECKING] big v0.1.0 ([ROOTURL]/monorepo#[..])
+[CHECKING] app v0.1.0 ([ROOTURL]/monorepo#[..])
+[CHECKING] bar v0.1.0 ([ROOT]/foo/bar)
+[CHECKING] foo v0.1.0 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]]
+            .unordered(),
+        )
+        .run();
+
+    let checkout = sparse_checkout();
+    assert!(checkout.join("crates/app/src/lib.rs").is_file());
+    assert!(checkout.join("crates/util/src/lib.rs").is_file());
+    assert!(checkout.join("crates/big/src/lib.rs").is_file());
+    assert!(!checkout.join("README.md").exists());
+}
+
+#[cargo_test]
+fn falls_back_to_full_checkout_for_root_package() {
+    let repo = git::new("dep", |project| {
+        project
+            .file(
+                "Cargo.toml",
+                r#"
+                    [package]
+                    name = "dep"
+                    version = "0.1.0"
+                    edition = "2015"
+                "#,
+            )
+            .file("src/lib.rs", "")
+    });
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "foo"
+                    version = "0.1.0"
+                    edition = "2015"
+
+                    [dependencies]
+                    dep = {{ git = "{}" }}
+                "#,
+                repo.url()
+            ),
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check -Zgit=sparse-checkout")
+        .masquerade_as_nightly_cargo(&["git"])
+        .run();
+
+    let checkouts = paths::home().join(".cargo/git/checkouts");
+    let db = checkouts
+        .read_dir()
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    let checkout = db.read_dir().unwrap().next().unwrap().unwrap().path();
+    assert!(!checkout.to_str().unwrap().ends_with("-sparse"));
+    assert!(checkout.join("src/lib.rs").is_file());
+}
@@ -0,0 +1,161 @@
+use cargo_test_support::prelude::*;
+use cargo_test_support::str;
+use cargo_test_support::{basic_manifest, project};
+
+const UNSAFE_LIB: &str = "
+pub fn foo(num: i32) -> u32 {
+    unsafe { std::mem::transmute(num) }
+}
+";
+
+#[cargo_test]
+fn rust_lints() {
+    let p = project()
+        .file(
+            ".cargo/config.toml",
+            r#"
+[lints.rust]
+unsafe_code = "deny"
+"#,
+        )
+        .file(
+            "Cargo.toml",
+            r#"
+[package]
+name = "foo"
+version = "0.0.1"
+edition = "2015"
+"#,
+        )
+        .file("src/lib.rs", UNSAFE_LIB)
+        .build();
+
+    p.cargo("check -Zconfig-lints")
+        .masquerade_as_nightly_cargo(&["config-lints"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[ERROR] usage of an `unsafe` block
+...
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn overrides_manifest() {
+    let p = project()
+        .file(
+            ".cargo/config.toml",
+            r#"
+[lints.rust]
+unsafe_code = "deny"
+"#,
+        )
+        .file(
+            "Cargo.toml",
+            r#"
+[package]
+name = "foo"
+version = "0.0.1"
+edition = "2015"
+
+[lints.rust]
+unsafe_code = "allow"
+"#,
+        )
+        .file("src/lib.rs", UNSAFE_LIB)
+        .build();
+
+    p.cargo("check -Zconfig-lints")
+        .masquerade_as_nightly_cargo(&["config-lints"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[ERROR] usage of an `unsafe` block
+...
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn requires_feature() {
+    let p = project()
+        .file(
+            ".cargo/config.toml",
+            r#"
+[lints.rust]
+unsafe_code = "deny"
+"#,
+        )
+        .file(
+            "Cargo.toml",
+            r#"
+[package]
+name = "foo"
+version = "0.0.1"
+edition = "2015"
+"#,
+        )
+        .file("src/lib.rs", UNSAFE_LIB)
+        .build();
+
+    p.cargo("check")
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+...
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn cargo_lints_level_source() {
+    let p = project()
+        .file(
+            ".cargo/config.toml",
+            r#"
+[lints.cargo]
+shadowed_optional_dependency = "deny"
+"#,
+        )
+        .file(
+            "Cargo.toml",
+            r#"
+[package]
+name = "foo"
+version = "0.0.1"
+edition = "2015"
+
+[dependencies]
+bar = { path = "bar", optional = true }
+
+[features]
+bar = []
+derive = ["dep:bar"]
+
+[lints.cargo]
+shadowed_optional_dependency = "allow"
+"#,
+        )
+        .file("src/lib.rs", "")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.0.1"))
+        .file("bar/src/lib.rs", "")
+        .build();
+
+    p.cargo("check -Zcargo-lints -Zconfig-lints")
+        .masquerade_as_nightly_cargo(&["cargo-lints", "config-lints"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] feature has the name of an optional dependency it doesn't enable: `bar`
+  --> Cargo.toml:11:1
+   |
+11 | bar = []
+   | ^^^
+   |
+   = [NOTE] `cargo::shadowed_optional_dependency` is set to `deny` in `[lints]` of `[ROOT]/foo/.cargo/config.toml`
+   = [HELP] add `"dep:bar"` to the feature, or give the feature another name
+
+"#]])
+        .run();
+}
@@ -0,0 +1,270 @@
+use cargo_test_support::compare::assert_e2e;
+use cargo_test_support::prelude::*;
+use cargo_test_support::registry::Package;
+use cargo_test_support::{project, str, Project};
+
+fn make_project(a_req: &str, b_req: &str) -> Project {
+    Package::new("dep", "0.1.0").publish();
+    Package::new("dep", "0.2.0").publish();
+    Package::new("dep", "1.0.0").publish();
+    Package::new("dep", "1.2.0").publish();
+
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+[workspace]
+members = ["a", "b"]
+resolver = "2"
+
+[workspace.lints.cargo]
+divergent_dependency_versions = "warn"
+"#,
+        )
+        .file(
+            "a/Cargo.toml",
+            &format!(
+                r#"
+[package]
+name = "a"
+version = "0.1.0"
+edition = "2015"
+
+[dependencies]
+dep = "{a_req}"
+
+[lints]
+workspace = true
+"#
+            ),
+        )
+        .file("a/src/lib.rs", "")
+        .file(
+            "b/Cargo.toml",
+            &format!(
+                r#"
+[package]
+name = "b"
+version = "0.1.0"
+edition = "2015"
+
+[dev-dependencies]
+dep = {{ version = "{b_req}", features = [] }}
+
+[lints]
+workspace = true
+"#
+            ),
+        )
+        .file("b/src/lib.rs", "")
+        .build()
+}
+
+#[cargo_test]
+fn warn() {
+    let p = make_project("1.0", "1.2");
+
+    p.cargo("check -Zcargo-lints")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(
+            str![[r#"
+[WARNING] dependency is required with different versions across the workspace: `dep`
+ --> b/Cargo.toml:8:7
+  |
+8 | dep = { version = "1.2", features = [] }
+  |       ----------------------------------
+  |
+  = [NOTE] `a/Cargo.toml` requires `1.0`
+  = [NOTE] `cargo::divergent_dependency_versions` is set to `warn` in `[lints]`
+  = [HELP] move `dep` to `[workspace.dependencies]` and use `dep.workspace = true`, or run `cargo fix` to do it
+[WARNING] dependency is required with different versions across the workspace: `dep`
+ --> a/Cargo.toml:8:7
+  |
+8 | dep = "1.0"
+  |       -----
+  |
+  = [NOTE] `b/Cargo.toml` requires `1.2`
+  = [NOTE] `cargo::divergent_dependency_versions` is set to `warn` in `[lints]`
+  = [HELP] move `dep` to `[workspace.dependencies]` and use `dep.workspace = true`, or run `cargo fix` to do it
+[UPDATING] `dummy-registry` index
+[LOCKING] 1 package to latest compatible version
+[DOWNLOADING] crates ...
+[DOWNLOADED] dep v1.2.0 (registry `dummy-registry`)
+[CHECKING] dep v1.2.0
+[CHECKING] a v0.1.0 ([ROOT]/foo/a)
+[CHECKING] b v0.1.0 ([ROOT]/foo/b)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]]
+            .unordered(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn incompatible_versions() {
+    let p = make_project("0.1", "0.2");
+
+    p.cargo("check -Zcargo-lints")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(
+            str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 2 packages to latest compatible versions
+[ADDING] dep v0.1.0 (available: v1.2.0)
+[ADDING] dep v0.2.0 (available: v1.2.0)
+[DOWNLOADING] crates ...
+[DOWNLOADED] dep v0.1.0 (registry `dummy-registry`)
+[CHECKING] dep v0.1.0
+[CHECKING] a v0.1.0 ([ROOT]/foo/a)
+[CHECKING] b v0.1.0 ([ROOT]/foo/b)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]]
+            .unordered(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn pinned_versions() {
+    let p = make_project("=1.0.0", "1.0");
+
+    p.cargo("fix -Zcargo-lints --workspace --allow-no-vcs")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(
+            str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 1 package to latest compatible version
+[ADDING] dep v1.0.0 (available: v1.2.0)
+[DOWNLOADING] crates ...
+[DOWNLOADED] dep v1.0.0 (registry `dummy-registry`)
+[CHECKING] dep v1.0.0
+[CHECKING] a v0.1.0 ([ROOT]/foo/a)
+[CHECKING] b v0.1.0 ([ROOT]/foo/b)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]]
+            .unordered(),
+        )
+        .run();
+    assert!(p.read_file("a/Cargo.toml").contains(r#"dep = "=1.0.0""#));
+}
+
+#[cargo_test]
+fn allowed_by_default() {
+    let p = make_project("1.0", "1.2");
+    p.change_file(
+        "Cargo.toml",
+        r#"
+[workspace]
+members = ["a", "b"]
+resolver = "2"
+
+[workspace.lints.cargo]
+"#,
+    );
+
+    p.cargo("check -Zcargo-lints")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(
+            str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 1 package to latest compatible version
+[DOWNLOADING] crates ...
+[DOWNLOADED] dep v1.2.0 (registry `dummy-registry`)
+[CHECKING] dep v1.2.0
+[CHECKING] a v0.1.0 ([ROOT]/foo/a)
+[CHECKING] b v0.1.0 ([ROOT]/foo/b)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]]
+            .unordered(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn fix() {
+    let p = make_project("1.0", "1.2");
+
+    p.cargo("fix -Zcargo-lints --workspace --allow-no-vcs")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(
+            str![[r#"
+[FIXED] Cargo.toml (1 fix)
+[FIXED] a/Cargo.toml (1 fix)
+[FIXED] b/Cargo.toml (1 fix)
+[UPDATING] `dummy-registry` index
+[LOCKING] 1 package to latest compatible version
+[DOWNLOADING] crates ...
+[DOWNLOADED] dep v1.2.0 (registry `dummy-registry`)
+[CHECKING] dep v1.2.0
+[CHECKING] a v0.1.0 ([ROOT]/foo/a)
+[CHECKING] b v0.1.0 ([ROOT]/foo/b)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]]
+            .unordered(),
+        )
+        .run();
+
+    assert_e2e().eq(
+        p.read_file("Cargo.toml"),
+        str![[r#"
+
+[workspace]
+members = ["a", "b"]
+resolver = "2"
+
+[workspace.lints.cargo]
+divergent_dependency_versions = "warn"
+
+[workspace.dependencies]
+dep = "1.2"
+
+"#]],
+    );
+    assert_e2e().eq(
+        p.read_file("a/Cargo.toml"),
+        str![[r#"
+
+[package]
+name = "a"
+version = "0.1.0"
+edition = "2015"
+
+[dependencies]
+dep.workspace = true
+
+[lints]
+workspace = true
+
+"#]],
+    );
+    assert_e2e().eq(
+        p.read_file("b/Cargo.toml"),
+        str![[r#"
+
+[package]
+name = "b"
+version = "0.1.0"
+edition = "2015"
+
+[dev-dependencies]
+dep = { features = [], workspace = true }
+
+[lints]
+workspace = true
+
+"#]],
+    );
+
+    p.cargo("check -Zcargo-lints")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(str![[r#"
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
@@ -0,0 +1,123 @@
+use cargo_test_support::prelude::*;
+use cargo_test_support::project;
+use cargo_test_support::str;
+
+#[cargo_test]
+fn warn() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+[package]
+name = "foo"
+version = "0.0.1"
+edition = "2015"
+
+[dependencies]
+bar = { path = "bar", optional = true }
+
+[dev-dependencies]
+baz = { path = "baz" }
+
+[features]
+bar = ["dep:bar", "bar/extra"]
+test-extra = ["baz/extra"]
+
+[lints.cargo]
+feature_enables_dev_dependency = "warn"
+"#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            "bar/Cargo.toml",
+            r#"
+[package]
+name = "bar"
+version = "0.0.1"
+edition = "2015"
+
+[features]
+extra = []
+"#,
+        )
+        .file("bar/src/lib.rs", "")
+        .file(
+            "baz/Cargo.toml",
+            r#"
+[package]
+name = "baz"
+version = "0.0.1"
+edition = "2015"
+
+[features]
+extra = []
+"#,
+        )
+        .file("baz/src/lib.rs", "")
+        .build();
+
+    p.cargo("check -Zcargo-lints")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(str![[r#"
+[WARNING] feature enables a feature of a dev-dependency: `baz/extra`
+  --> Cargo.toml:15:15
+   |
+15 | test-extra = ["baz/extra"]
+   |               -----------
+   |
+   = [NOTE] `cargo::feature_enables_dev_dependency` is set to `warn` in `[lints]`
+   = [HELP] `baz` is only a dev-dependency, so this has no effect for dependents of `foo`
+[LOCKING] 2 packages to latest compatible versions
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn allowed_by_default() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+[package]
+name = "foo"
+version = "0.0.1"
+edition = "2015"
+
+[dev-dependencies]
+baz = { path = "baz" }
+
+[features]
+test-extra = ["baz/extra"]
+
+[lints.cargo]
+"#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            "baz/Cargo.toml",
+            r#"
+[package]
+name = "baz"
+version = "0.0.1"
+edition = "2015"
+
+[features]
+extra = []
+"#,
+        )
+        .file("baz/src/lib.rs", "")
+        .build();
+
+    p.cargo("check -Zcargo-lints")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(str![[r#"
+[LOCKING] 1 package to latest compatible version
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
@@ -0,0 +1,203 @@
+use cargo_test_support::prelude::*;
+use cargo_test_support::project;
+use cargo_test_support::registry::Package;
+use cargo_test_support::str;
+
+fn publish_bar() {
+    Package::new("bar", "1.0.0").rust_version("1.60").publish();
+    Package::new("bar", "1.1.0").rust_version("1.60").publish();
+    Package::new("bar", "1.2.0").rust_version("1.80").publish();
+}
+
+#[cargo_test]
+fn warn() {
+    publish_bar();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+[package]
+name = "foo"
+version = "0.0.1"
+edition = "2015"
+rust-version = "1.70"
+
+[dependencies]
+bar = "1.0.0"
+"#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check -Zcargo-lints")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 1 package to latest compatible version
+[WARNING] dependency requires a newer Rust than the package's `rust-version`: `bar`
+ --> Cargo.toml:9:1
+  |
+9 | bar = "1.0.0"
+  | -------------
+  |
+  = [NOTE] `cargo::incompatible_dependency_rust_version` is set to `warn` by default
+  = [HELP] `bar` v1.2.0 requires Rust 1.80, while `rust-version` is 1.70; lock the highest compatible version with `cargo update bar@1.2.0 --precise 1.1.0`
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v1.2.0 (registry `dummy-registry`)
+[CHECKING] bar v1.2.0
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    p.cargo("update bar@1.2.0 --precise 1.1.0").run();
+    p.cargo("check -Zcargo-lints")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(str![[r#"
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v1.1.0 (registry `dummy-registry`)
+[CHECKING] bar v1.1.0
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn requirement_excludes_compatible_version() {
+    publish_bar();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+[package]
+name = "foo"
+version = "0.0.1"
+edition = "2015"
+rust-version = "1.70"
+
+[lints.cargo]
+incompatible_dependency_rust_version = "deny"
+
+[dev-dependencies]
+bar = "1.2.0"
+"#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check -Zcargo-lints")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 1 package to latest compatible version
+[ERROR] dependency requires a newer Rust than the package's `rust-version`: `bar`
+  --> Cargo.toml:12:1
+   |
+12 | bar = "1.2.0"
+   | ^^^^^^^^^^^^^
+   |
+   = [NOTE] `cargo::incompatible_dependency_rust_version` is set to `deny` in `[lints]`
+   = [HELP] `bar` v1.2.0 requires Rust 1.80, while `rust-version` is 1.70; the highest compatible version is v1.1.0, which needs the version requirement of `bar` to change
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn without_rust_version() {
+    publish_bar();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+[package]
+name = "foo"
+version = "0.0.1"
+edition = "2015"
+
+[dependencies]
+bar = "1.0.0"
+"#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check -Zcargo-lints")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 1 package to latest compatible version
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v1.2.0 (registry `dummy-registry`)
+[CHECKING] bar v1.2.0
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn only_when_building() {
+    publish_bar();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+[package]
+name = "foo"
+version = "0.0.1"
+edition = "2015"
+rust-version = "1.70"
+
+[dependencies]
+bar = "1.0.0"
+"#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("tree -Zcargo-lints")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stdout_data(str![[r#"
+foo v0.0.1 ([ROOT]/foo)
+└── bar v1.2.0
+
+"#]])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 1 package to latest compatible version
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v1.2.0 (registry `dummy-registry`)
+
+"#]])
+        .run();
+
+    // Reported once, though the build resolves the workspace again.
+    p.cargo("doc -Zcargo-lints")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(
+            str![[r#"
+[WARNING] dependency requires a newer Rust than the package's `rust-version`: `bar`
+ --> Cargo.toml:9:1
+  |
+9 | bar = "1.0.0"
+  | -------------
+  |
+  = [NOTE] `cargo::incompatible_dependency_rust_version` is set to `warn` by default
+  = [HELP] `bar` v1.2.0 requires Rust 1.80, while `rust-version` is 1.70; lock the highest compatible version with `cargo update bar@1.2.0 --precise 1.1.0`
+[DOCUMENTING] bar v1.2.0
+[CHECKING] bar v1.2.0
+[DOCUMENTING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[GENERATED] [ROOT]/foo/target/doc/foo/index.html
+
+"#]]
+            .unordered(),
+        )
+        .run();
+}
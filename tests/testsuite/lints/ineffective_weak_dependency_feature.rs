@@ -0,0 +1,103 @@
+use cargo_test_support::prelude::*;
+use cargo_test_support::project;
+use cargo_test_support::str;
+
+fn make_project(features: &str) -> cargo_test_support::Project {
+    project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+[package]
+name = "foo"
+version = "0.0.1"
+edition = "2015"
+
+[dependencies]
+bar = {{ path = "bar", optional = true }}
+
+[features]
+{features}
+"#
+            ),
+        )
+        .file("src/lib.rs", "")
+        .file(
+            "bar/Cargo.toml",
+            r#"
+[package]
+name = "bar"
+version = "0.0.1"
+edition = "2015"
+
+[features]
+std = []
+"#,
+        )
+        .file("bar/src/lib.rs", "")
+        .build()
+}
+
+#[cargo_test]
+fn explicit_dep() {
+    let p = make_project(r#"json = ["dep:bar", "bar?/std"]"#);
+
+    p.cargo("check -Zcargo-lints")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(str![[r#"
+[WARNING] weak dependency feature of a dependency the feature always enables: `bar?/std`
+  --> Cargo.toml:11:20
+   |
+11 | json = ["dep:bar", "bar?/std"]
+   |                    ----------
+   |
+   = [NOTE] `cargo::ineffective_weak_dependency_feature` is set to `warn` by default
+   = [HELP] feature `json` always enables `bar`, so use `bar/std`
+[LOCKING] 1 package to latest compatible version
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn implicit_feature() {
+    let p = make_project(r#"json = ["bar", "bar?/std"]"#);
+
+    p.cargo("check -Zcargo-lints")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(str![[r#"
+[WARNING] weak dependency feature of a dependency the feature always enables: `bar?/std`
+  --> Cargo.toml:11:16
+   |
+11 | json = ["bar", "bar?/std"]
+   |                ----------
+   |
+   = [NOTE] `cargo::ineffective_weak_dependency_feature` is set to `warn` by default
+   = [HELP] feature `json` always enables `bar`, so use `bar/std`
+[LOCKING] 1 package to latest compatible version
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn weak_only() {
+    let p = make_project(
+        r#"json = ["dep:bar"]
+std = ["bar?/std"]"#,
+    );
+
+    p.cargo("check -Zcargo-lints")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(str![[r#"
+[LOCKING] 1 package to latest compatible version
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
@@ -0,0 +1,169 @@
+use cargo_test_support::prelude::*;
+use cargo_test_support::project;
+use cargo_test_support::str;
+
+fn make_project() -> cargo_test_support::Project {
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+[package]
+name = "foo"
+version = "0.0.1"
+edition = "2015"
+
+[dependencies]
+bar = { path = "bar", optional = true }
+
+[features]
+json = ["dep:bar", "bar?/std"]
+"#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            "bar/Cargo.toml",
+            r#"
+[package]
+name = "bar"
+version = "0.0.1"
+edition = "2015"
+
+[features]
+std = []
+"#,
+        )
+        .file("bar/src/lib.rs", "")
+        .build()
+}
+
+#[cargo_test]
+fn json() {
+    let p = make_project();
+
+    p.cargo("check -Zcargo-lints --message-format json")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "manifest_path": "[ROOT]/foo/Cargo.toml",
+    "message": {
+      "$message_type": "diagnostic",
+      "children": [
+        {
+          "children": [],
+          "code": null,
+          "level": "note",
+          "message": "`cargo::ineffective_weak_dependency_feature` is set to `warn` by default",
+          "rendered": null,
+          "spans": []
+        },
+        {
+          "children": [],
+          "code": null,
+          "level": "help",
+          "message": "feature `json` always enables `bar`, so use `bar/std`",
+          "rendered": null,
+          "spans": [
+            {
+              "byte_end": 156,
+              "byte_start": 146,
+              "column_end": 30,
+              "column_start": 20,
+              "expansion": null,
+              "file_name": "[ROOT]/foo/Cargo.toml",
+              "is_primary": true,
+              "label": null,
+              "line_end": 11,
+              "line_start": 11,
+              "suggested_replacement": "\"bar/std\"",
+              "suggestion_applicability": "MachineApplicable",
+              "text": [
+                {
+                  "highlight_end": 30,
+                  "highlight_start": 20,
+                  "text": "json = [\"dep:bar\", \"bar?/std\"]"
+                }
+              ]
+            }
+          ]
+        }
+      ],
+      "code": {
+        "code": "cargo::ineffective_weak_dependency_feature",
+        "explanation": null
+      },
+      "level": "warning",
+      "message": "weak dependency feature of a dependency the feature always enables: `bar?/std`",
+      "rendered": "[WARNING] weak dependency feature of a dependency the feature always enables: `bar?/std`\n  --> Cargo.toml:11:20\n   |\n11 | json = [\"dep:bar\", \"bar?/std\"]\n   |                    ----------\n   |\n   = [NOTE] `cargo::ineffective_weak_dependency_feature` is set to `warn` by default\n   = [HELP] feature `json` always enables `bar`, so use `bar/std`\n",
+      "spans": [
+        {
+          "byte_end": 156,
+          "byte_start": 146,
+          "column_end": 30,
+          "column_start": 20,
+          "expansion": null,
+          "file_name": "[ROOT]/foo/Cargo.toml",
+          "is_primary": true,
+          "label": null,
+          "line_end": 11,
+          "line_start": 11,
+          "suggested_replacement": null,
+          "suggestion_applicability": null,
+          "text": [
+            {
+              "highlight_end": 30,
+              "highlight_start": 20,
+              "text": "json = [\"dep:bar\", \"bar?/std\"]"
+            }
+          ]
+        }
+      ]
+    },
+    "package_id": "path+[ROOTURL]/foo#0.0.1",
+    "reason": "cargo-lint"
+  },
+  {
+    "...": "{...}",
+    "reason": "compiler-artifact"
+  },
+  {
+    "reason": "build-finished",
+    "success": true
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .with_stderr_data(str![[r#"
+[LOCKING] 1 package to latest compatible version
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn json_render_diagnostics() {
+    let p = make_project();
+
+    p.cargo("check -Zcargo-lints --message-format json-render-diagnostics")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(str![[r#"
+[WARNING] weak dependency feature of a dependency the feature always enables: `bar?/std`
+  --> Cargo.toml:11:20
+   |
+11 | json = ["dep:bar", "bar?/std"]
+   |                    ----------
+   |
+   = [NOTE] `cargo::ineffective_weak_dependency_feature` is set to `warn` by default
+   = [HELP] feature `json` always enables `bar`, so use `bar/std`
+[LOCKING] 1 package to latest compatible version
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
@@ -3,9 +3,18 @@ use cargo_test_support::project;
 use cargo_test_support::registry::Package;
 use cargo_test_support::str;
 
+mod config;
+mod divergent_dependency_versions;
 mod error;
+mod feature_enables_dev_dependency;
+mod incompatible_dependency_rust_version;
+mod ineffective_weak_dependency_feature;
 mod inherited;
+mod message_format;
+mod shadowed_optional_dependency;
 mod unknown_lints;
+mod unused_dependencies;
+mod unused_features;
 mod warning;
 
 #[cargo_test]
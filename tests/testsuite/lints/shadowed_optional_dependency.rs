@@ -0,0 +1,83 @@
+use cargo_test_support::prelude::*;
+use cargo_test_support::project;
+use cargo_test_support::str;
+
+fn make_project(features: &str) -> cargo_test_support::Project {
+    project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+[package]
+name = "foo"
+version = "0.0.1"
+edition = "2015"
+
+[dependencies]
+bar = {{ path = "bar", optional = true }}
+
+[features]
+{features}
+"#
+            ),
+        )
+        .file("src/lib.rs", "")
+        .file(
+            "bar/Cargo.toml",
+            r#"
+[package]
+name = "bar"
+version = "0.0.1"
+edition = "2015"
+
+[features]
+derive = []
+"#,
+        )
+        .file("bar/src/lib.rs", "")
+        .build()
+}
+
+#[cargo_test]
+fn shadowed() {
+    let p = make_project(
+        r#"bar = []
+derive = ["dep:bar", "bar/derive"]"#,
+    );
+
+    p.cargo("check -Zcargo-lints")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(str![[r#"
+[WARNING] feature has the name of an optional dependency it doesn't enable: `bar`
+  --> Cargo.toml:11:1
+   |
+11 | bar = []
+   | ---
+   |
+   = [NOTE] `cargo::shadowed_optional_dependency` is set to `warn` by default
+   = [HELP] add `"dep:bar"` to the feature, or give the feature another name
+[LOCKING] 1 package to latest compatible version
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn enables_dependency() {
+    let p = make_project(
+        r#"bar = ["dep:bar"]
+derive = ["bar", "bar/derive"]"#,
+    );
+
+    p.cargo("check -Zcargo-lints")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(str![[r#"
+[LOCKING] 1 package to latest compatible version
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
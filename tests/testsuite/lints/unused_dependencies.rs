@@ -0,0 +1,403 @@
+use cargo_test_support::compare::assert_e2e;
+use cargo_test_support::prelude::*;
+use cargo_test_support::registry::Package;
+use cargo_test_support::{project, str, Project};
+
+fn make_project() -> Project {
+    Package::new("used", "1.0.0").publish();
+    Package::new("unused", "1.0.0")
+        .feature("std", &[])
+        .publish();
+    Package::new("test-only", "1.0.0").publish();
+    Package::new("build-unused", "1.0.0").publish();
+
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+[package]
+name = "foo"
+version = "0.1.0"
+edition = "2015"
+
+[dependencies]
+used = "1.0"
+unused = "1.0"
+
+[dev-dependencies]
+test-only = "1.0"
+
+[build-dependencies]
+build-unused = "1.0"
+
+[features]
+extra = ["unused/std"]
+
+[lints.cargo]
+unused_dependencies = "warn"
+"#,
+        )
+        .file("src/lib.rs", "extern crate used;")
+        .file("tests/t.rs", "extern crate test_only;")
+        .file("build.rs", "fn main() {}")
+        .build()
+}
+
+#[cargo_test]
+fn warn() {
+    let p = make_project();
+
+    p.cargo("check -Zcargo-lints --all-targets")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(
+            str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 4 packages to latest compatible versions
+[DOWNLOADING] crates ...
+[DOWNLOADED] used v1.0.0 (registry `dummy-registry`)
+[DOWNLOADED] unused v1.0.0 (registry `dummy-registry`)
+[DOWNLOADED] test-only v1.0.0 (registry `dummy-registry`)
+[DOWNLOADED] build-unused v1.0.0 (registry `dummy-registry`)
+[COMPILING] build-unused v1.0.0
+[CHECKING] unused v1.0.0
+[CHECKING] used v1.0.0
+[CHECKING] test-only v1.0.0
+[COMPILING] foo v0.1.0 ([ROOT]/foo)
+[WARNING] unused dependency: `unused`
+ --> Cargo.toml:9:1
+  |
+9 | unused = "1.0"
+  | --------------
+  |
+  = [NOTE] `cargo::unused_dependencies` is set to `warn` in `[lints]`
+  = [HELP] remove it with `cargo remove unused`
+[WARNING] unused dependency: `build-unused`
+  --> Cargo.toml:15:1
+   |
+15 | build-unused = "1.0"
+   | --------------------
+   |
+   = [HELP] remove it with `cargo remove --build build-unused`
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]]
+            .unordered(),
+        )
+        .run();
+
+    // The reports are replayed from the cache of fresh units.
+    p.cargo("check -Zcargo-lints --all-targets")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(str![[r#"
+[WARNING] unused dependency: `unused`
+...
+[WARNING] unused dependency: `build-unused`
+...
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn only_built_targets() {
+    let p = make_project();
+
+    // The dev-dependency isn't passed to any target that was built.
+    p.cargo("check -Zcargo-lints")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(
+            str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 4 packages to latest compatible versions
+[DOWNLOADING] crates ...
+[DOWNLOADED] used v1.0.0 (registry `dummy-registry`)
+[DOWNLOADED] unused v1.0.0 (registry `dummy-registry`)
+[DOWNLOADED] build-unused v1.0.0 (registry `dummy-registry`)
+[COMPILING] build-unused v1.0.0
+[COMPILING] foo v0.1.0 ([ROOT]/foo)
+[CHECKING] used v1.0.0
+[CHECKING] unused v1.0.0
+[WARNING] unused dependency: `unused`
+ --> Cargo.toml:9:1
+  |
+9 | unused = "1.0"
+  | --------------
+  |
+  = [NOTE] `cargo::unused_dependencies` is set to `warn` in `[lints]`
+  = [HELP] remove it with `cargo remove unused`
+[WARNING] unused dependency: `build-unused`
+  --> Cargo.toml:15:1
+   |
+15 | build-unused = "1.0"
+   | --------------------
+   |
+   = [HELP] remove it with `cargo remove --build build-unused`
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]]
+            .unordered(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn deny() {
+    let p = make_project();
+    p.change_file(
+        "Cargo.toml",
+        &p.read_file("Cargo.toml").replace(
+            r#"unused_dependencies = "warn""#,
+            r#"unused_dependencies = "deny""#,
+        ),
+    );
+
+    p.cargo("check -Zcargo-lints --all-targets")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_status(101)
+        .with_stderr_data(
+            str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 4 packages to latest compatible versions
+[DOWNLOADING] crates ...
+[DOWNLOADED] used v1.0.0 (registry `dummy-registry`)
+[DOWNLOADED] unused v1.0.0 (registry `dummy-registry`)
+[DOWNLOADED] test-only v1.0.0 (registry `dummy-registry`)
+[DOWNLOADED] build-unused v1.0.0 (registry `dummy-registry`)
+[COMPILING] build-unused v1.0.0
+[COMPILING] foo v0.1.0 ([ROOT]/foo)
+[CHECKING] unused v1.0.0
+[CHECKING] used v1.0.0
+[CHECKING] test-only v1.0.0
+[ERROR] unused dependency: `unused`
+ --> Cargo.toml:9:1
+  |
+9 | unused = "1.0"
+  | ^^^^^^^^^^^^^^
+  |
+  = [NOTE] `cargo::unused_dependencies` is set to `deny` in `[lints]`
+  = [HELP] remove it with `cargo remove unused`
+[ERROR] unused dependency: `build-unused`
+  --> Cargo.toml:15:1
+   |
+15 | build-unused = "1.0"
+   | ^^^^^^^^^^^^^^^^^^^^
+   |
+   = [HELP] remove it with `cargo remove --build build-unused`
+
+"#]]
+            .unordered(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn allowed_by_default() {
+    let p = make_project();
+    p.change_file(
+        "Cargo.toml",
+        &p.read_file("Cargo.toml")
+            .replace(r#"unused_dependencies = "warn""#, ""),
+    );
+
+    p.cargo("check -Zcargo-lints --all-targets")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(
+            str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 4 packages to latest compatible versions
+[DOWNLOADING] crates ...
+[DOWNLOADED] used v1.0.0 (registry `dummy-registry`)
+[DOWNLOADED] unused v1.0.0 (registry `dummy-registry`)
+[DOWNLOADED] test-only v1.0.0 (registry `dummy-registry`)
+[DOWNLOADED] build-unused v1.0.0 (registry `dummy-registry`)
+[COMPILING] build-unused v1.0.0
+[COMPILING] foo v0.1.0 ([ROOT]/foo)
+[CHECKING] unused v1.0.0
+[CHECKING] used v1.0.0
+[CHECKING] test-only v1.0.0
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]]
+            .unordered(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn requires_cargo_lints() {
+    let p = make_project();
+
+    p.cargo("check --all-targets")
+        .with_stderr_data(
+            str![[r#"
+[WARNING] unused manifest key `lints.cargo` (may be supported in a future version)
+
+this Cargo does not support nightly features, but if you
+switch to nightly channel you can pass
+`-Zcargo-lints` to enable this feature.
+[UPDATING] `dummy-registry` index
+[LOCKING] 4 packages to latest compatible versions
+[DOWNLOADING] crates ...
+[DOWNLOADED] used v1.0.0 (registry `dummy-registry`)
+[DOWNLOADED] unused v1.0.0 (registry `dummy-registry`)
+[DOWNLOADED] test-only v1.0.0 (registry `dummy-registry`)
+[DOWNLOADED] build-unused v1.0.0 (registry `dummy-registry`)
+[COMPILING] build-unused v1.0.0
+[COMPILING] foo v0.1.0 ([ROOT]/foo)
+[CHECKING] unused v1.0.0
+[CHECKING] used v1.0.0
+[CHECKING] test-only v1.0.0
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]]
+            .unordered(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn fix() {
+    let p = make_project();
+
+    // Code behind the `extra` feature may use the dependencies.
+    p.cargo("fix -Zcargo-lints --all-targets --allow-no-vcs")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .run();
+
+    p.cargo("fix -Zcargo-lints --all-targets --all-features --allow-no-vcs")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(str![[r#"
+...
+[FIXED] Cargo.toml (2 fixes)
+
+"#]])
+        .run();
+
+    assert_e2e().eq(
+        p.read_file("Cargo.toml"),
+        str![[r#"
+
+[package]
+name = "foo"
+version = "0.1.0"
+edition = "2015"
+
+[dependencies]
+used = "1.0"
+
+[dev-dependencies]
+test-only = "1.0"
+
+[features]
+extra = []
+
+[lints.cargo]
+unused_dependencies = "warn"
+
+"#]],
+    );
+
+    p.cargo("check -Zcargo-lints --all-targets")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(str![[r#"
+[COMPILING] foo v0.1.0 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn fix_keeps_dependencies_of_unbuilt_code() {
+    Package::new("doctest-only", "1.0.0").publish();
+    Package::new("cfg-only", "1.0.0").publish();
+    Package::new("optional", "1.0.0").publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+[package]
+name = "foo"
+version = "0.1.0"
+edition = "2015"
+
+[dependencies]
+optional = { version = "1.0", optional = true }
+
+[dev-dependencies]
+doctest-only = "1.0"
+
+[target.'cfg(all())'.dependencies]
+cfg-only = "1.0"
+
+[lints.cargo]
+unused_dependencies = "warn"
+"#,
+        )
+        .file(
+            "src/lib.rs",
+            r#"
+/// ```
+/// extern crate doctest_only;
+/// ```
+pub fn foo() {}
+"#,
+        )
+        .build();
+    let manifest = p.read_file("Cargo.toml");
+
+    p.cargo("fix -Zcargo-lints --all-targets --all-features --allow-no-vcs")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(
+            str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 3 packages to latest compatible versions
+[DOWNLOADING] crates ...
+[DOWNLOADED] optional v1.0.0 (registry `dummy-registry`)
+[DOWNLOADED] doctest-only v1.0.0 (registry `dummy-registry`)
+[DOWNLOADED] cfg-only v1.0.0 (registry `dummy-registry`)
+[CHECKING] cfg-only v1.0.0
+[CHECKING] optional v1.0.0
+[CHECKING] doctest-only v1.0.0
+[CHECKING] foo v0.1.0 ([ROOT]/foo)
+[WARNING] unused dependency: `cfg-only`
+  --> Cargo.toml:14:1
+   |
+14 | cfg-only = "1.0"
+   | ----------------
+   |
+   = [NOTE] `cargo::unused_dependencies` is set to `warn` in `[lints]`
+   = [HELP] remove it with `cargo remove --target 'cfg(all())' cfg-only`
+[WARNING] unused dependency: `doctest-only`
+  --> Cargo.toml:11:1
+   |
+11 | doctest-only = "1.0"
+   | --------------------
+   |
+   = [HELP] remove it with `cargo remove --dev doctest-only`
+[WARNING] unused dependency: `optional`
+ --> Cargo.toml:8:1
+  |
+8 | optional = { version = "1.0", optional = true }
+  | -----------------------------------------------
+  |
+  = [HELP] remove it with `cargo remove optional`
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]]
+            .unordered(),
+        )
+        .run();
+
+    assert_e2e().eq(p.read_file("Cargo.toml"), manifest);
+
+    p.cargo("test --doc")
+        .with_stderr_data(str![[r#"
+...
+[DOCTEST] foo
+...
+"#]])
+        .run();
+}
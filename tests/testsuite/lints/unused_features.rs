@@ -0,0 +1,128 @@
+use cargo_test_support::prelude::*;
+use cargo_test_support::project;
+use cargo_test_support::str;
+
+#[cargo_test]
+fn warn() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+[workspace]
+members = ["a", "b"]
+resolver = "2"
+
+[workspace.lints.cargo]
+unused_features = "warn"
+"#,
+        )
+        .file(
+            "a/Cargo.toml",
+            r#"
+[package]
+name = "a"
+version = "0.1.0"
+edition = "2015"
+
+[dependencies]
+b = { path = "../b", features = ["from-dep"] }
+
+[features]
+default = ["std"]
+std = ["alloc"]
+alloc = []
+b-extra = ["b/from-feature"]
+legacy = []
+
+[lints]
+workspace = true
+"#,
+        )
+        .file("a/src/lib.rs", "")
+        .file(
+            "b/Cargo.toml",
+            r#"
+[package]
+name = "b"
+version = "0.1.0"
+edition = "2015"
+
+[features]
+from-dep = []
+from-feature = ["implied"]
+implied = []
+never = []
+
+[lints]
+workspace = true
+"#,
+        )
+        .file("b/src/lib.rs", "")
+        .build();
+
+    p.cargo("check -Zcargo-lints")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(
+            str![[r#"
+[WARNING] feature is never enabled in the workspace: `never`
+  --> b/Cargo.toml:11:1
+   |
+11 | never = []
+   | -----
+   |
+   = [NOTE] `cargo::unused_features` is set to `warn` in `[lints]`
+   = [HELP] remove the feature, or enable it from `default` or a workspace member
+[WARNING] feature is never enabled in the workspace: `b-extra`
+  --> a/Cargo.toml:14:1
+   |
+14 | b-extra = ["b/from-feature"]
+   | -------
+   |
+   = [NOTE] `cargo::unused_features` is set to `warn` in `[lints]`
+   = [HELP] remove the feature, or enable it from `default` or a workspace member
+[WARNING] feature is never enabled in the workspace: `legacy`
+  --> a/Cargo.toml:15:1
+   |
+15 | legacy = []
+   | ------
+   |
+   = [HELP] remove the feature, or enable it from `default` or a workspace member
+[CHECKING] b v0.1.0 ([ROOT]/foo/b)
+[CHECKING] a v0.1.0 ([ROOT]/foo/a)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]]
+            .unordered(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn allowed_by_default() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+[package]
+name = "foo"
+version = "0.0.1"
+edition = "2015"
+
+[features]
+legacy = []
+
+[lints.cargo]
+"#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check -Zcargo-lints")
+        .masquerade_as_nightly_cargo(&["cargo-lints"])
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
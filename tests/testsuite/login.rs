@@ -367,3 +367,84 @@ fn default_registry_configured() {
     check_token(None, None);
     check_token(Some("a-new-token"), Some("alternative"));
 }
+
+#[cargo_test]
+fn status_requires_nightly() {
+    let _alternative = RegistryBuilder::new().alternative().build();
+
+    cargo_process("login --status --registry alternative")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--status` flag is unstable, and only available on the nightly channel of Cargo, but this is the `stable` channel
+See https://doc.rust-lang.org/book/appendix-07-nightly-rust.html for more information about Rust release channels.
+See https://github.com/rust-lang/cargo/issues/14708 for more information about the `--status` flag.
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn status() {
+    let _alternative = RegistryBuilder::new().alternative().build();
+
+    cargo_process("login -Zunstable-options --status --registry alternative")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_data(str![[r#"
+alternative ([ROOTURL]/alternative-registry)
+  cargo:token: token found (cached for the rest of the command)
+
+"#]])
+        .with_stderr_data(str![[r#"
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn status_not_logged_in() {
+    let registry = RegistryBuilder::new().no_configure_token().build();
+
+    cargo_process("login -Zunstable-options --status")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .replace_crates_io(registry.index_url())
+        .with_status(101)
+        .with_stdout_data(str![[r#"
+crates-io (https://github.com/rust-lang/crates.io-index)
+  cargo:token: no token found
+
+"#]])
+        .with_stderr_data(str![[r#"
+[ERROR] no token found, please run `cargo login`
+or use environment variable CARGO_REGISTRY_TOKEN
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn status_provider_chain() {
+    let _alternative = RegistryBuilder::new().alternative().build();
+    cargo_util::paths::append(
+        &paths::home().join(".cargo/config.toml"),
+        br#"
+            [registry]
+            global-credential-providers = ["cargo:token", "cargo:token-from-stdout missing-command"]
+        "#,
+    )
+    .unwrap();
+
+    cargo_process("login -Zunstable-options --status --registry alternative")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stdout_data(str![[r#"
+alternative ([ROOTURL]/alternative-registry)
+  cargo:token-from-stdout: failed: failed to spawn credential process: [..]
+  cargo:token: not used
+
+"#]])
+        .with_stderr_data(str![[r#"
+[ERROR] no token found for `alternative`, please run `cargo login --registry alternative`
+or use environment variable CARGO_REGISTRIES_ALTERNATIVE_TOKEN
+
+"#]])
+        .run();
+}
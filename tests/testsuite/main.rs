@@ -47,6 +47,7 @@ mod cargo_package;
 mod cargo_pkgid;
 mod cargo_publish;
 mod cargo_read_manifest;
+mod cargo_registry;
 mod cargo_remove;
 mod cargo_report;
 mod cargo_run;
@@ -66,6 +67,7 @@ mod cfg;
 mod check;
 mod check_cfg;
 mod clean;
+mod client_cert;
 mod collisions;
 mod concurrent;
 mod config;
@@ -83,7 +85,9 @@ mod direct_minimal_versions;
 mod directory;
 mod doc;
 mod docscrape;
+mod edit_metadata;
 mod edition;
+mod encrypted_credentials;
 mod error;
 mod features;
 mod features2;
@@ -99,6 +103,8 @@ mod git;
 mod git_auth;
 mod git_gc;
 mod git_shallow;
+mod git_signature;
+mod git_sparse;
 mod glob_targets;
 mod global_cache_tracker;
 mod help;
@@ -167,6 +173,7 @@ mod rustflags;
 mod rustup;
 mod script;
 mod search;
+mod semver_check;
 mod shell_quoting;
 mod source_replacement;
 mod ssh;
@@ -176,6 +183,7 @@ mod timings;
 mod tool_paths;
 mod tree;
 mod tree_graph_features;
+mod trusted_publishing;
 mod unit_graph;
 mod update;
 mod vendor;
@@ -192,3 +192,104 @@ fn simple_remove_with_asymmetric() {
         .with_status(0)
         .run();
 }
+
+#[cargo_test]
+fn add_with_role() {
+    let registry = registry::RegistryBuilder::new().http_api().build();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+                license = "MIT"
+                description = "foo"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("owner -a github:rust-lang:core --role publisher")
+        .replace_crates_io(registry.index_url())
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--role` flag is unstable, and only available on the nightly channel of Cargo, but this is the `stable` channel
+See https://doc.rust-lang.org/book/appendix-07-nightly-rust.html for more information about Rust release channels.
+See https://github.com/rust-lang/cargo/issues/14704 for more information about the `--role` flag.
+
+"#]])
+        .run();
+    p.cargo("owner -Zunstable-options -a github:rust-lang:core --role publisher")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .replace_crates_io(registry.index_url())
+        .with_stderr_data(str![[r#"
+[UPDATING] crates.io index
+[OWNER] github:rust-lang:core added as owners of crate foo
+
+"#]])
+        .run();
+    p.cargo("owner -a octocat")
+        .replace_crates_io(registry.index_url())
+        .run();
+    p.cargo("owner -l")
+        .replace_crates_io(registry.index_url())
+        .with_stdout_data(str![[r#"
+github:rust-lang:core [publisher]
+octocat
+
+"#]])
+        .run();
+
+    // Inviting an existing owner with a role changes its role.
+    p.cargo("owner -Zunstable-options -a github:rust-lang:core --role admin")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .replace_crates_io(registry.index_url())
+        .run();
+    p.cargo("owner -r octocat")
+        .replace_crates_io(registry.index_url())
+        .run();
+    p.cargo("owner -l")
+        .replace_crates_io(registry.index_url())
+        .with_stdout_data(str![[r#"
+github:rust-lang:core [admin]
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn role_without_add() {
+    let registry = registry::init();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+                license = "MIT"
+                description = "foo"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("owner -l --role publisher")
+        .replace_crates_io(registry.index_url())
+        .with_status(1)
+        .with_stderr_data(str![[r#"
+[ERROR] the following required arguments were not provided:
+  --add <LOGIN>
+
+Usage: cargo owner --add <LOGIN> --list --role <ROLE> [CRATE]
+
+For more information, try '--help'.
+
+"#]])
+        .run();
+}
@@ -3,6 +3,7 @@
 use std::fs::{self, read_to_string, File};
 use std::path::Path;
 
+use cargo_test_support::compare::assert_e2e;
 use cargo_test_support::prelude::*;
 use cargo_test_support::publish::validate_crate_contents;
 use cargo_test_support::registry::{self, Package};
@@ -6667,3 +6668,308 @@ See https://doc.rust-lang.org/cargo/reference/manifest.html#package-metadata for
 "#]])
         .run();
 }
+
+#[cargo_test]
+fn digests() {
+    let p = project().build();
+    let _ = git::repo(&paths::root().join("foo"))
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+            edition = "2015"
+            description = "foo"
+            license = "MIT"
+            documentation = "foo"
+        "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("package --no-verify").run();
+    assert!(!p
+        .root()
+        .join("target/package/foo-0.1.0.digests.json")
+        .exists());
+
+    p.cargo("package --no-verify -Zunstable-options --reproducible-check")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .run();
+    let digests = read_to_string(p.root().join("target/package/foo-0.1.0.digests.json")).unwrap();
+    assert_e2e().eq(
+        digests,
+        str![[r#"
+{
+  "cksum": "[..]",
+  "files": {
+    ".cargo_vcs_info.json": "[..]",
+    "Cargo.toml": "[..]",
+    "Cargo.toml.orig": "[..]",
+    "src/lib.rs": "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+  },
+  "name": "foo",
+  "v": 1,
+  "vcs_info": {
+    "git": {
+      "sha1": "[..]"
+    },
+    "path_in_vcs": ""
+  },
+  "vers": "0.1.0"
+}
+"#]]
+        .is_json(),
+    );
+}
+
+#[cargo_test]
+fn reproducible_check() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+            edition = "2015"
+            description = "foo"
+            license = "MIT"
+            documentation = "foo"
+        "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("package --no-verify --reproducible-check")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--reproducible-check` flag is unstable, and only available on the nightly channel of Cargo, but this is the `stable` channel
+See https://doc.rust-lang.org/book/appendix-07-nightly-rust.html for more information about Rust release channels.
+See https://github.com/rust-lang/cargo/issues/14701 for more information about the `--reproducible-check` flag.
+
+"#]])
+        .run();
+
+    p.cargo("package --no-verify -Zunstable-options --reproducible-check")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_data(str![[r#"
+[PACKAGING] foo v0.1.0 ([ROOT]/foo)
+[PACKAGED] 4 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+[REPACKAGING] foo v0.1.0 ([ROOT]/foo)
+
+"#]])
+        .run();
+    assert!(!p.root().join("target/package/reproducible-check").exists());
+}
+
+// Windows doesn't have executable bits.
+#[cfg(unix)]
+#[cargo_test]
+fn reproducible_check_executable() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+            edition = "2015"
+            description = "foo"
+            license = "MIT"
+            documentation = "foo"
+        "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .file("script.sh", "#!/bin/sh")
+        .build();
+    fs::set_permissions(
+        p.root().join("script.sh"),
+        fs::Permissions::from_mode(0o755),
+    )
+    .unwrap();
+
+    p.cargo("package --no-verify -Zunstable-options --reproducible-check")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[PACKAGING] foo v0.1.0 ([ROOT]/foo)
+[PACKAGED] 5 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+[REPACKAGING] foo v0.1.0 ([ROOT]/foo)
+[ERROR] package `foo v0.1.0 ([ROOT]/foo)` is not reproducible, repackaging it produced a different `.crate` file
+the contents of all files are identical, but their metadata differs
+
+"#]])
+        .run();
+    assert!(!p.root().join("target/package/reproducible-check").exists());
+    assert!(!p
+        .root()
+        .join("target/package/foo-0.1.0.digests.json")
+        .exists());
+}
+
+#[cargo_test]
+fn diff_published() {
+    registry::init();
+    Package::new("foo", "0.1.0")
+        .file(
+            "Cargo.toml",
+            r#"[package]
+edition = "2015"
+name = "foo"
+version = "0.1.0"
+build = false
+autolib = false
+autobins = false
+autoexamples = false
+autotests = false
+autobenches = false
+description = "foo"
+documentation = "foo"
+license = "MIT"
+
+[lib]
+name = "foo"
+path = "src/lib.rs"
+"#,
+        )
+        .file("src/lib.rs", "pub fn foo() {}")
+        .file("src/old.rs", "")
+        .file("README.md", "foo")
+        .publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "foo"
+            version = "0.2.0"
+            edition = "2015"
+            description = "foo"
+            license = "MIT"
+            documentation = "foo"
+        "#,
+        )
+        .file("src/lib.rs", "pub fn foo() -> u32 { 0 }")
+        .file("src/new.rs", "")
+        .file("README.md", "foo")
+        .build();
+
+    p.cargo("package --no-verify -Zunstable-options --diff-published")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_data(str![[r#"
+modified: Cargo.toml
+added: Cargo.toml.orig
+modified: src/lib.rs
+added: src/new.rs
+removed: src/old.rs
+
+--- foo-0.1.0/Cargo.toml
++++ foo-0.2.0/Cargo.toml
+@@ -1,7 +1,18 @@
++# THIS FILE IS AUTOMATICALLY GENERATED BY CARGO
++#
++# When uploading crates to the registry Cargo will automatically
++# "normalize" Cargo.toml files for maximal compatibility
++# with all versions of Cargo and also rewrite `path` dependencies
++# to registry (e.g., crates.io) dependencies.
++#
++# If you are reading this file be aware that the original Cargo.toml
++# will likely look very different (and much more reasonable).
++# See Cargo.toml.orig for the original contents.
++
+ [package]
+ edition = "2015"
+ name = "foo"
+-version = "0.1.0"
++version = "0.2.0"
+ build = false
+ autolib = false
+ autobins = false
+@@ -10,6 +21,7 @@
+ autobenches = false
+ description = "foo"
+ documentation = "foo"
++readme = "README.md"
+ license = "MIT"
+ 
+ [lib]
+
+"#]])
+        .with_stderr_data(str![[r#"
+[PACKAGING] foo v0.2.0 ([ROOT]/foo)
+[PACKAGED] 5 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+[UPDATING] `dummy-registry` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] foo v0.1.0 (registry `dummy-registry`)
+[COMPARING] contents of foo v0.2.0 ([ROOT]/foo) to foo v0.1.0
+
+"#]])
+        .run();
+
+    // Only the `.crate` file is compared, not its unpacked copy.
+    let src = paths::cargo_home().join("registry/src");
+    for entry in fs::read_dir(src).unwrap() {
+        let old = entry.unwrap().path().join("foo-0.1.0/src/old.rs");
+        if old.exists() {
+            fs::remove_file(old).unwrap();
+        }
+    }
+    p.cargo("package --no-verify -Zunstable-options --diff-published")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_data(str![[r#"
+modified: Cargo.toml
+added: Cargo.toml.orig
+modified: src/lib.rs
+added: src/new.rs
+removed: src/old.rs
+...
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn diff_published_nothing_published() {
+    registry::init();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+            edition = "2015"
+            description = "foo"
+            license = "MIT"
+            documentation = "foo"
+        "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("package --no-verify --diff-published")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--diff-published` flag is unstable, and only available on the nightly channel of Cargo, but this is the `stable` channel
+See https://doc.rust-lang.org/book/appendix-07-nightly-rust.html for more information about Rust release channels.
+See https://github.com/rust-lang/cargo/issues/14702 for more information about the `--diff-published` flag.
+
+"#]])
+        .run();
+
+    p.cargo("package --no-verify -Zunstable-options --diff-published")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_data("")
+        .with_stderr_data(str![[r#"
+[PACKAGING] foo v0.1.0 ([ROOT]/foo)
+[PACKAGED] 3 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+[UPDATING] `dummy-registry` index
+[NOTE] no version of `foo` lower than 0.1.0 is published, nothing to compare with
+
+"#]])
+        .run();
+}
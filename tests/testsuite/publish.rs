@@ -6,7 +6,7 @@ use std::sync::{Arc, Mutex};
 use cargo_test_support::git::{self, repo};
 use cargo_test_support::prelude::*;
 use cargo_test_support::registry::{self, Package, RegistryBuilder, Response};
-use cargo_test_support::{basic_manifest, project, publish, str};
+use cargo_test_support::{basic_manifest, cargo_process, project, publish, str};
 use cargo_test_support::{paths, Project};
 
 const CLEAN_FOO_JSON: &str = r#"
@@ -4031,3 +4031,244 @@ fn one_unpublishable_package() {
 "#]])
         .run();
 }
+
+/// Builds a `publish.checks` command which fails if the package has no
+/// `CHANGELOG.md`, returning its path.
+fn build_changelog_check() -> String {
+    let check = project()
+        .at("changelog-check")
+        .file("Cargo.toml", &basic_manifest("changelog-check", "1.0.0"))
+        .file(
+            "src/main.rs",
+            r##"
+                use std::io::Read;
+
+                fn main() {
+                    let mut new_crate = String::new();
+                    std::io::stdin().read_to_string(&mut new_crate).unwrap();
+                    assert!(new_crate.contains(r#""name":"foo""#), "{new_crate}");
+                    assert!(new_crate.contains(r#""vers":"0.0.1""#), "{new_crate}");
+                    if !std::path::Path::new("CHANGELOG.md").exists() {
+                        eprintln!("CHANGELOG.md is missing");
+                        std::process::exit(1);
+                    }
+                }
+            "##,
+        )
+        .build();
+    check.cargo("build").run();
+    check.bin("changelog-check").display().to_string()
+}
+
+#[cargo_test]
+fn publish_checks() {
+    let registry = RegistryBuilder::new().http_api().http_index().build();
+    let check = build_changelog_check();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+                authors = []
+                license = "MIT"
+                description = "foo"
+                documentation = "foo"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .file(
+            ".cargo/config.toml",
+            &format!(
+                r#"
+                    [publish]
+                    checks = ['{}']
+                "#,
+                check.replace('\\', "\\\\")
+            ),
+        )
+        .build();
+
+    p.cargo("publish --no-verify -Zpublish-checks")
+        .masquerade_as_nightly_cargo(&["publish-checks"])
+        .replace_crates_io(registry.index_url())
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] crates.io index
+[PACKAGING] foo v0.0.1 ([ROOT]/foo)
+[PACKAGED] 4 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+[RUNNING] `[ROOT]/changelog-check/target/debug/changelog-check[EXE]`
+[ERROR] publish check `[ROOT]/changelog-check/target/debug/changelog-check[EXE]` failed for `foo v0.0.1 ([ROOT]/foo)`, defined in [ROOT]/foo/.cargo/config.toml
+
+Caused by:
+  process didn't exit successfully: `[ROOT]/changelog-check/target/debug/changelog-check[EXE]` ([EXIT_STATUS]: 1)
+  --- stderr
+  CHANGELOG.md is missing
+
+"#]])
+        .run();
+    assert!(!p.root().join("target/package/publish-checks").exists());
+
+    p.change_file("CHANGELOG.md", "# 0.0.1");
+    p.cargo("publish --no-verify -Zpublish-checks")
+        .masquerade_as_nightly_cargo(&["publish-checks"])
+        .replace_crates_io(registry.index_url())
+        .with_stderr_data(str![[r#"
+[UPDATING] crates.io index
+[PACKAGING] foo v0.0.1 ([ROOT]/foo)
+[PACKAGED] 5 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+[RUNNING] `[ROOT]/changelog-check/target/debug/changelog-check[EXE]`
+[UPLOADING] foo v0.0.1 ([ROOT]/foo)
+[UPLOADED] foo v0.0.1 to registry `crates-io`
+[NOTE] waiting for `foo v0.0.1` to be available at registry `crates-io`.
+You may press ctrl-c to skip waiting; the crate should be available shortly.
+[PUBLISHED] foo v0.0.1 at registry `crates-io`
+
+"#]])
+        .run();
+    assert!(!p.root().join("target/package/publish-checks").exists());
+}
+
+#[cargo_test]
+fn stage_and_promote() {
+    let registry = RegistryBuilder::new().http_api().http_index().build();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+                authors = []
+                license = "MIT"
+                description = "foo"
+                documentation = "foo"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("publish --no-verify --stage")
+        .replace_crates_io(registry.index_url())
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--stage` flag is unstable, and only available on the nightly channel of Cargo, but this is the `stable` channel
+See https://doc.rust-lang.org/book/appendix-07-nightly-rust.html for more information about Rust release channels.
+See https://github.com/rust-lang/cargo/issues/14703 for more information about the `--stage` flag.
+
+"#]])
+        .run();
+
+    p.cargo("publish --no-verify -Zunstable-options --stage")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .replace_crates_io(registry.index_url())
+        .with_stderr_data(str![[r#"
+[UPDATING] crates.io index
+[PACKAGING] foo v0.0.1 ([ROOT]/foo)
+[PACKAGED] 4 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+[UPLOADING] foo v0.0.1 ([ROOT]/foo)
+[STAGED] foo v0.0.1 at registry `crates-io`
+[NOTE] run `cargo publish -Zunstable-options --promote foo@0.0.1` to make it available
+
+"#]])
+        .run();
+
+    // The staged package isn't in the index yet.
+    let dependent = project()
+        .at("bar")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "bar"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                foo = "0.0.1"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+    dependent
+        .cargo("generate-lockfile")
+        .replace_crates_io(registry.index_url())
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] crates.io index
+[ERROR] no matching package named `foo` found
+location searched: registry `crates-io`
+required by package `bar v0.0.1 ([ROOT]/bar)`
+
+"#]])
+        .run();
+
+    p.cargo("publish --promote foo@0.0.1")
+        .replace_crates_io(registry.index_url())
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--promote` flag is unstable, and only available on the nightly channel of Cargo, but this is the `stable` channel
+See https://doc.rust-lang.org/book/appendix-07-nightly-rust.html for more information about Rust release channels.
+See https://github.com/rust-lang/cargo/issues/14703 for more information about the `--promote` flag.
+
+"#]])
+        .run();
+
+    p.cargo("publish -Zunstable-options --promote foo@0.0.1")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .replace_crates_io(registry.index_url())
+        .with_stderr_data(str![[r#"
+[UPDATING] crates.io index
+[PROMOTING] foo v0.0.1
+[NOTE] waiting for `foo v0.0.1` to be available at registry `crates-io`.
+You may press ctrl-c to skip waiting; the crate should be available shortly.
+[PUBLISHED] foo v0.0.1 at registry `crates-io`
+
+"#]])
+        .run();
+
+    dependent
+        .cargo("generate-lockfile")
+        .replace_crates_io(registry.index_url())
+        .with_stderr_data(str![[r#"
+[UPDATING] crates.io index
+[LOCKING] 1 package to latest compatible version
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn promote_not_staged() {
+    let registry = RegistryBuilder::new().http_api().http_index().build();
+
+    cargo_process("publish -Zunstable-options --promote foo@0.0.1")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .replace_crates_io(registry.index_url())
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] crates.io index
+[PROMOTING] foo v0.0.1
+[ERROR] failed to promote `foo@0.0.1` in the registry at http://127.0.0.1:[..]/
+
+Caused by:
+  the remote server responded with an error (status 404 Not Found): no staged version 0.0.1 of crate `foo`
+
+"#]])
+        .run();
+
+    cargo_process("publish -Zunstable-options --promote foo")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .replace_crates_io(registry.index_url())
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] `foo` must specify the version of the crate to promote, like `foo@1.0.0`
+
+"#]])
+        .run();
+}
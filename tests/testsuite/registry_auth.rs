@@ -475,6 +475,156 @@ fn login_existing_token() {
         .run();
 }
 
+#[cargo_test]
+fn rotate_key() {
+    let _registry = RegistryBuilder::new()
+        .alternative()
+        .auth_required()
+        .http_index()
+        .token(Token::rfc_key())
+        .build();
+
+    let p = make_project();
+    cargo(&p, "login --registry alternative --rotate-key")
+        .env("CARGO_REGISTRY_GLOBAL_CREDENTIAL_PROVIDERS", "cargo:paseto")
+        .with_stdin("k3.secret.9Vxr5hVlI_g_orBZN54vPz20bmB4O76wB_MVqUSuJJJqHFLwP8kdn_RY5g6J6pQG")
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+k3.public.AyLHF6x5h6XPq54WwMyt6Q4TcVpvMiJqnFEEDXUIy2P67qBbsY2SVKxo_EdZGnPonA
+
+"#]])
+        .run();
+
+    let credentials =
+        std::fs::read_to_string(cargo_test_support::paths::home().join(".cargo/credentials.toml"))
+            .unwrap();
+    assert_e2e().eq(
+        credentials,
+        str![[r#"
+[registries.alternative]
+previous-secret-keys = ["k3.secret.fNYVuMvBgOlljt9TDohnaYLblghqaHoQquVZwgR6X12cBFHZLFsaU3q7X3k1Zn36"]
+secret-key = "k3.secret.9Vxr5hVlI_g_orBZN54vPz20bmB4O76wB_MVqUSuJJJqHFLwP8kdn_RY5g6J6pQG"
+secret-key-subject = "sub"
+
+"#]],
+    );
+
+    // The registry still only accepts the old key, and asks for it.
+    cargo(&p, "build")
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[LOCKING] 1 package to latest compatible version
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v0.0.1 (registry `alternative`)
+[COMPILING] bar v0.0.1 (registry `alternative`)
+[COMPILING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    // Rotating again keeps both of the keys replaced so far.
+    cargo(&p, "login --registry alternative --rotate-key")
+        .env("CARGO_REGISTRY_GLOBAL_CREDENTIAL_PROVIDERS", "cargo:paseto")
+        .with_stdin("")
+        .run();
+    let credentials =
+        std::fs::read_to_string(cargo_test_support::paths::home().join(".cargo/credentials.toml"))
+            .unwrap();
+    assert_e2e().eq(
+        credentials,
+        str![[r#"
+[registries.alternative]
+previous-secret-keys = ["k3.secret.fNYVuMvBgOlljt9TDohnaYLblghqaHoQquVZwgR6X12cBFHZLFsaU3q7X3k1Zn36", "k3.secret.9Vxr5hVlI_g_orBZN54vPz20bmB4O76wB_MVqUSuJJJqHFLwP8kdn_RY5g6J6pQG"]
+secret-key = "k3.secret.[..]"
+secret-key-subject = "sub"
+
+"#]],
+    );
+
+    // The registry still only accepts the first key, and asks for it.
+    cargo(&p, "build")
+        .with_stderr_data(str![[r#"
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+    cargo(&p, "update")
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[LOCKING] 0 packages to latest compatible versions
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn rotate_key_without_key() {
+    let _registry = RegistryBuilder::new()
+        .alternative()
+        .no_configure_token()
+        .http_index()
+        .build();
+
+    let p = make_project();
+    cargo(&p, "login --registry alternative --rotate-key")
+        .env("CARGO_REGISTRY_GLOBAL_CREDENTIAL_PROVIDERS", "cargo:paseto")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[ERROR] credential provider `cargo:paseto --rotate-key` failed action `login`
+
+Caused by:
+  there is no secret-key for `alternative` to rotate
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn rotate_key_without_paseto() {
+    let _registry = RegistryBuilder::new()
+        .alternative()
+        .token(Token::rfc_key())
+        .http_index()
+        .build();
+
+    // `cargo:token` is tried first, so it's the one `cargo login` would use.
+    let p = make_project();
+    cargo(&p, "login --registry alternative --rotate-key")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] `--rotate-key` requires the `cargo:paseto` credential provider, but `alternative` uses `cargo:token`
+
+"#]])
+        .run();
+
+    let credentials =
+        std::fs::read_to_string(cargo_test_support::paths::home().join(".cargo/credentials.toml"))
+            .unwrap();
+    assert!(!credentials.contains("previous-secret-keys"));
+}
+
+#[cargo_test]
+fn rotate_key_requires_nightly() {
+    let _registry = RegistryBuilder::new()
+        .alternative()
+        .token(Token::rfc_key())
+        .http_index()
+        .build();
+
+    let p = make_project();
+    p.cargo("login --registry alternative --rotate-key")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--rotate-key` flag is unstable, and only available on the nightly channel of Cargo, but this is the `stable` channel
+See https://doc.rust-lang.org/book/appendix-07-nightly-rust.html for more information about Rust release channels.
+See https://github.com/rust-lang/cargo/issues/10519 for more information about the `--rotate-key` flag.
+
+"#]])
+        .run();
+}
+
 #[cargo_test]
 fn duplicate_index() {
     let server = RegistryBuilder::new()
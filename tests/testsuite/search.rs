@@ -1,6 +1,7 @@
 //! Tests for the `cargo search` command.
 
-use std::collections::HashSet;
+use std::cmp;
+use std::collections::{HashMap, HashSet};
 
 use cargo::util::cache_lock::CacheLockMode;
 use cargo_test_support::cargo_process;
@@ -89,6 +90,45 @@ fn setup() -> RegistryBuilder {
         })
 }
 
+/// Responds to searches with `total` crates named `crate-N`, paged by the
+/// `page` and `per_page` parameters, with the filters of the search as their
+/// description. If `paging` is false, the `page` parameter is ignored.
+#[must_use]
+fn paged_setup(total: usize, paging: bool) -> RegistryBuilder {
+    RegistryBuilder::new()
+        .http_api()
+        .add_responder("/api/v1/crates", move |req, _| {
+            let params: HashMap<_, _> = req.url.query_pairs().into_owned().collect();
+            let per_page: usize = params["per_page"].parse().unwrap();
+            let page: usize = match params.get("page") {
+                Some(page) if paging => page.parse().unwrap(),
+                _ => 1,
+            };
+            let filters = ["sort", "category", "keyword"]
+                .iter()
+                .filter_map(|name| params.get(*name).map(|value| format!("{name}={value}")))
+                .collect::<Vec<_>>();
+            let crates = ((page - 1) * per_page..cmp::min(page * per_page, total))
+                .map(|i| {
+                    serde_json::json!({
+                        "name": format!("crate-{i}"),
+                        "max_version": "1.0.0",
+                        "description": (!filters.is_empty()).then(|| filters.join(" ")),
+                    })
+                })
+                .collect::<Vec<_>>();
+            Response {
+                code: 200,
+                headers: vec![],
+                body: serde_json::to_vec(&serde_json::json!({
+                    "crates": crates,
+                    "meta": { "total": total },
+                }))
+                .unwrap(),
+            }
+        })
+}
+
 #[cargo_test]
 fn not_update() {
     let registry = setup().build();
@@ -225,3 +265,125 @@ fn auth_required() {
         .with_stdout_data(SEARCH_RESULTS)
         .run();
 }
+
+#[cargo_test]
+fn json() {
+    let registry = setup().build();
+
+    cargo_process("search postgres --json")
+        .replace_crates_io(registry.index_url())
+        .with_stdout_data(
+            str![[r#"
+{
+  "crates": [
+    {
+      "description": "Design by contract style assertions for Rust",
+      "documentation": null,
+      "downloads": 2,
+      "homepage": null,
+      "max_version": "0.1.1",
+      "name": "hoare",
+      "recent_downloads": null,
+      "repository": "https://github.com/nick29581/libhoare",
+      "updated_at": "2014-11-20T21:49:21Z"
+    },
+    {
+      "description": "A native, synchronous PostgreSQL client",
+      "documentation": null,
+      "downloads": 535491,
+      "homepage": null,
+      "max_version": "0.17.3",
+      "name": "postgres",
+      "recent_downloads": 88321,
+      "repository": "https://github.com/sfackler/rust-postgres",
+      "updated_at": "2020-05-01T23:17:54.335921+00:00"
+    }
+  ],
+  "total": 2
+}
+"#]]
+            .is_json(),
+        )
+        .with_stderr_data(str![[r#"
+[UPDATING] crates.io index
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn paging() {
+    let registry = paged_setup(250, true).build();
+
+    cargo_process("search --limit 120")
+        .replace_crates_io(registry.index_url())
+        .with_stdout_data(str![[r#"
+crate-0 = "1.0.0"
+...
+crate-119 = "1.0.0"
+... and 130 crates more (use --limit N to see more)
+
+"#]])
+        .run();
+
+    let output = cargo_process("search --limit 300 --json")
+        .replace_crates_io(registry.index_url())
+        .run_json();
+    let crates = output["crates"].as_array().unwrap();
+    assert_eq!(crates.len(), 250);
+    assert_eq!(crates[249]["name"], "crate-249");
+    assert_eq!(output["total"], 250);
+}
+
+#[cargo_test]
+fn max_limit() {
+    let registry = paged_setup(1500, true).build();
+
+    cargo_process("search --limit 2000")
+        .replace_crates_io(registry.index_url())
+        .with_stdout_data(str![[r#"
+crate-0 = "1.0.0"
+...
+crate-999 = "1.0.0"
+... and 500 crates more (go to https://crates.io/search?q= to see more)
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn registry_without_paging() {
+    let registry = paged_setup(250, false).build();
+
+    let output = cargo_process("search --limit 300 --json")
+        .replace_crates_io(registry.index_url())
+        .run_json();
+    let crates = output["crates"].as_array().unwrap();
+    assert_eq!(crates.len(), 100);
+    assert_eq!(crates[99]["name"], "crate-99");
+
+    cargo_process("search foo --limit 300")
+        .replace_crates_io(registry.index_url())
+        .with_stdout_data(str![[r#"
+crate-0 = "1.0.0"
+...
+crate-99 = "1.0.0"
+... and 150 crates more (go to https://crates.io/search?q=foo to see more)
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn filters() {
+    let registry = paged_setup(2, true).build();
+
+    cargo_process("search --sort downloads --category parsing --keyword json")
+        .replace_crates_io(registry.index_url())
+        .with_stdout_data(str![[r#"
+crate-0 = "1.0.0"    # sort=downloads category=parsing keyword=json
+crate-1 = "1.0.0"    # sort=downloads category=parsing keyword=json
+
+"#]])
+        .run();
+}
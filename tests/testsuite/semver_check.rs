@@ -0,0 +1,193 @@
+//! Tests for `-Zsemver-check`.
+
+use cargo_test_support::prelude::*;
+use cargo_test_support::registry::{self, Package};
+use cargo_test_support::{project, str, Project};
+
+const PUBLISHED: &str = r#"
+    pub fn add(a: u32, b: u32) -> u32 { a + b }
+    pub fn sub(a: u32, b: u32) -> u32 { a - b }
+    pub struct Point { pub x: u32, pub y: u32 }
+    pub enum Shape { Circle, Square }
+    pub mod inner { pub trait Area { fn area(&self) -> u32; } }
+"#;
+
+fn publish_foo() {
+    Package::new("foo", "1.0.0")
+        .file("src/lib.rs", PUBLISHED)
+        .publish();
+}
+
+fn foo(version: &str, lib: &str) -> Project {
+    project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "foo"
+                    version = "{version}"
+                    edition = "2015"
+                    license = "MIT"
+                    description = "foo"
+                "#
+            ),
+        )
+        .file("src/lib.rs", lib)
+        .build()
+}
+
+#[cargo_test(nightly, reason = "rustdoc JSON output is unstable")]
+fn compatible_changes() {
+    registry::init();
+    publish_foo();
+    let p = foo(
+        "1.1.0",
+        &format!(
+            "{PUBLISHED}
+            pub fn mul(a: u32, b: u32) -> u32 {{ a * b }}
+            impl Point {{ pub fn origin() -> Point {{ Point {{ x: 0, y: 0 }} }} }}
+            "
+        ),
+    );
+
+    p.cargo("package --no-verify -Zsemver-check")
+        .masquerade_as_nightly_cargo(&["semver-check"])
+        .with_stderr_data(str![[r#"
+[WARNING] manifest has no documentation, homepage or repository.
+See https://doc.rust-lang.org/cargo/reference/manifest.html#package-metadata for more info.
+[UPDATING] `dummy-registry` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] foo v1.0.0 (registry `dummy-registry`)
+[COMPARING] public API of foo v1.1.0 ([ROOT]/foo) to foo v1.0.0
+[DOCUMENTING] foo v1.1.0 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[WARNING] no edition set: defaulting to the 2015 edition while the latest is 2021
+[DOCUMENTING] foo v1.0.0
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[PACKAGING] foo v1.1.0 ([ROOT]/foo)
+[PACKAGED] 3 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+
+"#]])
+        .run();
+}
+
+#[cargo_test(nightly, reason = "rustdoc JSON output is unstable")]
+fn breaking_changes() {
+    registry::init();
+    publish_foo();
+    let p = foo(
+        "1.0.1",
+        r#"
+            pub fn add(a: u64, b: u64) -> u64 { a + b }
+            pub struct Point { pub x: u32, pub y: u32, pub z: u32 }
+            pub enum Shape { Circle, Square, Triangle }
+            pub mod inner { pub trait Area { fn area(&self) -> u32; fn volume(&self) -> u32; } }
+        "#,
+    );
+
+    p.cargo("package --no-verify -Zsemver-check")
+        .masquerade_as_nightly_cargo(&["semver-check"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[WARNING] manifest has no documentation, homepage or repository.
+See https://doc.rust-lang.org/cargo/reference/manifest.html#package-metadata for more info.
+[UPDATING] `dummy-registry` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] foo v1.0.0 (registry `dummy-registry`)
+[COMPARING] public API of foo v1.0.1 ([ROOT]/foo) to foo v1.0.0
+[DOCUMENTING] foo v1.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[WARNING] no edition set: defaulting to the 2015 edition while the latest is 2021
+[DOCUMENTING] foo v1.0.0
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[ERROR] the public API of `foo` has breaking changes since the published version 1.0.0, but version 1.0.1 is semver compatible with it:
+  changed struct `foo::Point`
+  changed enum `foo::Shape`
+  changed function `foo::add`
+  changed trait `foo::inner::Area`
+  removed function `foo::sub`
+
+Bump the version to 2.0.0 or later to publish these changes.
+
+"#]])
+        .run();
+    assert!(!p.root().join("target/package/foo-1.0.1.crate").exists());
+
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "2.0.0"
+            edition = "2015"
+            license = "MIT"
+            description = "foo"
+        "#,
+    );
+    p.cargo("package --no-verify -Zsemver-check")
+        .masquerade_as_nightly_cargo(&["semver-check"])
+        .with_stderr_data(str![[r#"
+[WARNING] manifest has no documentation, homepage or repository.
+See https://doc.rust-lang.org/cargo/reference/manifest.html#package-metadata for more info.
+[UPDATING] `dummy-registry` index
+[COMPARING] public API of foo v2.0.0 ([ROOT]/foo) to foo v1.0.0
+[DOCUMENTING] foo v2.0.0 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[WARNING] no edition set: defaulting to the 2015 edition while the latest is 2021
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[PACKAGING] foo v2.0.0 ([ROOT]/foo)
+[PACKAGED] 3 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+
+"#]])
+        .run();
+}
+
+#[cargo_test(nightly, reason = "rustdoc JSON output is unstable")]
+fn ignores_pre_releases() {
+    registry::init();
+    publish_foo();
+    Package::new("foo", "2.0.0-alpha.1")
+        .file("src/lib.rs", "pub fn add(a: u64, b: u64) -> u64 { a + b }")
+        .publish();
+    let p = foo("1.0.1", PUBLISHED);
+
+    p.cargo("package --no-verify -Zsemver-check")
+        .masquerade_as_nightly_cargo(&["semver-check"])
+        .with_stderr_data(str![[r#"
+[WARNING] manifest has no documentation, homepage or repository.
+See https://doc.rust-lang.org/cargo/reference/manifest.html#package-metadata for more info.
+[UPDATING] `dummy-registry` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] foo v1.0.0 (registry `dummy-registry`)
+[COMPARING] public API of foo v1.0.1 ([ROOT]/foo) to foo v1.0.0
+[DOCUMENTING] foo v1.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[WARNING] no edition set: defaulting to the 2015 edition while the latest is 2021
+[DOCUMENTING] foo v1.0.0
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[PACKAGING] foo v1.0.1 ([ROOT]/foo)
+[PACKAGED] 3 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn nothing_published() {
+    registry::init();
+    let p = foo("0.1.0", "pub fn add(a: u32, b: u32) -> u32 { a + b }");
+
+    p.cargo("package --no-verify -Zsemver-check")
+        .masquerade_as_nightly_cargo(&["semver-check"])
+        .with_stderr_data(str![[r#"
+[WARNING] manifest has no documentation, homepage or repository.
+See https://doc.rust-lang.org/cargo/reference/manifest.html#package-metadata for more info.
+[UPDATING] `dummy-registry` index
+[NOTE] skipping semver check, no version of `foo` lower than 0.1.0 is published
+[PACKAGING] foo v0.1.0 ([ROOT]/foo)
+[PACKAGED] 3 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+
+"#]])
+        .run();
+}
@@ -0,0 +1,162 @@
+//! Tests for the `cargo:trusted-publishing` credential provider.
+
+use cargo_test_support::prelude::*;
+use cargo_test_support::registry::{self, Response, TestRegistry};
+use cargo_test_support::{project, str, Project};
+
+fn setup(status: u32, body: &'static str) -> (Project, TestRegistry) {
+    let server = registry::RegistryBuilder::new()
+        .no_configure_token()
+        .no_configure_registry()
+        .token(registry::Token::Plaintext("short-lived".to_string()))
+        .alternative()
+        .http_api()
+        .http_index()
+        .add_responder("/trusted-publishing/tokens", move |req, _| {
+            let request =
+                serde_json::from_slice::<serde_json::Value>(req.body.as_ref().unwrap()).unwrap();
+            assert_eq!(request["jwt"], "identity-jwt");
+            Response {
+                code: status,
+                headers: vec![],
+                body: body.as_bytes().to_vec(),
+            }
+        })
+        .build();
+
+    let p = project()
+        .file(
+            ".cargo/config.toml",
+            &format!(
+                r#"
+                    [registries.alternative]
+                    index = "{}"
+                    credential-provider = [
+                        "cargo:trusted-publishing",
+                        "--endpoint",
+                        "{}trusted-publishing/tokens",
+                        "--token-env",
+                        "CI_ID_TOKEN",
+                    ]
+                "#,
+                server.index_url(),
+                server.api_url(),
+            ),
+        )
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+                description = "foo"
+                license = "MIT"
+                homepage = "https://example.com/"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+    (p, server)
+}
+
+#[cargo_test]
+fn publish() {
+    let (p, _t) = setup(
+        200,
+        r#"{"token": "short-lived", "expires_at": "2099-01-01T00:00:00Z"}"#,
+    );
+
+    p.cargo("publish --no-verify --registry alternative -Ztrusted-publishing")
+        .masquerade_as_nightly_cargo(&["trusted-publishing"])
+        .env("CI_ID_TOKEN", "identity-jwt")
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[PACKAGING] foo v0.1.0 ([ROOT]/foo)
+[PACKAGED] 3 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+[UPLOADING] foo v0.1.0 ([ROOT]/foo)
+[UPLOADED] foo v0.1.0 to registry `alternative`
+[NOTE] waiting for `foo v0.1.0` to be available at registry `alternative`.
+You may press ctrl-c to skip waiting; the crate should be available shortly.
+[PUBLISHED] foo v0.1.0 at registry `alternative`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn identity_token_from_file() {
+    let (p, _t) = setup(200, r#"{"token": "short-lived"}"#);
+    p.change_file("identity-token", "identity-jwt\n");
+    let config = p.read_file(".cargo/config.toml").replace(
+        r#""--token-env",
+                        "CI_ID_TOKEN","#,
+        r#""--token-file",
+                        "identity-token","#,
+    );
+    p.change_file(".cargo/config.toml", &config);
+
+    p.cargo("publish --no-verify --registry alternative -Ztrusted-publishing")
+        .masquerade_as_nightly_cargo(&["trusted-publishing"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[PACKAGING] foo v0.1.0 ([ROOT]/foo)
+[PACKAGED] 4 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+[UPLOADING] foo v0.1.0 ([ROOT]/foo)
+[UPLOADED] foo v0.1.0 to registry `alternative`
+[NOTE] waiting for `foo v0.1.0` to be available at registry `alternative`.
+You may press ctrl-c to skip waiting; the crate should be available shortly.
+[PUBLISHED] foo v0.1.0 at registry `alternative`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn missing_identity_token() {
+    let (p, _t) = setup(200, r#"{"token": "short-lived"}"#);
+
+    p.cargo("publish --no-verify --registry alternative -Ztrusted-publishing")
+        .masquerade_as_nightly_cargo(&["trusted-publishing"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[ERROR] no token found for `alternative`, please run `cargo login --registry alternative`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn exchange_rejected() {
+    let (p, _t) = setup(403, r#"{"errors": [{"detail": "no trusted publisher"}]}"#);
+
+    p.cargo("publish --no-verify --registry alternative -Ztrusted-publishing")
+        .masquerade_as_nightly_cargo(&["trusted-publishing"])
+        .env("CI_ID_TOKEN", "identity-jwt")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[ERROR] credential provider `cargo:trusted-publishing --endpoint http://[..]/trusted-publishing/tokens --token-env CI_ID_TOKEN` failed action `get`
+
+Caused by:
+  failed to exchange identity token at `http://[..]/trusted-publishing/tokens` (status 403): {"errors": [{"detail": "no trusted publisher"}]}
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn requires_nightly() {
+    let (p, _t) = setup(200, r#"{"token": "short-lived"}"#);
+
+    p.cargo("publish --no-verify --registry alternative")
+        .env("CI_ID_TOKEN", "identity-jwt")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[ERROR] cargo:trusted-publishing requires -Ztrusted-publishing
+
+"#]])
+        .run();
+}
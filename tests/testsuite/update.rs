@@ -1488,6 +1488,45 @@ fn precise_yanked_multiple_presence() {
     assert!(lockfile.contains("\nname = \"bar\"\nversion = \"0.1.1\""));
 }
 
+#[cargo_test]
+fn precise_yanked_with_reason() {
+    let registry = registry::RegistryBuilder::new().http_api().build();
+    Package::new("bar", "0.1.0").publish();
+    Package::new("bar", "0.1.1").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+
+                [dependencies]
+                bar = "0.1"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("generate-lockfile").run();
+    p.cargo("update --precise 0.1.0 bar").run();
+
+    p.cargo("yank bar@0.1.1 --reason miscompiles --advisory https://example.com/bar/1")
+        .replace_crates_io(registry.index_url())
+        .run();
+
+    p.cargo("update --precise 0.1.1 bar")
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[WARNING] selected package `bar@0.1.1` was yanked by the author
+[NOTE] yank reason: miscompiles
+[NOTE] yank advisory: https://example.com/bar/1
+[NOTE] if possible, try a compatible non-yanked version
+[UPDATING] bar v0.1.0 -> v0.1.1
+
+"#]])
+        .run();
+}
+
 #[cargo_test]
 fn report_behind() {
     Package::new("two-ver", "0.1.0").publish();
@@ -5,6 +5,7 @@ use std::fs;
 use cargo_test_support::prelude::*;
 use cargo_test_support::project;
 use cargo_test_support::registry;
+use cargo_test_support::registry::Package;
 use cargo_test_support::str;
 
 fn setup(name: &str, version: &str) {
@@ -212,3 +213,126 @@ fn inline_and_explicit_version() {
 "#]])
         .run();
 }
+
+#[cargo_test]
+fn reason_and_advisory() {
+    let registry = registry::RegistryBuilder::new().http_api().build();
+    Package::new("bar", "0.1.0").publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+                authors = []
+                license = "MIT"
+                description = "foo"
+
+                [dependencies]
+                bar = "0.1"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+    p.cargo("generate-lockfile").run();
+
+    p.cargo("yank bar@0.1.0 --reason")
+        .arg("data loss when writing large files")
+        .arg("--advisory=https://example.com/advisories/1")
+        .replace_crates_io(registry.index_url())
+        .with_stderr_data(str![[r#"
+[UPDATING] crates.io index
+[YANK] bar@0.1.0
+
+"#]])
+        .run();
+
+    p.cargo("package --no-verify")
+        .with_stderr_data(str![[r#"
+[WARNING] manifest has no documentation, homepage or repository.
+See https://doc.rust-lang.org/cargo/reference/manifest.html#package-metadata for more info.
+[PACKAGING] foo v0.0.1 ([ROOT]/foo)
+[UPDATING] `dummy-registry` index
+[WARNING] package `bar v0.1.0` in Cargo.lock is yanked in registry `crates-io`, consider updating to a version that is not yanked
+[NOTE] yank reason: data loss when writing large files
+[NOTE] yank advisory: https://example.com/advisories/1
+[PACKAGED] 4 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+
+"#]])
+        .run();
+
+    // Un-yanking drops the reason along with the yank.
+    p.cargo("yank --undo bar@0.1.0")
+        .replace_crates_io(registry.index_url())
+        .with_stderr_data(str![[r#"
+[UPDATING] crates.io index
+      Unyank bar@0.1.0
+
+"#]])
+        .run();
+
+    p.cargo("package --no-verify")
+        .with_stderr_data(str![[r#"
+[WARNING] manifest has no documentation, homepage or repository.
+See https://doc.rust-lang.org/cargo/reference/manifest.html#package-metadata for more info.
+[PACKAGING] foo v0.0.1 ([ROOT]/foo)
+[UPDATING] `dummy-registry` index
+[PACKAGED] 4 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn invalid_advisory() {
+    setup("foo", "0.0.1");
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                authors = []
+                license = "MIT"
+                description = "foo"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("yank foo@0.0.1 --advisory not-a-url")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] invalid advisory URL `not-a-url`: relative URL without a base
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn reason_with_undo() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &cargo_test_support::basic_manifest("foo", "0.0.1"),
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("yank --undo foo@0.0.1 --reason broken")
+        .with_status(1)
+        .with_stderr_data(str![[r#"
+[ERROR] the argument '--undo' cannot be used with '--reason <REASON>'
+
+Usage: cargo[EXE] yank --undo <CRATE>
+
+For more information, try '--help'.
+
+"#]])
+        .run();
+}